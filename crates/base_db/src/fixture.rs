@@ -111,6 +111,7 @@ impl ChangeFixture {
         let mut crate_deps = Vec::new();
         let mut default_crate_root: Option<FileId> = None;
         let mut default_cfg = CfgOptions::default();
+        let mut default_potential_cfg = CfgOptions::default();
 
         let mut file_set = FileSet::default();
         let mut current_source_root_kind = SourceRootKind::Local;
@@ -156,8 +157,9 @@ impl ChangeFixture {
                     meta.edition,
                     Some(crate_name.clone().into()),
                     version,
-                    meta.cfg.clone(),
+                    meta.rust_version,
                     meta.cfg,
+                    meta.potential_cfg,
                     meta.env,
                     Default::default(),
                     false,
@@ -174,6 +176,7 @@ impl ChangeFixture {
                 assert!(default_crate_root.is_none());
                 default_crate_root = Some(file_id);
                 default_cfg = meta.cfg;
+                default_potential_cfg = meta.potential_cfg;
             }
 
             change.change_file(file_id, Some(Arc::new(text)));
@@ -191,8 +194,9 @@ impl ChangeFixture {
                 Edition::CURRENT,
                 Some(CrateName::new("test").unwrap().into()),
                 None,
-                default_cfg.clone(),
+                None,
                 default_cfg,
+                default_potential_cfg,
                 Env::default(),
                 Default::default(),
                 false,
@@ -228,6 +232,7 @@ impl ChangeFixture {
                 Edition::Edition2021,
                 Some(CrateDisplayName::from_canonical_name("core".to_string())),
                 None,
+                None,
                 CfgOptions::default(),
                 CfgOptions::default(),
                 Env::default(),
@@ -265,6 +270,7 @@ impl ChangeFixture {
                 Edition::Edition2021,
                 Some(CrateDisplayName::from_canonical_name("proc_macros".to_string())),
                 None,
+                None,
                 CfgOptions::default(),
                 CfgOptions::default(),
                 Env::default(),
@@ -388,7 +394,10 @@ struct FileMeta {
     deps: Vec<String>,
     extern_prelude: Vec<String>,
     cfg: CfgOptions,
+    /// `cfg` plus the declared features.
+    potential_cfg: CfgOptions,
     edition: Edition,
+    rust_version: Option<String>,
     env: Env,
     introduce_new_source_root: Option<SourceRootKind>,
 }
@@ -415,6 +424,10 @@ impl From<Fixture> for FileMeta {
         let mut cfg = CfgOptions::default();
         f.cfg_atoms.iter().for_each(|it| cfg.insert_atom(it.into()));
         f.cfg_key_values.iter().for_each(|(k, v)| cfg.insert_key_value(k.into(), v.into()));
+        let mut potential_cfg = cfg.clone();
+        f.features
+            .iter()
+            .for_each(|it| potential_cfg.insert_key_value("feature".into(), it.into()));
         let deps = f.deps;
         FileMeta {
             path: f.path,
@@ -422,7 +435,9 @@ impl From<Fixture> for FileMeta {
             extern_prelude: f.extern_prelude.unwrap_or_else(|| deps.clone()),
             deps,
             cfg,
+            potential_cfg,
             edition: f.edition.as_ref().map_or(Edition::CURRENT, |v| Edition::from_str(v).unwrap()),
+            rust_version: f.rust_version,
             env: f.env.into_iter().collect(),
             introduce_new_source_root: f.introduce_new_source_root.map(|kind| match &*kind {
                 "local" => SourceRootKind::Local,
@@ -215,6 +215,8 @@ pub struct CrateData {
     pub root_file_id: FileId,
     pub edition: Edition,
     pub version: Option<String>,
+    /// The minimum supported Rust version, as declared by the `rust-version` of a Cargo package.
+    pub rust_version: Option<String>,
     /// A name used in the package's project declaration: for Cargo projects,
     /// its `[package].name` can be different for other project types or even
     /// absent (a dummy crate for the code snippet, for example).
@@ -276,6 +278,7 @@ impl CrateGraph {
         edition: Edition,
         display_name: Option<CrateDisplayName>,
         version: Option<String>,
+        rust_version: Option<String>,
         cfg_options: CfgOptions,
         potential_cfg_options: CfgOptions,
         env: Env,
@@ -287,6 +290,7 @@ impl CrateGraph {
             root_file_id: file_id,
             edition,
             version,
+            rust_version,
             display_name,
             cfg_options,
             potential_cfg_options,
@@ -595,6 +599,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -607,6 +612,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -619,6 +625,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -645,6 +652,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -657,6 +665,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -680,6 +689,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -692,6 +702,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -704,6 +715,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -727,6 +739,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
@@ -739,6 +752,7 @@ mod tests {
             Edition2018,
             None,
             None,
+            None,
             CfgOptions::default(),
             CfgOptions::default(),
             Env::default(),
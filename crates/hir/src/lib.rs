@@ -29,6 +29,7 @@ mod has_source;
 pub mod diagnostics;
 pub mod db;
 pub mod symbols;
+pub mod term_search;
 
 mod display;
 
@@ -52,7 +53,7 @@ use hir_def::{
     LocalEnumVariantId, LocalFieldId, Lookup, MacroExpander, MacroId, ModuleId, StaticId, StructId,
     TraitId, TypeAliasId, TypeOrConstParamId, TypeParamId, UnionId,
 };
-use hir_expand::{name::name, MacroCallKind};
+use hir_expand::{builtin_attr_macro::BuiltinAttrExpander, name::name, MacroCallKind};
 use hir_ty::{
     autoderef,
     consteval::{eval_const, ComputedExpr, ConstEvalCtx, ConstEvalError, ConstExt},
@@ -199,6 +200,10 @@ impl Crate {
         db.crate_graph()[self.id].version.clone()
     }
 
+    pub fn rust_version(self, db: &dyn HirDatabase) -> Option<String> {
+        db.crate_graph()[self.id].rust_version.clone()
+    }
+
     pub fn display_name(self, db: &dyn HirDatabase) -> Option<CrateDisplayName> {
         db.crate_graph()[self.id].display_name.clone()
     }
@@ -1648,6 +1653,89 @@ impl Trait {
     pub fn is_unsafe(&self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_unsafe
     }
+
+    /// Whether `dyn Trait` is a valid type.
+    ///
+    /// This approximates the object safety rules on the signatures as written, without looking at
+    /// supertraits.
+    pub fn is_object_safe(self, db: &dyn HirDatabase) -> bool {
+        if requires_sized_self(&db.generic_params(self.id.into())) {
+            return false;
+        }
+        db.trait_data(self.id).items.iter().all(|&(_, item)| match item {
+            AssocItemId::ConstId(_) => false,
+            AssocItemId::TypeAliasId(it) => db.generic_params(it.into()).tocs.is_empty(),
+            AssocItemId::FunctionId(it) => {
+                let generics = db.generic_params(it.into());
+                if requires_sized_self(&generics) {
+                    // Such methods are not callable on trait objects, so they don't matter.
+                    return true;
+                }
+                let data = db.function_data(it);
+                let mut params = data.params.iter().map(|(_, ty)| ty);
+                let receiver_ok =
+                    data.has_self_param() && params.next().map_or(false, |ty| is_self_receiver(ty));
+                receiver_ok
+                    && generics.tocs.is_empty()
+                    && params.chain(iter::once(&data.ret_type)).all(|ty| !mentions_self(ty))
+            }
+        })
+    }
+}
+
+fn requires_sized_self(generics: &hir_def::generics::GenericParams) -> bool {
+    use hir_def::{
+        generics::{TypeParamProvenance, WherePredicate, WherePredicateTypeTarget},
+        type_ref::TypeBound,
+    };
+
+    generics.where_predicates.iter().any(|pred| {
+        let (target, bound) = match pred {
+            WherePredicate::TypeBound { target, bound }
+            | WherePredicate::ForLifetime { target, bound, .. } => (target, bound),
+            WherePredicate::Lifetime { .. } => return false,
+        };
+        let is_self = match target {
+            WherePredicateTypeTarget::TypeRef(ty) => is_self_path(ty),
+            WherePredicateTypeTarget::TypeOrConstParam(id) => generics.tocs[*id]
+                .type_param()
+                .map_or(false, |it| it.provenance == TypeParamProvenance::TraitSelf),
+        };
+        let is_sized = match &**bound {
+            TypeBound::Path(path, _) => {
+                path.segments().last().map_or(false, |it| it.name.to_smol_str() == "Sized")
+            }
+            _ => false,
+        };
+        is_self && is_sized
+    })
+}
+
+fn is_self_path(ty: &TypeRef) -> bool {
+    matches!(ty, TypeRef::Path(path) if path.mod_path().as_ident() == Some(&name![Self]))
+}
+
+/// `self`, `&self`, `&mut self` and `self: Box<Self>` or similar smart pointers.
+fn is_self_receiver(ty: &TypeRef) -> bool {
+    match ty {
+        TypeRef::Reference(inner, ..) => is_self_receiver(inner),
+        TypeRef::Path(_) if is_self_path(ty) => true,
+        TypeRef::Path(path) => {
+            path.segments().last().and_then(|it| it.args_and_bindings).map_or(false, |args| {
+                match &*args.args {
+                    [hir_def::path::GenericArg::Type(inner)] => is_self_receiver(inner),
+                    _ => false,
+                }
+            })
+        }
+        _ => false,
+    }
+}
+
+fn mentions_self(ty: &TypeRef) -> bool {
+    let mut res = false;
+    ty.walk(&mut |ty| res |= is_self_path(ty));
+    res
 }
 
 impl HasVisibility for Trait {
@@ -1812,6 +1900,28 @@ impl Macro {
         matches!(self.kind(db), MacroKind::Attr)
     }
 
+    /// Whether this is the builtin `#[test]` attribute macro.
+    pub fn is_builtin_test(&self, db: &dyn HirDatabase) -> bool {
+        matches!(self.builtin_attr_expander(db), Some(it) if it.is_test())
+    }
+
+    /// Whether this is the builtin `#[bench]` attribute macro.
+    pub fn is_builtin_bench(&self, db: &dyn HirDatabase) -> bool {
+        matches!(self.builtin_attr_expander(db), Some(it) if it.is_bench())
+    }
+
+    fn builtin_attr_expander(&self, db: &dyn HirDatabase) -> Option<BuiltinAttrExpander> {
+        let expander = match self.id {
+            MacroId::Macro2Id(it) => it.lookup(db.upcast()).expander,
+            MacroId::MacroRulesId(it) => it.lookup(db.upcast()).expander,
+            MacroId::ProcMacroId(_) => return None,
+        };
+        match expander {
+            MacroExpander::BuiltInAttr(it) => Some(it),
+            _ => None,
+        }
+    }
+
     pub fn is_derive(&self, db: &dyn HirDatabase) -> bool {
         matches!(self.kind(db), MacroKind::Derive)
     }
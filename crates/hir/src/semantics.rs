@@ -22,7 +22,8 @@ use smallvec::{smallvec, SmallVec};
 use syntax::{
     algo::skip_trivia_token,
     ast::{self, HasAttrs as _, HasGenericParams, HasLoopBody},
-    match_ast, AstNode, Direction, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextSize,
+    match_ast, AstNode, Direction, SmolStr, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextRange,
+    TextSize,
 };
 
 use crate::{
@@ -180,6 +181,17 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.speculative_expand(actual_macro_call, speculative_args, token_to_map)
     }
 
+    /// Finds the `macro_rules` fragment kind and range `token_to_map` is in, for when
+    /// [`Self::speculative_expand`] fails.
+    pub fn speculative_macro_fragment(
+        &self,
+        actual_macro_call: &ast::MacroCall,
+        speculative_args: &ast::TokenTree,
+        token_to_map: SyntaxToken,
+    ) -> Option<(SmolStr, TextRange)> {
+        self.imp.speculative_macro_fragment(actual_macro_call, speculative_args, token_to_map)
+    }
+
     pub fn speculative_expand_attr_macro(
         &self,
         actual_macro_call: &ast::Item,
@@ -541,6 +553,29 @@ impl<'db> SemanticsImpl<'db> {
         )
     }
 
+    fn speculative_macro_fragment(
+        &self,
+        actual_macro_call: &ast::MacroCall,
+        speculative_args: &ast::TokenTree,
+        token_to_map: SyntaxToken,
+    ) -> Option<(SmolStr, TextRange)> {
+        let SourceAnalyzer { file_id, resolver, .. } =
+            self.analyze_no_infer(actual_macro_call.syntax());
+        let macro_call = InFile::new(file_id, actual_macro_call);
+        let krate = resolver.krate()?;
+        let macro_call_id = macro_call.as_call_id(self.db.upcast(), krate, |path| {
+            resolver
+                .resolve_path_as_macro(self.db.upcast(), &path)
+                .map(|it| macro_id_to_def_id(self.db.upcast(), it))
+        })?;
+        hir_expand::db::speculative_fragment(
+            self.db.upcast(),
+            macro_call_id,
+            speculative_args.syntax(),
+            token_to_map,
+        )
+    }
+
     fn speculative_expand_attr(
         &self,
         actual_macro_call: &ast::Item,
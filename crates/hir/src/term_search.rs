@@ -0,0 +1,393 @@
+//! Term search: synthesising expressions of a given type from what is in scope.
+//!
+//! The search works bottom-up. We start from the locals, constants and statics
+//! visible at the cursor and then, round by round, apply everything that
+//! produces a new value: functions, struct and variant constructors, field
+//! accesses and methods. An item is applied only once we know how to produce
+//! all of its arguments, so every expression we find is well-typed (modulo
+//! ownership, which we don't check).
+//!
+//! Generic functions and ADTs are skipped, as we'd need to pick the type
+//! arguments. The number of rounds and of expressions found are bounded by
+//! [`TermSearchConfig`], and so is, optionally, the time spent searching.
+//!
+//! Besides the names in scope, the search uses the items given by the caller,
+//! which `ide_db` finds with its `items_locator`.
+
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::{
+    db::HirDatabase, Adt, AsAssocItem, AssocItem, AssocItemContainer, Const, Field, Function,
+    GenericDef, HasAttrs, HasVisibility, Local, Module, ModuleDef, ScopeDef, SemanticsScope,
+    Static, Struct, StructKind, Type, Variant,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct TermSearchConfig {
+    /// Number of rounds of applying functions and constructors.
+    pub depth: usize,
+    /// Number of expressions found after which the search gives up.
+    pub max_exprs: usize,
+    /// Number of distinct expressions remembered for each type.
+    pub exprs_per_type: usize,
+    /// Time after which the search gives up. Leaving it unset makes the results independent
+    /// of the load of the machine.
+    pub budget: Option<Duration>,
+}
+
+impl Default for TermSearchConfig {
+    fn default() -> Self {
+        TermSearchConfig { depth: 3, max_exprs: 500, exprs_per_type: 3, budget: None }
+    }
+}
+
+/// An expression found by term search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Local(Local),
+    Const(Const),
+    Static(Static),
+    /// A free or associated function without `self`.
+    Function {
+        func: Function,
+        params: Vec<Expr>,
+    },
+    Method {
+        func: Function,
+        target: Box<Expr>,
+        params: Vec<Expr>,
+    },
+    Variant {
+        variant: Variant,
+        params: Vec<Expr>,
+    },
+    Struct {
+        strukt: Struct,
+        params: Vec<Expr>,
+    },
+    Field {
+        expr: Box<Expr>,
+        field: Field,
+    },
+}
+
+impl Expr {
+    pub fn ty(&self, db: &dyn HirDatabase) -> Type {
+        match self {
+            Expr::Local(it) => it.ty(db),
+            Expr::Const(it) => it.ty(db),
+            Expr::Static(it) => it.ty(db),
+            Expr::Function { func, .. } | Expr::Method { func, .. } => func.ret_type(db),
+            Expr::Variant { variant, .. } => variant.parent_enum(db).ty(db),
+            Expr::Struct { strukt, .. } => strukt.ty(db),
+            Expr::Field { field, .. } => field.ty(db),
+        }
+    }
+
+    /// Renders the expression as source code valid in `module`.
+    ///
+    /// Returns `None` if some item used by the expression can't be named from there.
+    pub fn gen_source_code(&self, db: &dyn HirDatabase, module: Module) -> Option<String> {
+        let path =
+            |def: ModuleDef| module.find_use_path(db.upcast(), def).map(|path| path.to_string());
+        let args = |params: &[Expr]| -> Option<String> {
+            let params: Vec<_> =
+                params.iter().map(|it| it.gen_source_code(db, module)).collect::<Option<_>>()?;
+            Some(params.join(", "))
+        };
+        let res = match self {
+            Expr::Local(it) => it.name(db).to_string(),
+            Expr::Const(it) => path(ModuleDef::Const(*it))?,
+            Expr::Static(it) => path(ModuleDef::Static(*it))?,
+            Expr::Function { func, params } => {
+                let callee = match func.as_assoc_item(db).map(|it| it.container(db)) {
+                    Some(AssocItemContainer::Impl(imp)) => {
+                        let adt = imp.self_ty(db).as_adt()?;
+                        format!("{}::{}", path(ModuleDef::Adt(adt))?, func.name(db))
+                    }
+                    Some(AssocItemContainer::Trait(_)) => return None,
+                    None => path(ModuleDef::Function(*func))?,
+                };
+                format!("{}({})", callee, args(params)?)
+            }
+            Expr::Method { func, target, params } => {
+                let target = target.gen_source_code(db, module)?;
+                format!("{}.{}({})", target, func.name(db), args(params)?)
+            }
+            Expr::Variant { variant, params } => {
+                let path = path(ModuleDef::Variant(*variant))?;
+                constructor(db, path, variant.kind(db), &variant.fields(db), params, module)?
+            }
+            Expr::Struct { strukt, params } => {
+                let path = path(ModuleDef::Adt(Adt::Struct(*strukt)))?;
+                constructor(db, path, strukt.kind(db), &strukt.fields(db), params, module)?
+            }
+            Expr::Field { expr, field } => {
+                format!("{}.{}", expr.gen_source_code(db, module)?, field.name(db))
+            }
+        };
+        Some(res)
+    }
+}
+
+fn constructor(
+    db: &dyn HirDatabase,
+    path: String,
+    kind: StructKind,
+    fields: &[Field],
+    params: &[Expr],
+    module: Module,
+) -> Option<String> {
+    let params: Vec<_> =
+        params.iter().map(|it| it.gen_source_code(db, module)).collect::<Option<_>>()?;
+    let res = match kind {
+        StructKind::Unit => path,
+        StructKind::Tuple => format!("{}({})", path, params.join(", ")),
+        StructKind::Record => format!(
+            "{} {{ {} }}",
+            path,
+            fields.iter().zip(params).format_with(", ", |(field, param), f| f(&format_args!(
+                "{}: {}",
+                field.name(db),
+                param
+            )))
+        ),
+    };
+    Some(res)
+}
+
+/// Expressions found so far, grouped by type.
+#[derive(Default)]
+struct LookupTable {
+    data: Vec<(Type, Vec<Expr>)>,
+}
+
+impl LookupTable {
+    fn find(&self, db: &dyn HirDatabase, ty: &Type) -> Option<&Expr> {
+        self.data
+            .iter()
+            .find(|(it, _)| it == ty || it.could_unify_with(db, ty))
+            .and_then(|(_, exprs)| exprs.first())
+    }
+
+    fn find_all(&self, db: &dyn HirDatabase, ty: &Type) -> Vec<Expr> {
+        self.data
+            .iter()
+            .filter(|(it, _)| it == ty || it.could_unify_with(db, ty))
+            .flat_map(|(_, exprs)| exprs.iter().cloned())
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.data.iter().map(|(_, exprs)| exprs.len()).sum()
+    }
+
+    /// Returns `true` if the expression is new.
+    fn insert(&mut self, ty: Type, expr: Expr, limit: usize) -> bool {
+        if ty.is_unknown() {
+            return false;
+        }
+        match self.data.iter_mut().find(|(it, _)| *it == ty) {
+            Some((_, exprs)) => {
+                if exprs.len() >= limit || exprs.contains(&expr) {
+                    return false;
+                }
+                exprs.push(expr);
+            }
+            None => self.data.push((ty, vec![expr])),
+        }
+        true
+    }
+}
+
+/// Finds expressions of type `goal` that can be written at `scope`, from the names in scope and
+/// the `items` visible from it.
+///
+/// Simpler expressions come first.
+pub fn term_search(
+    scope: &SemanticsScope,
+    goal: &Type,
+    items: &[ModuleDef],
+    config: &TermSearchConfig,
+) -> Vec<Expr> {
+    let _p = profile::span("term_search");
+    let deadline = config.budget.map(|it| Instant::now() + it);
+    let db = scope.db;
+    let module = match scope.module() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let current_fn = scope.resolver().body_owner().and_then(|owner| match owner {
+        hir_def::DefWithBodyId::FunctionId(it) => Some(Function::from(it)),
+        _ => None,
+    });
+
+    let mut lookup = LookupTable::default();
+    let mut adts = Vec::new();
+    let mut funcs = Vec::new();
+    scope.process_all_names(&mut |_, def| match def {
+        ScopeDef::Local(it) => {
+            lookup.insert(it.ty(db), Expr::Local(it), config.exprs_per_type);
+        }
+        ScopeDef::ModuleDef(ModuleDef::Const(it)) => {
+            lookup.insert(it.ty(db), Expr::Const(it), config.exprs_per_type);
+        }
+        ScopeDef::ModuleDef(ModuleDef::Static(it)) => {
+            lookup.insert(it.ty(db), Expr::Static(it), config.exprs_per_type);
+        }
+        ScopeDef::ModuleDef(ModuleDef::Function(it)) => funcs.push(it),
+        ScopeDef::ModuleDef(ModuleDef::Adt(it)) => adts.push(it),
+        _ => (),
+    });
+    for &item in items {
+        match item {
+            ModuleDef::Const(it) if it.is_visible_from(db, module) => {
+                lookup.insert(it.ty(db), Expr::Const(it), config.exprs_per_type);
+            }
+            ModuleDef::Static(it) if it.is_visible_from(db, module) => {
+                lookup.insert(it.ty(db), Expr::Static(it), config.exprs_per_type);
+            }
+            ModuleDef::Function(it) if it.is_visible_from(db, module) && !funcs.contains(&it) => {
+                funcs.push(it)
+            }
+            ModuleDef::Adt(it) if it.is_visible_from(db, module) && !adts.contains(&it) => {
+                adts.push(it)
+            }
+            _ => (),
+        }
+    }
+    // The goal type itself may not be in scope by name.
+    if let Some(adt) = goal.as_adt() {
+        if !adts.contains(&adt) {
+            adts.push(adt);
+        }
+    }
+    adts.retain(|adt| {
+        !adt.has_non_default_type_params(db) && !adt.attrs(db).by_key("non_exhaustive").exists()
+    });
+    for adt in &adts {
+        adt.ty(db).iterate_assoc_items(db, module.krate(), |item| {
+            if let AssocItem::Function(func) = item {
+                if func.self_param(db).is_none() && func.is_visible_from(db, module) {
+                    funcs.push(func);
+                }
+            }
+            None::<()>
+        });
+    }
+    funcs.retain(|&func| {
+        Some(func) != current_fn
+            && !func.is_unsafe(db)
+            && GenericDef::from(func).type_params(db).is_empty()
+    });
+
+    // Bounds each round, the expressions found so far count towards the limit too.
+    let exhausted = |lookup: &LookupTable, new_exprs: &[Expr]| {
+        lookup.len() + new_exprs.len() >= config.max_exprs
+            || deadline.map_or(false, |it| Instant::now() >= it)
+    };
+    for _ in 0..config.depth {
+        let mut new_exprs = Vec::new();
+
+        for adt in &adts {
+            if exhausted(&lookup, &new_exprs) {
+                break;
+            }
+            match *adt {
+                Adt::Struct(strukt) => {
+                    let fields = strukt.fields(db);
+                    if fields.iter().all(|it| it.is_visible_from(db, module)) {
+                        if let Some(params) =
+                            find_params(db, &lookup, fields.iter().map(|it| it.ty(db)))
+                        {
+                            new_exprs.push(Expr::Struct { strukt, params });
+                        }
+                    }
+                }
+                Adt::Enum(enum_) => {
+                    for variant in enum_.variants(db) {
+                        let fields = variant.fields(db);
+                        if let Some(params) =
+                            find_params(db, &lookup, fields.iter().map(|it| it.ty(db)))
+                        {
+                            new_exprs.push(Expr::Variant { variant, params });
+                        }
+                    }
+                }
+                Adt::Union(_) => (),
+            }
+        }
+
+        for &func in &funcs {
+            if exhausted(&lookup, &new_exprs) {
+                break;
+            }
+            let params = func.assoc_fn_params(db);
+            if let Some(params) = find_params(db, &lookup, params.iter().map(|it| it.ty().clone()))
+            {
+                new_exprs.push(Expr::Function { func, params });
+            }
+        }
+
+        for (ty, exprs) in &lookup.data {
+            if exhausted(&lookup, &new_exprs) {
+                break;
+            }
+            let target = match exprs.first() {
+                Some(it) => it,
+                None => continue,
+            };
+            if let Some(Adt::Struct(strukt)) = ty.as_adt() {
+                for field in strukt.fields(db) {
+                    if field.is_visible_from(db, module) {
+                        new_exprs.push(Expr::Field { expr: Box::new(target.clone()), field });
+                    }
+                }
+            }
+            if ty.as_adt().map_or(true, |adt| adt.has_non_default_type_params(db)) {
+                continue;
+            }
+            ty.iterate_assoc_items(db, module.krate(), |item| {
+                let func = match item {
+                    AssocItem::Function(it) => it,
+                    _ => return None::<()>,
+                };
+                if func.self_param(db).is_none()
+                    || Some(func) == current_fn
+                    || func.is_unsafe(db)
+                    || !func.is_visible_from(db, module)
+                    || !GenericDef::from(func).type_params(db).is_empty()
+                {
+                    return None;
+                }
+                let params = func.assoc_fn_params(db);
+                if let Some(params) =
+                    find_params(db, &lookup, params.iter().skip(1).map(|it| it.ty().clone()))
+                {
+                    new_exprs.push(Expr::Method { func, target: Box::new(target.clone()), params });
+                }
+                None
+            });
+        }
+
+        let mut changed = false;
+        for expr in new_exprs {
+            changed |= lookup.insert(expr.ty(db), expr, config.exprs_per_type);
+        }
+        if !changed || exhausted(&lookup, &[]) {
+            break;
+        }
+    }
+
+    lookup.find_all(db, goal)
+}
+
+fn find_params(
+    db: &dyn HirDatabase,
+    lookup: &LookupTable,
+    tys: impl Iterator<Item = Type>,
+) -> Option<Vec<Expr>> {
+    tys.map(|ty| lookup.find(db, &ty).cloned()).collect()
+}
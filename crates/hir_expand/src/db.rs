@@ -10,7 +10,7 @@ use rustc_hash::FxHashSet;
 use syntax::{
     algo::diff,
     ast::{self, HasAttrs, HasDocComments},
-    AstNode, GreenNode, Parse, SyntaxNode, SyntaxToken, T,
+    AstNode, GreenNode, Parse, SmolStr, SyntaxNode, SyntaxToken, TextRange, T,
 };
 
 use crate::{
@@ -227,6 +227,33 @@ pub fn expand_speculative(
     Some((node.syntax_node(), token))
 }
 
+/// Finds the kind and range of the `macro_rules` fragment `token_to_map` is matched by when the
+/// macro is called with `speculative_args`. Unlike [`expand_speculative`], this doesn't need the
+/// arguments to match a rule completely, so completion can fall back to it.
+pub fn speculative_fragment(
+    db: &dyn AstDatabase,
+    actual_macro_call: MacroCallId,
+    speculative_args: &SyntaxNode,
+    token_to_map: SyntaxToken,
+) -> Option<(SmolStr, TextRange)> {
+    let loc = db.lookup_intern_macro_call(actual_macro_call);
+    if !matches!(loc.kind, MacroCallKind::FnLike { .. }) {
+        return None;
+    }
+    let macro_def = db.macro_def(loc.def).ok()?;
+    let mac = match &*macro_def {
+        TokenExpander::DeclarativeMacro { mac, .. } => mac,
+        _ => return None,
+    };
+    let args_start = speculative_args.text_range().start();
+    let (mut tt, tmap) = syntax_node_to_token_tree(speculative_args);
+    tt.delimiter = None;
+    let token_id = tmap.token_by_range(token_to_map.text_range().checked_sub(args_start)?)?;
+    let fragment = mac.fragment_at(&tt, token_id)?;
+    let range = tmap.covering_range(&fragment.tokens)?;
+    Some((fragment.kind, range + args_start))
+}
+
 fn ast_id_map(db: &dyn AstDatabase, file_id: HirFileId) -> Arc<AstIdMap> {
     let map = db.parse_or_expand(file_id).map(|it| AstIdMap::from_source(&it)).unwrap_or_default();
     Arc::new(map)
@@ -11,7 +11,7 @@ use crate::{
     fn_references::find_all_methods,
     goto_implementation::goto_implementation,
     references::find_all_refs,
-    runnables::{runnables, Runnable},
+    runnables::{runnables, Runnable, RunnablesConfig},
     NavigationTarget, RunnableKind,
 };
 
@@ -41,6 +41,7 @@ pub struct AnnotationConfig {
     pub annotate_references: bool,
     pub annotate_method_references: bool,
     pub annotate_enum_variant_references: bool,
+    pub runnables: RunnablesConfig,
 }
 
 pub(crate) fn annotations(
@@ -51,7 +52,7 @@ pub(crate) fn annotations(
     let mut annotations = Vec::default();
 
     if config.annotate_runnables {
-        for runnable in runnables(db, file_id) {
+        for runnable in runnables(db, &config.runnables, file_id) {
             if should_skip_runnable(&runnable.kind, config.binary_target) {
                 continue;
             }
@@ -208,6 +209,7 @@ mod tests {
                     annotate_references: true,
                     annotate_method_references: true,
                     annotate_enum_variant_references: true,
+                    runnables: Default::default(),
                 },
                 file_id,
             )
@@ -0,0 +1,266 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::FilePosition,
+    change_signature::{self, SignatureChange},
+    defs::{Definition, IdentClass},
+    helpers::pick_best_token,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxKind};
+
+// Feature: Change Signature
+//
+// Reorders, adds, renames and retypes the parameters of a function. The functions implementing
+// or implemented by a trait method are changed alongside it, and the argument lists of all calls
+// are rewritten to match, passing a default expression for added parameters.
+//
+// The new signature is sent by the client with the `experimental/changeSignature` request. The
+// `change_signature` assist uses the same machinery to move parameters around.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    change: &SignatureChange,
+) -> Result<SourceChange, String> {
+    let sema = Semantics::new(db);
+    let func = function_at(&sema, position).ok_or("no function at the cursor")?;
+    change_signature::change_signature(&sema, func, change)
+}
+
+/// The function named or called by the token at `position`, or otherwise the one around it.
+fn function_at(sema: &Semantics<RootDatabase>, position: FilePosition) -> Option<hir::Function> {
+    let file = sema.parse(position.file_id);
+    let token =
+        pick_best_token(file.syntax().token_at_offset(position.offset), |kind| match kind {
+            SyntaxKind::IDENT => 1,
+            _ => 0,
+        })?;
+    let named = IdentClass::classify_token(sema, &token)
+        .into_iter()
+        .flat_map(IdentClass::definitions)
+        .find_map(|def| match def {
+            Definition::Function(it) => Some(it),
+            _ => None,
+        });
+    named.or_else(|| sema.to_def(&token.parent()?.ancestors().find_map(ast::Fn::cast)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::change_signature::{ParamChange, SignatureChange};
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    fn existing(index: usize) -> ParamChange {
+        ParamChange::Existing { index, name: None, ty: None }
+    }
+
+    fn check(params: Vec<ParamChange>, ra_fixture_before: &str, ra_fixture_after: &str) {
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let change = SignatureChange { params };
+        let source_change = analysis.change_signature(position, change).unwrap().unwrap();
+        let mut text = analysis.file_text(position.file_id).unwrap().to_string();
+        source_change.get_source_edit(position.file_id).unwrap().apply(&mut text);
+        assert_eq_text!(&trim_indent(ra_fixture_after), &text);
+    }
+
+    fn check_error(params: Vec<ParamChange>, ra_fixture: &str, expected: &str) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let change = SignatureChange { params };
+        let err = analysis.change_signature(position, change).unwrap().unwrap_err();
+        assert_eq!(err, expected);
+    }
+
+    #[test]
+    fn reorder_and_add() {
+        check(
+            vec![
+                existing(1),
+                existing(0),
+                ParamChange::Added {
+                    name: "verbose".to_owned(),
+                    ty: "bool".to_owned(),
+                    default: "false".to_owned(),
+                },
+            ],
+            r#"
+fn foo$0(a: i32, b: &str) {}
+fn main() {
+    foo(1 + 1, "x");
+}
+"#,
+            r#"
+fn foo(b: &str, a: i32, verbose: bool) {}
+fn main() {
+    foo("x", 1 + 1, false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_param() {
+        check(
+            vec![existing(1)],
+            r#"
+fn foo(a: i32, b: u8) {}
+fn main() {
+    fo$0o(1, 2);
+}
+"#,
+            r#"
+fn foo(b: u8) {}
+fn main() {
+    foo(2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rename_and_retype() {
+        check(
+            vec![ParamChange::Existing {
+                index: 0,
+                name: Some("count".to_owned()),
+                ty: Some("u64".to_owned()),
+            }],
+            r#"
+struct S { n: u32 }
+fn foo(#[allow(unused)] n$0: u32) -> S {
+    let _ = n + 1;
+    S { n }
+}
+"#,
+            r#"
+struct S { n: u32 }
+fn foo(#[allow(unused)] count: u64) -> S {
+    let _ = count + 1;
+    S { n: count }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_and_recursive_calls() {
+        check(
+            vec![
+                ParamChange::Existing { index: 1, name: Some("y".to_owned()), ty: None },
+                existing(0),
+            ],
+            r#"
+fn foo(a: u32, b: u32) -> u32 {
+    $0if b == 0 { a } else { foo(foo(a, b - 1), b - 1) }
+}
+"#,
+            r#"
+fn foo(y: u32, a: u32) -> u32 {
+    if y == 0 { a } else { foo(y - 1, foo(y - 1, a)) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_method_family() {
+        check(
+            vec![existing(1), existing(0)],
+            r#"
+trait Shape {
+    fn scale(&mut self, x: f32, y: f32);
+}
+struct Rect;
+impl Shape for Rect {
+    fn scale$0(&mut self, w: f32, h: f32) {}
+}
+fn resize<S: Shape>(s: &mut S, r: &mut Rect) {
+    s.scale(1.0, 2.0);
+    r.scale(3.0, 4.0);
+    Rect::scale(r, 5.0, 6.0);
+}
+"#,
+            r#"
+trait Shape {
+    fn scale(&mut self, y: f32, x: f32);
+}
+struct Rect;
+impl Shape for Rect {
+    fn scale(&mut self, h: f32, w: f32) {}
+}
+fn resize<S: Shape>(s: &mut S, r: &mut Rect) {
+    s.scale(2.0, 1.0);
+    r.scale(4.0, 3.0);
+    Rect::scale(r, 6.0, 5.0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_changes() {
+        check_error(
+            vec![existing(0), existing(0)],
+            "fn foo$0(a: u8) {}",
+            "parameter 0 is used more than once",
+        );
+        check_error(vec![existing(1)], "fn foo$0(a: u8) {}", "there is no parameter 1");
+        check_error(vec![], "struct S$0;", "no function at the cursor");
+        check_error(
+            vec![existing(1)],
+            "fn foo$0(a: u8, b: u8) -> u8 { a + b }",
+            "parameter `a` is still used in `foo`",
+        );
+    }
+
+    #[test]
+    fn usages_which_cant_be_updated() {
+        check_error(
+            vec![existing(1), existing(0)],
+            r#"
+macro_rules! call { ($e:expr) => { $e }; }
+fn foo$0(a: u8, b: u8) {}
+fn main() {
+    call!(foo(1, 2));
+    [1].iter().map(|_| foo);
+    foo(1, 2);
+}
+"#,
+            "can't update the usages which aren't calls or are in macro calls, at \
+             /main.rs:4:11, /main.rs:5:24",
+        );
+    }
+
+    #[test]
+    fn keeps_multi_line_layout() {
+        check(
+            vec![existing(1), existing(0)],
+            r#"
+fn foo$0(
+    first: u32,
+    second: u32,
+) {}
+fn main() {
+    foo(
+        1,
+        2
+    );
+}
+"#,
+            r#"
+fn foo(
+    second: u32,
+    first: u32,
+) {}
+fn main() {
+    foo(
+        2,
+        1
+    );
+}
+"#,
+        );
+    }
+}
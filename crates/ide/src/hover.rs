@@ -20,13 +20,14 @@ use syntax::{ast, match_ast, AstNode, SyntaxKind::*, SyntaxNode, SyntaxToken, T}
 use crate::{
     doc_links::token_as_doc_comment,
     markup::Markup,
-    runnables::{runnable_fn, runnable_mod},
+    runnables::{runnable_fn, runnable_mod, RunnablesConfig},
     FileId, FilePosition, NavigationTarget, RangeInfo, Runnable, TryToNav,
 };
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HoverConfig {
     pub links_in_hover: bool,
     pub documentation: Option<HoverDocFormat>,
+    pub runnables: RunnablesConfig,
 }
 
 impl HoverConfig {
@@ -184,7 +185,7 @@ pub(crate) fn hover_for_definition(
             res.actions.push(action);
         }
 
-        if let Some(action) = runnable_action(sema, definition, file_id) {
+        if let Some(action) = runnable_action(sema, &config.runnables, definition, file_id) {
             res.actions.push(action);
         }
 
@@ -292,11 +293,12 @@ fn show_fn_references_action(db: &RootDatabase, def: Definition) -> Option<Hover
 
 fn runnable_action(
     sema: &hir::Semantics<RootDatabase>,
+    config: &RunnablesConfig,
     def: Definition,
     file_id: FileId,
 ) -> Option<HoverAction> {
     match def {
-        Definition::Module(it) => runnable_mod(sema, it).map(HoverAction::Runnable),
+        Definition::Module(it) => runnable_mod(sema, config, it).map(HoverAction::Runnable),
         Definition::Function(func) => {
             let src = func.source(sema.db)?;
             if src.file_id != file_id.into() {
@@ -305,7 +307,7 @@ fn runnable_action(
                 return None;
             }
 
-            runnable_fn(sema, func).map(HoverAction::Runnable)
+            runnable_fn(sema, config, func).map(HoverAction::Runnable)
         }
         _ => None,
     }
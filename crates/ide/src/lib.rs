@@ -25,6 +25,7 @@ mod navigation_target;
 mod annotations;
 mod call_hierarchy;
 mod call_info;
+mod change_signature;
 mod doc_links;
 mod highlight_related;
 mod expand_macro;
@@ -84,18 +85,22 @@ pub use crate::{
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
     join_lines::JoinLinesConfig,
     markup::Markup,
-    moniker::{MonikerKind, MonikerResult, PackageInformation},
+    moniker::{
+        MonikerDescriptor, MonikerDescriptorKind, MonikerIdentifier, MonikerKind, MonikerResult,
+        PackageInformation,
+    },
     move_item::Direction,
     navigation_target::NavigationTarget,
     prime_caches::ParallelPrimeCachesProgress,
     references::ReferenceSearchResult,
     rename::RenameError,
-    runnables::{Runnable, RunnableKind, TestId},
+    runnables::{Runnable, RunnableKind, RunnablesConfig, TestId},
     static_index::{StaticIndex, StaticIndexedFile, TokenId, TokenStaticData},
     syntax_highlighting::{
         tags::{Highlight, HlMod, HlMods, HlOperator, HlPunct, HlTag},
         HlRange,
     },
+    typing::TypingConfig,
 };
 pub use hir::{Documentation, Semantics};
 pub use ide_assists::{
@@ -103,13 +108,14 @@ pub use ide_assists::{
 };
 pub use ide_completion::{
     CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit, Snippet,
-    SnippetScope,
+    SnippetScope, TraitImplBody,
 };
 pub use ide_db::{
     base_db::{
         Cancelled, Change, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange,
         SourceRoot, SourceRootId,
     },
+    change_signature::{ParamChange, SignatureChange},
     label::Label,
     line_index::{LineCol, LineColUtf16, LineIndex},
     search::{ReferenceCategory, SearchScope},
@@ -227,6 +233,7 @@ impl Analysis {
             Edition::CURRENT,
             None,
             None,
+            None,
             cfg_options.clone(),
             cfg_options,
             Env::default(),
@@ -338,6 +345,7 @@ impl Analysis {
     /// automatically.
     pub fn on_char_typed(
         &self,
+        config: &TypingConfig,
         position: FilePosition,
         char_typed: char,
     ) -> Cancellable<Option<SourceChange>> {
@@ -345,7 +353,7 @@ impl Analysis {
         if !typing::TRIGGER_CHARS.contains(char_typed) {
             return Ok(None);
         }
-        self.with_db(|db| typing::on_char_typed(db, position, char_typed))
+        self.with_db(|db| typing::on_char_typed(db, config, position, char_typed))
     }
 
     /// Returns a tree representation of symbols in the file. Useful to draw a
@@ -494,17 +502,22 @@ impl Analysis {
     }
 
     /// Returns the set of possible targets to run for the current file.
-    pub fn runnables(&self, file_id: FileId) -> Cancellable<Vec<Runnable>> {
-        self.with_db(|db| runnables::runnables(db, file_id))
+    pub fn runnables(
+        &self,
+        config: &RunnablesConfig,
+        file_id: FileId,
+    ) -> Cancellable<Vec<Runnable>> {
+        self.with_db(|db| runnables::runnables(db, config, file_id))
     }
 
     /// Returns the set of tests for the given file position.
     pub fn related_tests(
         &self,
+        config: &RunnablesConfig,
         position: FilePosition,
         search_scope: Option<SearchScope>,
     ) -> Cancellable<Vec<Runnable>> {
-        self.with_db(|db| runnables::related_tests(db, position, search_scope))
+        self.with_db(|db| runnables::related_tests(db, config, position, search_scope))
     }
 
     /// Computes syntax highlighting for the given file
@@ -625,6 +638,15 @@ impl Analysis {
         self.with_db(|db| rename::will_rename_file(db, file_id, new_name_stem))
     }
 
+    /// Changes the parameters of the function at `position` and updates all of its callers.
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        change: SignatureChange,
+    ) -> Cancellable<Result<SourceChange, String>> {
+        self.with_db(|db| change_signature::change_signature(db, position, &change))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
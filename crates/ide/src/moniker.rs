@@ -1,7 +1,20 @@
 //! This module generates [moniker](https://microsoft.github.io/language-server-protocol/specifications/lsif/0.6.0/specification/#exportsImports)
 //! for LSIF and LSP.
+//!
+//! A moniker identifies a definition by its crate and a list of descriptors:
+//!
+//! * modules are namespaces, items in block expressions are nested under the enclosing item,
+//! * associated items are nested under their trait, or under the self type and trait of their
+//!   impl (trait generic arguments included, e.g. `S::From<u8>::from`),
+//! * enum variants and fields are nested under their enum and variant,
+//! * `#[macro_export]`ed macros live at the crate root,
+//! * locals are nested under their enclosing body, shadowed bindings get an `@N` suffix.
 
-use hir::{db::DefDatabase, AsAssocItem, AssocItemContainer, Crate, Name, Semantics};
+use either::Either;
+use hir::{
+    db::DefDatabase, AsAssocItem, AssocItemContainer, Crate, DefWithBody, HasAttrs, HasSource,
+    HirDisplay, Impl, Local, Module, ModuleSource, Semantics, VariantDef,
+};
 use ide_db::{
     base_db::{CrateOrigin, FileId, FileLoader, FilePosition},
     defs::{Definition, IdentClass},
@@ -9,21 +22,48 @@ use ide_db::{
     RootDatabase,
 };
 use itertools::Itertools;
-use syntax::{AstNode, SyntaxKind::*, T};
+use syntax::{
+    ast::{self, HasName},
+    match_ast, AstNode,
+    SyntaxKind::*,
+    T,
+};
 
 use crate::{doc_links::token_as_doc_comment, RangeInfo};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MonikerDescriptorKind {
+    Namespace,
+    Type,
+    Term,
+    Method,
+    TypeParameter,
+    Parameter,
+    Macro,
+    Meta,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonikerDescriptor {
+    pub name: String,
+    pub desc: MonikerDescriptorKind,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MonikerIdentifier {
-    crate_name: String,
-    path: Vec<Name>,
+    pub crate_name: String,
+    pub description: Vec<MonikerDescriptor>,
 }
 
 impl ToString for MonikerIdentifier {
     fn to_string(&self) -> String {
         match self {
-            MonikerIdentifier { path, crate_name } => {
-                format!("{}::{}", crate_name, path.iter().map(|x| x.to_string()).join("::"))
+            MonikerIdentifier { description, crate_name } => {
+                format!(
+                    "{}::{}",
+                    crate_name,
+                    description.iter().map(|x| x.name.to_string()).join("::")
+                )
             }
         }
     }
@@ -33,6 +73,8 @@ impl ToString for MonikerIdentifier {
 pub enum MonikerKind {
     Import,
     Export,
+    /// A crate-local symbol, like a local variable, which can't be referred to from other crates.
+    Local,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -45,7 +87,7 @@ pub struct MonikerResult {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PackageInformation {
     pub name: String,
-    pub repo: String,
+    pub repo: Option<String>,
     pub version: String,
 }
 
@@ -105,13 +147,24 @@ pub(crate) fn def_to_moniker(
     def: Definition,
     from_crate: Crate,
 ) -> Option<MonikerResult> {
-    if matches!(def, Definition::GenericParam(_) | Definition::SelfType(_) | Definition::Local(_)) {
+    if let Definition::Local(local) = def {
+        return local_to_moniker(db, local, from_crate);
+    }
+    if matches!(def, Definition::GenericParam(_) | Definition::SelfType(_)) {
         return None;
     }
     let module = def.module(db)?;
     let krate = module.krate();
-    let mut path = vec![];
-    path.extend(module.path_to_root(db).into_iter().filter_map(|x| x.name(db)));
+    let mut description = vec![];
+
+    // `#[macro_export]`ed macros live at the crate root, regardless of where they are defined.
+    let is_exported_macro = match def {
+        Definition::Macro(it) => it.attrs(db).by_key("macro_export").exists(),
+        _ => false,
+    };
+    if !is_exported_macro {
+        description.extend(module_descriptors(db, module));
+    }
 
     // Handle associated items within a trait
     if let Some(assoc) = def.as_assoc_item(db) {
@@ -120,62 +173,196 @@ pub(crate) fn def_to_moniker(
             AssocItemContainer::Trait(trait_) => {
                 // Because different traits can have functions with the same name,
                 // we have to include the trait name as part of the moniker for uniqueness.
-                path.push(trait_.name(db));
-            }
-            AssocItemContainer::Impl(impl_) => {
-                // Because a struct can implement multiple traits, for implementations
-                // we add both the struct name and the trait name to the path
-                if let Some(adt) = impl_.self_ty(db).as_adt() {
-                    path.push(adt.name(db));
-                }
-
-                if let Some(trait_) = impl_.trait_(db) {
-                    path.push(trait_.name(db));
-                }
+                description.push(MonikerDescriptor {
+                    name: trait_.name(db).to_string(),
+                    desc: MonikerDescriptorKind::Type,
+                });
             }
+            AssocItemContainer::Impl(impl_) => description.extend(impl_descriptors(db, impl_)),
         }
     }
 
-    if let Definition::Field(it) = def {
-        path.push(it.parent_def(db).name(db));
+    match def {
+        Definition::Field(it) => {
+            if let VariantDef::Variant(variant) = it.parent_def(db) {
+                description.push(MonikerDescriptor {
+                    name: variant.parent_enum(db).name(db).to_string(),
+                    desc: MonikerDescriptorKind::Type,
+                });
+            }
+            description.push(MonikerDescriptor {
+                name: it.parent_def(db).name(db).to_string(),
+                desc: MonikerDescriptorKind::Type,
+            });
+        }
+        Definition::Variant(it) => description.push(MonikerDescriptor {
+            name: it.parent_enum(db).name(db).to_string(),
+            desc: MonikerDescriptorKind::Type,
+        }),
+        _ => (),
     }
 
-    path.push(def.name(db)?);
+    let name_desc = match def {
+        Definition::Module(_) => MonikerDescriptorKind::Namespace,
+        Definition::Function(_) => MonikerDescriptorKind::Method,
+        Definition::Macro(_) => MonikerDescriptorKind::Macro,
+        Definition::Adt(_)
+        | Definition::Variant(_)
+        | Definition::Trait(_)
+        | Definition::TypeAlias(_)
+        | Definition::BuiltinType(_) => MonikerDescriptorKind::Type,
+        Definition::Field(_) | Definition::Const(_) | Definition::Static(_) => {
+            MonikerDescriptorKind::Term
+        }
+        Definition::GenericParam(_) => MonikerDescriptorKind::TypeParameter,
+        Definition::Local(_) => MonikerDescriptorKind::Parameter,
+        Definition::SelfType(_)
+        | Definition::Label(_)
+        | Definition::BuiltinAttr(_)
+        | Definition::ToolModule(_) => MonikerDescriptorKind::Meta,
+    };
+    description.push(MonikerDescriptor { name: def.name(db)?.to_string(), desc: name_desc });
+
     Some(MonikerResult {
         identifier: MonikerIdentifier {
             crate_name: krate.display_name(db)?.crate_name().to_string(),
-            path,
+            description,
         },
         kind: if krate == from_crate { MonikerKind::Export } else { MonikerKind::Import },
-        package_information: {
-            let name = krate.display_name(db)?.to_string();
-            let (repo, version) = match krate.origin(db) {
-                CrateOrigin::CratesIo { repo } => (repo?, krate.version(db)?),
-                CrateOrigin::Lang => (
-                    "https://github.com/rust-lang/rust/".to_string(),
-                    "compiler_version".to_string(),
-                ),
-                CrateOrigin::Unknown => return None,
-            };
-            PackageInformation { name, repo, version }
-        },
+        package_information: package_information(db, krate)?,
     })
 }
 
+/// Locals are identified by the moniker of their enclosing body followed by the
+/// binding name. Shadowed bindings of the same name get an `@N` suffix, where `N`
+/// is the number of preceding bindings with that name in the body.
+fn local_to_moniker(db: &RootDatabase, local: Local, from_crate: Crate) -> Option<MonikerResult> {
+    if local.module(db).krate() != from_crate {
+        return None;
+    }
+    let parent: Definition = match local.parent(db) {
+        DefWithBody::Function(it) => it.into(),
+        DefWithBody::Static(it) => it.into(),
+        DefWithBody::Const(it) => it.into(),
+    };
+    let MonikerResult { identifier, package_information, .. } =
+        def_to_moniker(db, parent, from_crate)?;
+
+    let name = local.name(db).to_string();
+    let source = local.source(db);
+    let node = match &source.value {
+        Either::Left(pat) => pat.syntax().clone(),
+        Either::Right(self_param) => self_param.syntax().clone(),
+    };
+    let body = node.ancestors().find(|it| {
+        ast::Fn::can_cast(it.kind())
+            || ast::Const::can_cast(it.kind())
+            || ast::Static::can_cast(it.kind())
+    })?;
+    let shadowed = body
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .take_while(|pat| pat.syntax() != &node)
+        .filter(|pat| pat.name().map_or(false, |it| it.text() == name))
+        .count();
+    let name = if shadowed == 0 { name } else { format!("{}@{}", name, shadowed) };
+
+    let desc = if local.is_param(db) {
+        MonikerDescriptorKind::Parameter
+    } else {
+        MonikerDescriptorKind::Term
+    };
+    let mut description = identifier.description;
+    description.push(MonikerDescriptor { name, desc });
+    Some(MonikerResult {
+        identifier: MonikerIdentifier { crate_name: identifier.crate_name, description },
+        kind: MonikerKind::Local,
+        package_information,
+    })
+}
+
+/// Named modules become namespaces, while block modules are identified by the item
+/// whose body contains them.
+fn module_descriptors(db: &RootDatabase, module: Module) -> Vec<MonikerDescriptor> {
+    module
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .flat_map(|module| {
+            if let Some(name) = module.name(db) {
+                let desc = MonikerDescriptorKind::Namespace;
+                return vec![MonikerDescriptor { name: name.to_string(), desc }];
+            }
+            match module.definition_source(db).value {
+                ModuleSource::BlockExpr(block) => block
+                    .syntax()
+                    .ancestors()
+                    .find_map(|it| {
+                        let (name, desc) = match_ast! {
+                            match it {
+                                ast::Fn(it) => (it.name()?, MonikerDescriptorKind::Method),
+                                ast::Const(it) => (it.name()?, MonikerDescriptorKind::Term),
+                                ast::Static(it) => (it.name()?, MonikerDescriptorKind::Term),
+                                _ => return None,
+                            }
+                        };
+                        Some(MonikerDescriptor { name: name.text().to_string(), desc })
+                    })
+                    .into_iter()
+                    .collect(),
+                _ => vec![],
+            }
+        })
+        .collect()
+}
+
+/// An impl is identified by its self type, followed by the implemented trait (including
+/// its generic arguments) for trait impls. This keeps e.g. the items of `impl From<u8> for S`
+/// and `impl From<u16> for S` apart.
+fn impl_descriptors(db: &RootDatabase, impl_: Impl) -> Vec<MonikerDescriptor> {
+    let mut res = Vec::new();
+    let self_ty = impl_.self_ty(db);
+    let self_ty_name = match self_ty.as_adt() {
+        Some(adt) => adt.name(db).to_string(),
+        None => self_ty.display(db).to_string(),
+    };
+    res.push(MonikerDescriptor { name: self_ty_name, desc: MonikerDescriptorKind::Type });
+
+    if let Some(trait_) = impl_.trait_(db) {
+        let generic_args = impl_
+            .source(db)
+            .and_then(|src| match src.value.trait_()? {
+                ast::Type::PathType(ty) => ty.path()?.segment()?.generic_arg_list(),
+                _ => None,
+            })
+            .map(|args| args.syntax().text().to_string().split_whitespace().join(" "))
+            .unwrap_or_default();
+        res.push(MonikerDescriptor {
+            name: format!("{}{}", trait_.name(db), generic_args),
+            desc: MonikerDescriptorKind::Type,
+        });
+    }
+    res
+}
+
+fn package_information(db: &RootDatabase, krate: Crate) -> Option<PackageInformation> {
+    let name = krate.display_name(db)?.to_string();
+    let (repo, version) = match krate.origin(db) {
+        CrateOrigin::CratesIo { repo } => (repo, krate.version(db)?),
+        CrateOrigin::Lang => {
+            (Some("https://github.com/rust-lang/rust/".to_string()), "compiler_version".to_string())
+        }
+        CrateOrigin::Unknown => return None,
+    };
+    Some(PackageInformation { name, repo, version })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fixture;
 
     use super::MonikerKind;
 
-    #[track_caller]
-    fn no_moniker(ra_fixture: &str) {
-        let (analysis, position) = fixture::position(ra_fixture);
-        if let Some(x) = analysis.moniker(position).unwrap() {
-            assert_eq!(x.info.len(), 0, "Moniker founded but no moniker expected: {:?}", x);
-        }
-    }
-
     #[track_caller]
     fn check_moniker(ra_fixture: &str, identifier: &str, package: &str, kind: MonikerKind) {
         let (analysis, position) = fixture::position(ra_fixture);
@@ -202,7 +389,7 @@ pub mod module {
 }
 "#,
             "foo::module::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Import,
         );
         check_moniker(
@@ -218,7 +405,7 @@ pub mod module {
 }
 "#,
             "foo::module::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -235,7 +422,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -252,7 +439,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::MY_CONST",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -269,7 +456,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::MyType",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -292,11 +479,33 @@ pub mod module {
 }
 "#,
             "foo::module::MyStruct::MyTrait::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
 
+    #[test]
+    fn moniker_for_nested_module_item() {
+        check_moniker(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::outer::inner::func;
+fn main() {
+    func$0();
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub mod outer {
+    pub mod inner {
+        pub fn func() {}
+    }
+}
+"#,
+            "foo::outer::inner::func",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Import,
+        );
+    }
+
     #[test]
     fn moniker_for_field() {
         check_moniker(
@@ -312,14 +521,14 @@ pub struct St {
 }
 "#,
             "foo::St::a",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Import,
         );
     }
 
     #[test]
-    fn no_moniker_for_local() {
-        no_moniker(
+    fn moniker_for_local() {
+        check_moniker(
             r#"
 //- /lib.rs crate:main deps:foo
 use foo::module::func;
@@ -333,6 +542,143 @@ pub mod module {
     }
 }
 "#,
+            "foo::module::func::x",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Local,
+        );
+    }
+
+    #[test]
+    fn moniker_for_shadowed_local() {
+        check_moniker(
+            r#"
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub fn func(x: i32) {
+    let x = x + 1;
+    let x$0 = x * 2;
+}
+"#,
+            "foo::func::x@2",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Local,
+        );
+    }
+
+    #[test]
+    fn moniker_for_item_in_block() {
+        check_moniker(
+            r#"
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub struct S;
+pub fn func() {
+    struct S$0;
+}
+"#,
+            "foo::func::S",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn moniker_for_generic_trait_impl() {
+        check_moniker(
+            r#"
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub trait From<T> {
+    fn from(t: T) -> Self;
+}
+pub struct S;
+impl From<u8> for S {
+    fn from(_: u8) -> Self { S }
+}
+impl From<u16> for S {
+    fn from$0(_: u16) -> Self { S }
+}
+"#,
+            "foo::S::From<u16>::from",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn moniker_for_impl_on_non_adt() {
+        check_moniker(
+            r#"
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub trait Tr {
+    fn f(&self);
+}
+impl Tr for [u8] {
+    fn f$0(&self) {}
+}
+"#,
+            "foo::[u8]::Tr::f",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Export,
+        );
+    }
+
+    #[test]
+    fn moniker_for_variant_field() {
+        check_moniker(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::E;
+fn main() {
+    let _ = E::V { f$0: 0 };
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub enum E {
+    V { f: i32 },
+}
+"#,
+            "foo::E::V::f",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Import,
+        );
+    }
+
+    #[test]
+    fn moniker_for_exported_macro() {
+        check_moniker(
+            r#"
+//- /lib.rs crate:main deps:foo
+fn main() {
+    foo::mac$0!();
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub mod module {
+    #[macro_export]
+    macro_rules! mac {
+        () => {};
+    }
+}
+"#,
+            "foo::mac",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Import,
+        );
+    }
+
+    #[test]
+    fn moniker_for_macro() {
+        check_moniker(
+            r#"
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub mod module {
+    macro_rules! mac {
+        () => {};
+    }
+    fn f() {
+        mac$0!();
+    }
+}
+"#,
+            "foo::module::mac",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Export,
         );
     }
 }
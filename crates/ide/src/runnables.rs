@@ -2,8 +2,7 @@ use std::fmt;
 
 use ast::HasName;
 use cfg::CfgExpr;
-use hir::{AsAssocItem, HasAttrs, HasSource, HirDisplay, Semantics};
-use ide_assists::utils::test_related_attribute;
+use hir::{AsAssocItem, HasAttrs, HasSource, HirDisplay, InFile, PathResolution, Semantics};
 use ide_db::{
     base_db::{FilePosition, FileRange},
     defs::Definition,
@@ -15,12 +14,19 @@ use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::{always, format_to};
 use syntax::{
+    algo::find_node_at_range,
     ast::{self, AstNode, HasAttrs as _},
     SmolStr, SyntaxNode,
 };
 
 use crate::{references, FileId, NavigationTarget, ToNav, TryToNav};
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunnablesConfig {
+    /// Paths of additional attributes marking a function as a test, like `rstest::rstest`.
+    pub extra_test_attributes: Vec<String>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Runnable {
     pub use_name_in_title: bool,
@@ -120,7 +126,11 @@ impl Runnable {
 // | VS Code | **Rust Analyzer: Run**
 // |===
 // image::https://user-images.githubusercontent.com/48062697/113065583-055aae80-91b1-11eb-958f-d67efcaf6a2f.gif[]
-pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
+pub(crate) fn runnables(
+    db: &RootDatabase,
+    config: &RunnablesConfig,
+    file_id: FileId,
+) -> Vec<Runnable> {
     let sema = Semantics::new(db);
 
     let mut res = Vec::new();
@@ -152,18 +162,22 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     };
     visit_file_defs(&sema, file_id, &mut |def| {
         let runnable = match def {
-            Definition::Module(it) => runnable_mod(&sema, it),
-            Definition::Function(it) => runnable_fn(&sema, it),
+            Definition::Module(it) => runnable_mod(&sema, config, it),
+            Definition::Function(it) => runnable_fn(&sema, config, it),
             Definition::SelfType(impl_) => runnable_impl(&sema, &impl_),
             _ => None,
         };
+        let cases = match (def, &runnable) {
+            (Definition::Function(it), Some(runnable)) => runnable_fn_cases(&sema, it, runnable),
+            _ => Vec::new(),
+        };
         add_opt(runnable.or_else(|| module_def_doctest(sema.db, def)), Some(def));
+        cases.into_iter().for_each(|it| add_opt(Some(it), Some(def)));
         if let Definition::SelfType(impl_) = def {
             impl_.items(db).into_iter().for_each(|assoc| {
                 let runnable = match assoc {
-                    hir::AssocItem::Function(it) => {
-                        runnable_fn(&sema, it).or_else(|| module_def_doctest(sema.db, it.into()))
-                    }
+                    hir::AssocItem::Function(it) => runnable_fn(&sema, config, it)
+                        .or_else(|| module_def_doctest(sema.db, it.into())),
                     hir::AssocItem::Const(it) => module_def_doctest(sema.db, it.into()),
                     hir::AssocItem::TypeAlias(it) => module_def_doctest(sema.db, it.into()),
                 };
@@ -173,7 +187,7 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     });
 
     sema.to_module_defs(file_id)
-        .map(|it| runnable_mod_outline_definition(&sema, it))
+        .map(|it| runnable_mod_outline_definition(&sema, config, it))
         .for_each(|it| add_opt(it, None));
 
     res.extend(in_macro_expansion.into_iter().flat_map(|(_, runnables)| {
@@ -201,6 +215,7 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
 // |===
 pub(crate) fn related_tests(
     db: &RootDatabase,
+    config: &RunnablesConfig,
     position: FilePosition,
     search_scope: Option<SearchScope>,
 ) -> Vec<Runnable> {
@@ -208,13 +223,14 @@ pub(crate) fn related_tests(
     let mut res: FxHashSet<Runnable> = FxHashSet::default();
     let syntax = sema.parse(position.file_id).syntax().clone();
 
-    find_related_tests(&sema, &syntax, position, search_scope, &mut res);
+    find_related_tests(&sema, config, &syntax, position, search_scope, &mut res);
 
     res.into_iter().collect()
 }
 
 fn find_related_tests(
     sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
     syntax: &SyntaxNode,
     position: FilePosition,
     search_scope: Option<SearchScope>,
@@ -237,12 +253,12 @@ fn find_related_tests(
             if let Some(fn_def) =
                 sema.ancestors_with_macros(name_ref.syntax().clone()).find_map(ast::Fn::cast)
             {
-                if let Some(runnable) = as_test_runnable(sema, &fn_def) {
+                if let Some(runnable) = as_test_runnable(sema, config, &fn_def) {
                     // direct test
                     tests.insert(runnable);
-                } else if let Some(module) = parent_test_module(sema, &fn_def) {
+                } else if let Some(module) = parent_test_module(sema, config, &fn_def) {
                     // indirect test
-                    find_related_tests_in_module(sema, syntax, &fn_def, &module, tests);
+                    find_related_tests_in_module(sema, config, syntax, &fn_def, &module, tests);
                 }
             }
         }
@@ -251,6 +267,7 @@ fn find_related_tests(
 
 fn find_related_tests_in_module(
     sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
     syntax: &SyntaxNode,
     fn_def: &ast::Fn,
     parent_module: &hir::Module,
@@ -270,24 +287,32 @@ fn find_related_tests_in_module(
     let file_id = mod_source.file_id.original_file(sema.db);
     let mod_scope = SearchScope::file_range(FileRange { file_id, range });
     let fn_pos = FilePosition { file_id, offset: fn_name.syntax().text_range().start() };
-    find_related_tests(sema, syntax, fn_pos, Some(mod_scope), tests)
+    find_related_tests(sema, config, syntax, fn_pos, Some(mod_scope), tests)
 }
 
-fn as_test_runnable(sema: &Semantics<RootDatabase>, fn_def: &ast::Fn) -> Option<Runnable> {
-    if test_related_attribute(fn_def).is_some() {
-        let function = sema.to_def(fn_def)?;
-        runnable_fn(sema, function)
-    } else {
-        None
+fn as_test_runnable(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    fn_def: &ast::Fn,
+) -> Option<Runnable> {
+    let function = sema.to_def(fn_def)?;
+    let runnable = runnable_fn(sema, config, function)?;
+    match runnable.kind {
+        RunnableKind::Test { .. } => Some(runnable),
+        _ => None,
     }
 }
 
-fn parent_test_module(sema: &Semantics<RootDatabase>, fn_def: &ast::Fn) -> Option<hir::Module> {
+fn parent_test_module(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    fn_def: &ast::Fn,
+) -> Option<hir::Module> {
     fn_def.syntax().ancestors().find_map(|node| {
         let module = ast::Module::cast(node)?;
         let module = sema.to_def(&module)?;
 
-        if has_test_function_or_multiple_test_submodules(sema, &module) {
+        if has_test_function_or_multiple_test_submodules(sema, config, &module) {
             Some(module)
         } else {
             None
@@ -295,8 +320,12 @@ fn parent_test_module(sema: &Semantics<RootDatabase>, fn_def: &ast::Fn) -> Optio
     })
 }
 
-pub(crate) fn runnable_fn(sema: &Semantics<RootDatabase>, def: hir::Function) -> Option<Runnable> {
-    let func = def.source(sema.db)?;
+pub(crate) fn runnable_fn(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    def: hir::Function,
+) -> Option<Runnable> {
+    let func = sema.source(def)?;
     let name = def.name(sema.db).to_smol_str();
 
     let root = def.module(sema.db).krate().root_module(sema.db);
@@ -312,13 +341,12 @@ pub(crate) fn runnable_fn(sema: &Semantics<RootDatabase>, def: hir::Function) ->
             canonical_path.map(TestId::Path).unwrap_or(TestId::Name(name))
         };
 
-        if test_related_attribute(&func.value).is_some() {
-            let attr = TestAttr::from_fn(&func.value);
-            RunnableKind::Test { test_id: test_id(), attr }
-        } else if func.value.has_atom_attr("bench") {
-            RunnableKind::Bench { test_id: test_id() }
-        } else {
-            return None;
+        match test_fn_kind(sema, config, &func)? {
+            TestFnKind::Test => {
+                let attr = TestAttr::from_fn(sema, &func);
+                RunnableKind::Test { test_id: test_id(), attr }
+            }
+            TestFnKind::Bench => RunnableKind::Bench { test_id: test_id() },
         }
     };
 
@@ -331,8 +359,12 @@ pub(crate) fn runnable_fn(sema: &Semantics<RootDatabase>, def: hir::Function) ->
     Some(Runnable { use_name_in_title: false, nav, kind, cfg })
 }
 
-pub(crate) fn runnable_mod(sema: &Semantics<RootDatabase>, def: hir::Module) -> Option<Runnable> {
-    if !has_test_function_or_multiple_test_submodules(sema, &def) {
+pub(crate) fn runnable_mod(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    def: hir::Module,
+) -> Option<Runnable> {
+    if !has_test_function_or_multiple_test_submodules(sema, config, &def) {
         return None;
     }
     let path =
@@ -367,9 +399,10 @@ pub(crate) fn runnable_impl(sema: &Semantics<RootDatabase>, def: &hir::Impl) ->
 /// Creates a test mod runnable for outline modules at the top of their definition.
 fn runnable_mod_outline_definition(
     sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
     def: hir::Module,
 ) -> Option<Runnable> {
-    if !has_test_function_or_multiple_test_submodules(sema, &def) {
+    if !has_test_function_or_multiple_test_submodules(sema, config, &def) {
         return None;
     }
     let path =
@@ -459,15 +492,197 @@ pub struct TestAttr {
 }
 
 impl TestAttr {
-    fn from_fn(fn_def: &ast::Fn) -> TestAttr {
-        let ignore = fn_def
-            .attrs()
+    fn from_fn(sema: &Semantics<RootDatabase>, fn_def: &InFile<ast::Fn>) -> TestAttr {
+        let ignore = fn_attrs(sema, fn_def)
             .filter_map(|attr| attr.simple_name())
             .any(|attribute_text| attribute_text == "ignore");
         TestAttr { ignore }
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TestFnKind {
+    Test,
+    Bench,
+}
+
+/// Checks whether the function is a test or a benchmark.
+///
+/// Attribute paths are resolved where possible, so renamed imports of the builtin attributes and
+/// the attributes listed in [`RunnablesConfig::extra_test_attributes`] are recognised as well. If
+/// the function was produced by an attribute macro, the attributes of the original item count too.
+fn test_fn_kind(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    fn_def: &InFile<ast::Fn>,
+) -> Option<TestFnKind> {
+    fn_attrs(sema, fn_def).find_map(|attr| attr_test_fn_kind(sema, config, &attr))
+}
+
+fn attr_test_fn_kind(
+    sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
+    attr: &ast::Attr,
+) -> Option<TestFnKind> {
+    let path = attr.path()?;
+    let text = path.syntax().text().to_string();
+    let is_extra_test_attr = |path: &str| {
+        config
+            .extra_test_attributes
+            .iter()
+            .any(|it| it.trim_start_matches("::") == path.trim_start_matches("::"))
+    };
+    if is_extra_test_attr(&text) {
+        return Some(TestFnKind::Test);
+    }
+    if let Some(PathResolution::Def(hir::ModuleDef::Macro(mac))) = sema.resolve_path(&path) {
+        if mac.is_builtin_test(sema.db) {
+            return Some(TestFnKind::Test);
+        }
+        if mac.is_builtin_bench(sema.db) {
+            return Some(TestFnKind::Bench);
+        }
+        let krate = mac.module(sema.db).krate();
+        let mac_path = krate
+            .display_name(sema.db)
+            .map(|it| it.to_string())
+            .into_iter()
+            .chain(
+                mac.module(sema.db)
+                    .path_to_root(sema.db)
+                    .into_iter()
+                    .rev()
+                    .filter_map(|it| it.name(sema.db))
+                    .map(|it| it.to_string()),
+            )
+            .chain(Some(mac.name(sema.db).to_string()))
+            .join("::");
+        if is_extra_test_attr(&mac_path) {
+            return Some(TestFnKind::Test);
+        }
+    }
+    if text == "bench" {
+        Some(TestFnKind::Bench)
+    } else if text.starts_with("test") || text.ends_with("test") {
+        Some(TestFnKind::Test)
+    } else {
+        None
+    }
+}
+
+/// Returns the attributes of the function, including the ones of the original item if the
+/// function was produced by an attribute macro.
+fn fn_attrs(
+    sema: &Semantics<RootDatabase>,
+    fn_def: &InFile<ast::Fn>,
+) -> impl Iterator<Item = ast::Attr> {
+    let original = fn_def
+        .file_id
+        .is_attr_macro(sema.db)
+        .then(|| fn_def.file_id.call_node(sema.db))
+        .flatten()
+        .and_then(|call| {
+            let root = sema.parse_or_expand(call.file_id)?;
+            find_node_at_range::<ast::Fn>(&root, call.value.text_range())
+        });
+    fn_def.value.attrs().chain(original.into_iter().flat_map(|it| it.attrs()))
+}
+
+/// Creates a runnable for every case of a parametrised test, that is for every `#[case(..)]`
+/// attribute of an `rstest` test and every `#[test_case(..)]` attribute.
+fn runnable_fn_cases(
+    sema: &Semantics<RootDatabase>,
+    def: hir::Function,
+    runnable: &Runnable,
+) -> Vec<Runnable> {
+    let (test_id, attr) = match &runnable.kind {
+        RunnableKind::Test { test_id, attr } => (test_id, *attr),
+        _ => return Vec::new(),
+    };
+    let func = match sema.source(def) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let attrs = fn_attrs(sema, &func).collect::<Vec<_>>();
+
+    let rstest_cases = attrs
+        .iter()
+        .filter(|attr| {
+            attr.path().and_then(|path| path.first_segment()).map_or(false, |segment| {
+                segment.syntax().text() == "case" && attr.token_tree().is_some()
+            })
+        })
+        .collect::<Vec<_>>();
+    let width = rstest_cases.len().to_string().len();
+    let cases = rstest_cases.into_iter().enumerate().map(|(idx, attr)| {
+        let mut name = format!("case_{:0width$}", idx + 1, width = width);
+        if let Some(description) = attr.path().and_then(|path| path.segment()) {
+            if attr.path().and_then(|path| path.qualifier()).is_some() {
+                format_to!(name, "_{}", description.syntax().text());
+            }
+        }
+        (attr, name)
+    });
+
+    let test_cases = attrs.iter().filter_map(|attr| {
+        let path = attr.path()?;
+        if path.segment()?.syntax().text() != "test_case" {
+            return None;
+        }
+        let tt = attr.token_tree()?;
+        let args = tt.syntax().text().to_string();
+        let args = args.strip_prefix('(')?.strip_suffix(')')?;
+        let name = match args.split_once(';') {
+            Some((_, description)) => description.trim().trim_matches('"').to_string(),
+            None => args.to_string(),
+        };
+        Some((attr, escape_test_name(&name)))
+    });
+
+    cases
+        .chain(test_cases)
+        .filter_map(|(case_attr, name)| {
+            let range = sema.original_range_opt(case_attr.syntax())?;
+            let mut nav = runnable.nav.clone();
+            if range.file_id != nav.file_id {
+                return None;
+            }
+            nav.full_range = range.range;
+            nav.focus_range = None;
+            nav.name = name.clone().into();
+            let test_id = TestId::Path(format!("{}::{}", test_id, name));
+            Some(Runnable {
+                use_name_in_title: true,
+                nav,
+                kind: RunnableKind::Test { test_id, attr },
+                cfg: runnable.cfg.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Turns a `test_case` description into the name of the generated test function.
+fn escape_test_name(input: &str) -> String {
+    if input.is_empty() {
+        return "_empty".to_string();
+    }
+    let mut last_underscore = false;
+    let mut name = String::with_capacity(input.len());
+    for c in input.chars() {
+        if c.is_alphanumeric() {
+            last_underscore = false;
+            name.push(c.to_ascii_lowercase());
+        } else if !last_underscore {
+            last_underscore = true;
+            name.push('_');
+        }
+    }
+    if !name.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
 const RUSTDOC_FENCE: &str = "```";
 const RUSTDOC_CODE_BLOCK_ATTRIBUTES_RUNNABLE: &[&str] =
     &["", "rust", "should_panic", "edition2015", "edition2018", "edition2021"];
@@ -498,6 +713,7 @@ fn has_runnable_doc_test(attrs: &hir::Attrs) -> bool {
 // but that bloats the runnables for no real benefit, since all tests can be run by the submodule already
 fn has_test_function_or_multiple_test_submodules(
     sema: &Semantics<RootDatabase>,
+    config: &RunnablesConfig,
     module: &hir::Module,
 ) -> bool {
     let mut number_of_test_submodules = 0;
@@ -505,14 +721,14 @@ fn has_test_function_or_multiple_test_submodules(
     for item in module.declarations(sema.db) {
         match item {
             hir::ModuleDef::Function(f) => {
-                if let Some(it) = f.source(sema.db) {
-                    if test_related_attribute(&it.value).is_some() {
+                if let Some(it) = sema.source(f) {
+                    if test_fn_kind(sema, config, &it) == Some(TestFnKind::Test) {
                         return true;
                     }
                 }
             }
             hir::ModuleDef::Module(submodule) => {
-                if has_test_function_or_multiple_test_submodules(sema, &submodule) {
+                if has_test_function_or_multiple_test_submodules(sema, config, &submodule) {
                     number_of_test_submodules += 1;
                 }
             }
@@ -536,9 +752,18 @@ mod tests {
         // FIXME: fold this into `expect` as well
         actions: &[RunnableTestKind],
         expect: Expect,
+    ) {
+        check_with_config(&RunnablesConfig::default(), ra_fixture, actions, expect)
+    }
+
+    fn check_with_config(
+        config: &RunnablesConfig,
+        ra_fixture: &str,
+        actions: &[RunnableTestKind],
+        expect: Expect,
     ) {
         let (analysis, position) = fixture::position(ra_fixture);
-        let runnables = analysis.runnables(position.file_id).unwrap();
+        let runnables = analysis.runnables(config, position.file_id).unwrap();
         expect.assert_debug_eq(&runnables);
         assert_eq!(
             actions,
@@ -548,7 +773,7 @@ mod tests {
 
     fn check_tests(ra_fixture: &str, expect: Expect) {
         let (analysis, position) = fixture::position(ra_fixture);
-        let tests = analysis.related_tests(position, None).unwrap();
+        let tests = analysis.related_tests(&RunnablesConfig::default(), position, None).unwrap();
         expect.assert_debug_eq(&tests);
     }
 
@@ -1812,6 +2037,337 @@ mod module {
         );
     }
 
+    #[test]
+    fn extra_test_attributes() {
+        check_with_config(
+            &RunnablesConfig { extra_test_attributes: vec!["proc_macros::identity".to_owned()] },
+            r#"
+//- proc_macros: identity
+//- /lib.rs
+$0
+use proc_macros::identity as check;
+
+#[proc_macros::identity]
+fn t0() {}
+
+#[check]
+fn t1() {}
+
+#[proc_macros::input_replace]
+fn not_a_test() {}
+"#,
+            &[TestMod, Test, Test],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..145,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 84..94,
+                            focus_range: 87..89,
+                            name: "t1",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "t1",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 63..73,
+                            focus_range: 66..68,
+                            name: "t0",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "t0",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn builtin_test_attribute_through_import() {
+        check(
+            r#"
+//- /lib.rs
+$0
+#[rustc_builtin_macro]
+pub macro test($item:item) {}
+
+#[rustc_builtin_macro]
+pub macro bench($item:item) {}
+
+mod tests {
+    use crate::test as check;
+    use crate::bench as measure;
+
+    #[check]
+    fn t() {}
+
+    #[measure]
+    fn b() {}
+}
+"#,
+            &[TestMod, Test, Bench],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 110..244,
+                            focus_range: 114..119,
+                            name: "tests",
+                            kind: Module,
+                            description: "mod tests",
+                        },
+                        kind: TestMod {
+                            path: "tests",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 190..212,
+                            focus_range: 206..207,
+                            name: "t",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "tests::t",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 218..242,
+                            focus_range: 236..237,
+                            name: "b",
+                            kind: Function,
+                        },
+                        kind: Bench {
+                            test_id: Path(
+                                "tests::b",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parametrised_test_cases() {
+        check(
+            r#"
+//- /lib.rs
+$0
+#[rstest]
+#[case(0, 1)]
+#[case::answer(42, 43)]
+fn increment(#[case] input: u32, #[case] expected: u32) {}
+
+#[test_case(1, 2)]
+#[test_case(-1, 0 ; "Negative one")]
+fn add_one(input: i32, expected: i32) {}
+"#,
+            &[Test, Test, Test, Test, Test, Test, TestMod],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 1..107,
+                            focus_range: 52..61,
+                            name: "increment",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "increment",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 11..24,
+                            name: "case_1",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "increment::case_1",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 25..48,
+                            name: "case_2_answer",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "increment::case_2_answer",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 109..205,
+                            focus_range: 168..175,
+                            name: "add_one",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "add_one",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 109..127,
+                            name: "_1_2",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "add_one::_1_2",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: true,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 128..164,
+                            name: "negative_one",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "add_one::negative_one",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..206,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn find_no_tests() {
         check_tests(
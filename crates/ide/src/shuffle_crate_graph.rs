@@ -30,6 +30,7 @@ pub(crate) fn shuffle_crate_graph(db: &mut RootDatabase) {
             data.edition,
             data.display_name.clone(),
             data.version.clone(),
+            data.rust_version.clone(),
             data.cfg_options.clone(),
             data.potential_cfg_options.clone(),
             data.env.clone(),
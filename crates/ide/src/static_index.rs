@@ -3,7 +3,7 @@
 
 use std::collections::HashMap;
 
-use hir::{db::HirDatabase, Crate, Module, Semantics};
+use hir::{db::HirDatabase, AsAssocItem, AssocItemContainer, Crate, Impl, Module, Semantics};
 use ide_db::{
     base_db::{FileId, FileRange, SourceDatabaseExt},
     defs::{Definition, IdentClass},
@@ -42,6 +42,7 @@ pub struct TokenStaticData {
     pub definition: Option<FileRange>,
     pub references: Vec<ReferenceData>,
     pub moniker: Option<MonikerResult>,
+    pub implements: Vec<MonikerResult>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -123,8 +124,11 @@ impl StaticIndex<'_> {
             syntax::NodeOrToken::Node(_) => None,
             syntax::NodeOrToken::Token(x) => Some(x),
         });
-        let hover_config =
-            HoverConfig { links_in_hover: true, documentation: Some(HoverDocFormat::Markdown) };
+        let hover_config = HoverConfig {
+            links_in_hover: true,
+            documentation: Some(HoverDocFormat::Markdown),
+            runnables: Default::default(),
+        };
         let tokens = tokens.filter(|token| {
             matches!(
                 token.kind(),
@@ -149,6 +153,9 @@ impl StaticIndex<'_> {
                         .map(|x| FileRange { file_id: x.file_id, range: x.focus_or_full_range() }),
                     references: vec![],
                     moniker: current_crate.and_then(|cc| def_to_moniker(self.db, def, cc)),
+                    implements: current_crate
+                        .map(|cc| implemented_monikers(self.db, def, cc))
+                        .unwrap_or_default(),
                 });
                 self.def_map.insert(def, x);
                 x
@@ -167,6 +174,16 @@ impl StaticIndex<'_> {
     }
 
     pub fn compute(analysis: &Analysis) -> StaticIndex {
+        StaticIndex::compute_filtered(analysis, |_| true)
+    }
+
+    /// Like [`StaticIndex::compute`], but only indexes the workspace files accepted by `filter`.
+    ///
+    /// This allows re-indexing just the files (or crates) that changed since a previous run.
+    pub fn compute_filtered(
+        analysis: &Analysis,
+        filter: impl Fn(FileId) -> bool,
+    ) -> StaticIndex<'_> {
         let db = &*analysis.db;
         let work = all_modules(db).into_iter().filter(|module| {
             let file_id = module.definition_source(db).file_id.original_file(db);
@@ -184,7 +201,7 @@ impl StaticIndex<'_> {
         let mut visited_files = FxHashSet::default();
         for module in work {
             let file_id = module.definition_source(db).file_id.original_file(db);
-            if visited_files.contains(&file_id) {
+            if visited_files.contains(&file_id) || !filter(file_id) {
                 continue;
             }
             this.add_file(file_id);
@@ -195,6 +212,41 @@ impl StaticIndex<'_> {
     }
 }
 
+/// Monikers of the traits (or trait items) that `def` implements, in a stable order.
+fn implemented_monikers(
+    db: &RootDatabase,
+    def: Definition,
+    from_crate: Crate,
+) -> Vec<MonikerResult> {
+    let implemented = match def {
+        Definition::Adt(adt) => Impl::all_for_type(db, adt.ty(db))
+            .into_iter()
+            .filter_map(|impl_| impl_.trait_(db))
+            .map(Definition::Trait)
+            .collect(),
+        _ => match def.as_assoc_item(db).map(|assoc| assoc.container(db)) {
+            Some(AssocItemContainer::Impl(impl_)) => {
+                let name = def.name(db);
+                impl_
+                    .trait_(db)
+                    .into_iter()
+                    .flat_map(|trait_| trait_.items(db))
+                    .filter(|item| item.name(db) == name)
+                    .map(Definition::from)
+                    .collect()
+            }
+            _ => vec![],
+        },
+    };
+    let mut monikers: Vec<_> = implemented
+        .into_iter()
+        .filter_map(|def: Definition| def_to_moniker(db, def, from_crate))
+        .collect();
+    monikers.sort_by_key(|moniker| moniker.identifier.to_string());
+    monikers.dedup();
+    monikers
+}
+
 fn get_definition(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Option<Definition> {
     for token in sema.descend_into_macros(token) {
         let def = IdentClass::classify_token(sema, &token).map(IdentClass::definitions);
@@ -311,4 +363,19 @@ struct Hello(i32);
 "#,
         );
     }
+
+    #[test]
+    fn filtered_files() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /main.rs
+mod foo;
+fn main() {}
+//- /foo.rs
+pub fn foo() {}
+"#,
+        );
+        let s = StaticIndex::compute_filtered(&analysis, |it| it == file_id);
+        assert_eq!(s.files.iter().map(|it| it.file_id).collect::<Vec<_>>(), vec![file_id]);
+    }
 }
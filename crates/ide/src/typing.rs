@@ -17,12 +17,13 @@ mod on_enter;
 
 use ide_db::{
     base_db::{FilePosition, SourceDatabase},
-    RootDatabase,
+    RootDatabase, SnippetCap,
 };
 use syntax::{
-    algo::find_node_at_offset,
-    ast::{self, edit::IndentLevel, AstToken},
-    AstNode, Parse, SourceFile, SyntaxKind, TextRange, TextSize,
+    algo::{find_node_at_offset, non_trivia_sibling},
+    ast::{self, edit::IndentLevel, AstToken, HasArgList, HasLoopBody},
+    match_ast, AstNode, Direction, NodeOrToken, Parse, SourceFile, SyntaxKind, TextRange, TextSize,
+    T,
 };
 
 use text_edit::{Indel, TextEdit};
@@ -32,7 +33,31 @@ use crate::SourceChange;
 pub(crate) use on_enter::on_enter;
 
 // Don't forget to add new trigger characters to `server_capabilities` in `caps.rs`.
-pub(crate) const TRIGGER_CHARS: &str = ".=>{";
+pub(crate) const TRIGGER_CHARS: &str = ".=>{<|\n";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypingConfig {
+    /// Insert a closing `>` when typing `<` after a path taking generic arguments.
+    pub close_angle_brackets: bool,
+    /// Insert the closing `|` when typing `|` to start the parameter list of a closure.
+    pub close_closure_params: bool,
+    /// Complete `=` to `=> ,` when typing it after the pattern of a match arm.
+    pub match_arm_arrow: bool,
+    /// Add the missing `;` to a `let` statement when starting a new line after it.
+    pub let_semicolon_on_newline: bool,
+    pub snippet_cap: Option<SnippetCap>,
+}
+
+struct ExtendedTextEdit {
+    edit: TextEdit,
+    is_snippet: bool,
+}
+
+impl From<TextEdit> for ExtendedTextEdit {
+    fn from(edit: TextEdit) -> Self {
+        ExtendedTextEdit { edit, is_snippet: false }
+    }
+}
 
 // Feature: On Typing Assists
 //
@@ -41,6 +66,12 @@ pub(crate) const TRIGGER_CHARS: &str = ".=>{";
 // - typing `let =` tries to smartly add `;` if `=` is followed by an existing expression
 // - typing `.` in a chain method call auto-indents
 // - typing `{` in front of an expression inserts a closing `}` after the expression
+// - typing `<` after a path that takes generic arguments inserts a closing `>`
+// - typing `|` to start the parameter list of a closure inserts the closing `|`
+// - typing `=` after the pattern of a match arm turns it into `=> ,`
+// - starting a new line after a `let` statement adds the missing `;`
+//
+// Each of the last four can be toggled with the corresponding `rust-analyzer.typing.*` setting.
 //
 // VS Code::
 //
@@ -54,6 +85,7 @@ pub(crate) const TRIGGER_CHARS: &str = ".=>{";
 // image::https://user-images.githubusercontent.com/48062697/113171066-105c2000-923f-11eb-87ab-f4a263346567.gif[]
 pub(crate) fn on_char_typed(
     db: &RootDatabase,
+    config: &TypingConfig,
     position: FilePosition,
     char_typed: char,
 ) -> Option<SourceChange> {
@@ -64,23 +96,31 @@ pub(crate) fn on_char_typed(
     if !stdx::always!(file.tree().syntax().text().char_at(position.offset) == Some(char_typed)) {
         return None;
     }
-    let edit = on_char_typed_inner(file, position.offset, char_typed)?;
-    Some(SourceChange::from_text_edit(position.file_id, edit))
+    let edit = on_char_typed_inner(config, file, position.offset, char_typed)?;
+    let mut change = SourceChange::from_text_edit(position.file_id, edit.edit);
+    change.is_snippet = edit.is_snippet;
+    Some(change)
 }
 
 fn on_char_typed_inner(
+    config: &TypingConfig,
     file: &Parse<SourceFile>,
     offset: TextSize,
     char_typed: char,
-) -> Option<TextEdit> {
+) -> Option<ExtendedTextEdit> {
     if !stdx::always!(TRIGGER_CHARS.contains(char_typed)) {
         return None;
     }
     match char_typed {
-        '.' => on_dot_typed(&file.tree(), offset),
-        '=' => on_eq_typed(&file.tree(), offset),
-        '>' => on_arrow_typed(&file.tree(), offset),
-        '{' => on_opening_brace_typed(file, offset),
+        '.' => on_dot_typed(&file.tree(), offset).map(Into::into),
+        '=' => on_eq_typed(&file.tree(), offset)
+            .map(Into::into)
+            .or_else(|| on_match_arm_eq_typed(config, &file.tree(), offset)),
+        '>' => on_arrow_typed(&file.tree(), offset).map(Into::into),
+        '{' => on_opening_brace_typed(file, offset).map(Into::into),
+        '<' => on_left_angle_typed(config, &file.tree(), offset),
+        '|' => on_pipe_typed(config, &file.tree(), offset),
+        '\n' => on_newline_typed(config, &file.tree(), offset).map(Into::into),
         _ => unreachable!(),
     }
 }
@@ -185,6 +225,201 @@ fn on_eq_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
     Some(TextEdit::insert(offset, ";".to_string()))
 }
 
+/// Turns `=` typed right after the pattern of a match arm into `=> $0,`.
+fn on_match_arm_eq_typed(
+    config: &TypingConfig,
+    file: &SourceFile,
+    offset: TextSize,
+) -> Option<ExtendedTextEdit> {
+    if !config.match_arm_arrow {
+        return None;
+    }
+    let eq = file.syntax().token_at_offset(offset).right_biased()?;
+    if eq.kind() != T![=] {
+        return None;
+    }
+    let arm = eq.ancestors().find_map(ast::MatchArm::cast)?;
+    if arm.fat_arrow_token().is_some() || arm.expr().is_some() {
+        return None;
+    }
+    let pat = arm.pat()?;
+    if non_trivia_sibling(pat.syntax().clone().into(), Direction::Next)
+        .map_or(true, |it| it.text_range().start() != offset)
+    {
+        return None;
+    }
+
+    let after_eq = offset + TextSize::of('=');
+    let needs_comma =
+        arm.comma_token().is_none() && file.syntax().text().char_at(after_eq) != Some(',');
+    let (text, is_snippet) = match config.snippet_cap {
+        Some(_) if needs_comma => ("> $0,", true),
+        Some(_) | None => ("> ", false),
+    };
+    Some(ExtendedTextEdit { edit: TextEdit::insert(after_eq, text.to_string()), is_snippet })
+}
+
+/// Adds a closing `>` when `<` is typed to start the generic arguments of a path, like in
+/// `Vec<` or `iter::<`.
+fn on_left_angle_typed(
+    config: &TypingConfig,
+    file: &SourceFile,
+    offset: TextSize,
+) -> Option<ExtendedTextEdit> {
+    if !config.close_angle_brackets {
+        return None;
+    }
+    config.snippet_cap?;
+    let l_angle = file.syntax().token_at_offset(offset).right_biased()?;
+    if l_angle.kind() != T![<] {
+        return None;
+    }
+    let generic_args = ast::GenericArgList::cast(l_angle.parent()?)?;
+    if generic_args.r_angle_token().is_some() {
+        return None;
+    }
+    Some(ExtendedTextEdit {
+        edit: TextEdit::insert(offset + TextSize::of('<'), "$0>".to_string()),
+        is_snippet: true,
+    })
+}
+
+/// Adds the closing `|` when `|` is typed to start the parameter list of a closure.
+fn on_pipe_typed(
+    config: &TypingConfig,
+    file: &SourceFile,
+    offset: TextSize,
+) -> Option<ExtendedTextEdit> {
+    if !config.close_closure_params {
+        return None;
+    }
+    config.snippet_cap?;
+    let pipe = file.syntax().token_at_offset(offset).right_biased()?;
+    if pipe.kind() != T![|] {
+        return None;
+    }
+    let param_list = ast::ParamList::cast(pipe.parent()?)?;
+    ast::ClosureExpr::cast(param_list.syntax().parent()?)?;
+    let pipes = param_list.syntax().children_with_tokens().filter(|it| it.kind() == T![|]);
+    if pipes.count() != 1 {
+        return None;
+    }
+    Some(ExtendedTextEdit {
+        edit: TextEdit::insert(offset + TextSize::of('|'), "$0|".to_string()),
+        is_snippet: true,
+    })
+}
+
+/// Adds the missing `;` to a `let` statement when a new line is started right after it.
+fn on_newline_typed(
+    config: &TypingConfig,
+    file: &SourceFile,
+    offset: TextSize,
+) -> Option<TextEdit> {
+    if !config.let_semicolon_on_newline {
+        return None;
+    }
+    let whitespace =
+        file.syntax().token_at_offset(offset).right_biased().and_then(ast::Whitespace::cast)?;
+    let let_stmt = whitespace
+        .syntax()
+        .prev_sibling_or_token()
+        .and_then(|it| it.into_node())
+        .and_then(ast::LetStmt::cast)?;
+    if let_stmt.semicolon_token().is_some() {
+        return None;
+    }
+    // An incomplete statement, like `let x = a +`, is still being typed.
+    if !is_complete(&let_stmt) {
+        return None;
+    }
+    // The expression may continue on the next line, like a method chain.
+    let next_token = non_trivia_sibling(whitespace.syntax().clone().into(), Direction::Next)
+        .and_then(|it| match it {
+            NodeOrToken::Node(node) => node.first_token(),
+            NodeOrToken::Token(token) => Some(token),
+        });
+    if next_token.map_or(false, |it| continues_expr(it.kind())) {
+        return None;
+    }
+    let before_newline = TextRange::new(whitespace.syntax().text_range().start(), offset);
+    if file.syntax().text().slice(before_newline).contains_char('\n') {
+        return None;
+    }
+    Some(TextEdit::insert(let_stmt.syntax().text_range().end(), ";".to_string()))
+}
+
+/// Whether nothing but the `;` is missing from the `let` statement: it has no error nodes,
+/// all its delimiters are closed and none of its parts lacks an operand or a body.
+fn is_complete(let_stmt: &ast::LetStmt) -> bool {
+    if (let_stmt.eq_token().is_some() && let_stmt.initializer().is_none())
+        || (let_stmt.colon_token().is_some() && let_stmt.ty().is_none())
+    {
+        return false;
+    }
+    let mut depth = 0;
+    for token in let_stmt.syntax().descendants_with_tokens().filter_map(|it| it.into_token()) {
+        match token.kind() {
+            T!['('] | T!['['] | T!['{'] => depth += 1,
+            T![')'] | T![']'] | T!['}'] => depth -= 1,
+            _ => (),
+        }
+    }
+    if depth != 0 {
+        return false;
+    }
+    let_stmt.syntax().descendants().all(|node| {
+        match_ast! {
+            match node {
+                ast::BinExpr(it) => it.lhs().is_some() && it.rhs().is_some(),
+                ast::PrefixExpr(it) => it.expr().is_some(),
+                ast::CastExpr(it) => it.ty().is_some(),
+                ast::FieldExpr(it) => it.name_ref().is_some(),
+                ast::MethodCallExpr(it) => it.arg_list().is_some(),
+                ast::MacroCall(it) => it.token_tree().is_some(),
+                ast::IfExpr(it) => it.condition().is_some() && it.then_branch().is_some(),
+                ast::WhileExpr(it) => it.condition().is_some() && it.loop_body().is_some(),
+                ast::ForExpr(it) => it.iterable().is_some() && it.loop_body().is_some(),
+                ast::LoopExpr(it) => it.loop_body().is_some(),
+                ast::MatchExpr(it) => it.expr().is_some() && it.match_arm_list().is_some(),
+                ast::ClosureExpr(it) => it.body().is_some(),
+                ast::GenericArgList(it) => it.r_angle_token().is_some(),
+                _ => node.kind() != SyntaxKind::ERROR,
+            }
+        }
+    })
+}
+
+/// Whether a line starting with `kind` continues the expression of the line before it.
+fn continues_expr(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        T![.]
+            | T![?]
+            | T![as]
+            | T![+]
+            | T![-]
+            | T![*]
+            | T![/]
+            | T![%]
+            | T![^]
+            | T![&]
+            | T![|]
+            | T![&&]
+            | T![||]
+            | T![<]
+            | T![>]
+            | T![<<]
+            | T![>>]
+            | T![<=]
+            | T![>=]
+            | T![==]
+            | T![!=]
+            | T![..]
+            | T![..=]
+    )
+}
+
 /// Returns an edit which should be applied when a dot ('.') is typed on a blank line, indenting the line appropriately.
 fn on_dot_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
     if !stdx::always!(file.syntax().text().char_at(offset) == Some('.')) {
@@ -262,13 +497,29 @@ mod tests {
 
     use super::*;
 
+    const TEST_CONFIG: TypingConfig = TypingConfig {
+        close_angle_brackets: true,
+        close_closure_params: true,
+        match_arm_arrow: true,
+        let_semicolon_on_newline: true,
+        snippet_cap: SnippetCap::new(true),
+    };
+
     fn do_type_char(char_typed: char, before: &str) -> Option<String> {
+        do_type_char_with_config(&TEST_CONFIG, char_typed, before)
+    }
+
+    fn do_type_char_with_config(
+        config: &TypingConfig,
+        char_typed: char,
+        before: &str,
+    ) -> Option<String> {
         let (offset, mut before) = extract_offset(before);
         let edit = TextEdit::insert(offset, char_typed.to_string());
         edit.apply(&mut before);
         let parse = SourceFile::parse(&before);
-        on_char_typed_inner(&parse, offset, char_typed).map(|it| {
-            it.apply(&mut before);
+        on_char_typed_inner(config, &parse, offset, char_typed).map(|it| {
+            it.edit.apply(&mut before);
             before.to_string()
         })
     }
@@ -714,4 +965,280 @@ fn foo() {
 "#,
         );
     }
+
+    #[test]
+    fn adds_closing_angle_bracket_for_generic_args() {
+        type_char(
+            '<',
+            r#"
+fn foo() {
+    let v: Vec$0
+}
+"#,
+            r#"
+fn foo() {
+    let v: Vec<$0>
+}
+"#,
+        );
+        type_char(
+            '<',
+            r#"
+fn foo() {
+    let v = iter.collect::$0();
+}
+"#,
+            r#"
+fn foo() {
+    let v = iter.collect::<$0>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_closing_angle_bracket_for_comparison() {
+        type_char_noop(
+            '<',
+            r#"
+fn foo() {
+    let b = a $0 c;
+}
+"#,
+        );
+        type_char_noop(
+            '<',
+            r#"
+fn foo() {
+    let v: Vec$0i32>;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_closing_pipe_for_closure_params() {
+        type_char(
+            '|',
+            r#"
+fn foo() {
+    xs.map($0);
+}
+"#,
+            r#"
+fn foo() {
+    xs.map(|$0|);
+}
+"#,
+        );
+        type_char_noop(
+            '|',
+            r#"
+fn foo() {
+    xs.map(|x$0);
+}
+"#,
+        );
+        type_char_noop(
+            '|',
+            r#"
+fn foo() {
+    let b = a $0 c;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_fat_arrow_in_match_arm() {
+        type_char(
+            '=',
+            r#"
+fn foo() {
+    match x {
+        Some(y) $0
+    }
+}
+"#,
+            r#"
+fn foo() {
+    match x {
+        Some(y) => $0,
+    }
+}
+"#,
+        );
+        type_char(
+            '=',
+            r#"
+fn foo() {
+    match x {
+        Some(y) $0
+        None => (),
+    }
+}
+"#,
+            r#"
+fn foo() {
+    match x {
+        Some(y) => $0,
+        None => (),
+    }
+}
+"#,
+        );
+        type_char_noop(
+            '=',
+            r#"
+fn foo() {
+    match x {
+        Some(y) if y $0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_fat_arrow_without_snippets() {
+        let config = TypingConfig { snippet_cap: None, ..TEST_CONFIG };
+        let actual = do_type_char_with_config(&config, '=', "fn foo() { match x { Some(y) $0 } }");
+        assert_eq_text!("fn foo() { match x { Some(y) =>  } }", &actual.unwrap());
+        assert!(do_type_char_with_config(&config, '<', "fn foo() { let v: Vec$0 }").is_none());
+    }
+
+    #[test]
+    fn adds_semicolon_to_let_on_newline() {
+        type_char(
+            '\n',
+            r#"
+fn foo() {
+    let x = 92$0
+}
+"#,
+            r#"
+fn foo() {
+    let x = 92;
+
+}
+"#,
+        );
+        type_char(
+            '\n',
+            r#"
+fn foo() {
+    let x: Vec<i32> = Vec::new()$0
+    bar();
+}
+"#,
+            r#"
+fn foo() {
+    let x: Vec<i32> = Vec::new();
+
+    bar();
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = $0
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = 92;$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_semicolon_for_incomplete_let() {
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = a +$0
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = foo($0
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = if a$0
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x: Vec<$0
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = a as$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_semicolon_when_expression_continues() {
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = foo$0
+        .bar();
+}
+"#,
+        );
+        type_char_noop(
+            '\n',
+            r#"
+fn foo() {
+    let x = foo$0
+        + bar;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn typing_assists_can_be_disabled() {
+        let config = TypingConfig {
+            close_angle_brackets: false,
+            close_closure_params: false,
+            match_arm_arrow: false,
+            let_semicolon_on_newline: false,
+            ..TEST_CONFIG
+        };
+        for (char_typed, before) in [
+            ('<', "fn foo() { let v: Vec$0 }"),
+            ('|', "fn foo() { xs.map($0); }"),
+            ('=', "fn foo() { match x { Some(y) $0 } }"),
+            ('\n', "fn foo() { let x = 92$0 }"),
+        ] {
+            assert!(do_type_char_with_config(&config, char_typed, before).is_none());
+        }
+    }
 }
@@ -0,0 +1,175 @@
+use ide_db::{
+    change_signature::{change_signature as apply_change, ParamChange, SignatureChange},
+    source_change::SourceChange,
+};
+use syntax::{ast, AstNode};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: change_signature
+//
+// Moves a parameter one position to the left or right. The arguments of all calls are reordered,
+// and so are the parameters of the other functions of a trait method family.
+//
+// ```
+// fn frobnicate($0x: i32, y: bool) {}
+//
+// fn main() {
+//     frobnicate(92, true);
+// }
+// ```
+// ->
+// ```
+// fn frobnicate(y: bool, x: i32) {}
+//
+// fn main() {
+//     frobnicate(true, 92);
+// }
+// ```
+pub(crate) fn change_signature(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let param: ast::Param = ctx.find_node_at_offset()?;
+    let param_list = param.syntax().parent().and_then(ast::ParamList::cast)?;
+    let fn_ = param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    let func = ctx.sema.to_def(&fn_)?;
+
+    let param_count = param_list.params().count();
+    let index = param_list.params().position(|it| it == param)?;
+    let moves =
+        [("Move parameter right", index + 1), ("Move parameter left", index.wrapping_sub(1))];
+    for (label, new_index) in moves {
+        if new_index >= param_count {
+            continue;
+        }
+        let mut order: Vec<_> = (0..param_count).collect();
+        order.swap(index, new_index);
+        let change = SignatureChange {
+            params: order
+                .into_iter()
+                .map(|index| ParamChange::Existing { index, name: None, ty: None })
+                .collect(),
+        };
+        // Not offered when some usage can't be updated, the assist has no way to tell why.
+        let source_change = match apply_change(&ctx.sema, func, &change) {
+            Ok(it) => it,
+            Err(_) => continue,
+        };
+        acc.add(
+            AssistId("change_signature", AssistKind::RefactorRewrite),
+            label,
+            param.syntax().text_range(),
+            |builder| apply_source_change(builder, source_change),
+        );
+    }
+    Some(())
+}
+
+fn apply_source_change(builder: &mut AssistBuilder, source_change: SourceChange) {
+    for (file_id, edit) in source_change.source_file_edits {
+        builder.edit_file(file_id);
+        for indel in edit {
+            builder.replace(indel.delete, indel.insert);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn move_left() {
+        check_assist_by_label(
+            change_signature,
+            r#"
+fn frobnicate(x: i32, y: bool, $0z: u8) {}
+
+fn main() {
+    frobnicate(1, false, 2);
+}
+"#,
+            r#"
+fn frobnicate(x: i32, z: u8, y: bool) {}
+
+fn main() {
+    frobnicate(1, 2, false);
+}
+"#,
+            "Move parameter left",
+        );
+    }
+
+    #[test]
+    fn method_with_self() {
+        check_assist(
+            change_signature,
+            r#"
+struct S;
+impl S {
+    fn frobnicate(&self, $0x: i32, y: bool) {}
+}
+
+fn main() {
+    S.frobnicate(1, true);
+    S::frobnicate(&S, 2, false);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn frobnicate(&self, y: bool, x: i32) {}
+}
+
+fn main() {
+    S.frobnicate(true, 1);
+    S::frobnicate(&S, false, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn calls_in_other_files() {
+        check_assist(
+            change_signature,
+            r#"
+//- /main.rs
+mod foo;
+pub fn frobnicate($0x: i32, y: bool) {}
+//- /foo.rs
+fn bar() {
+    crate::frobnicate(1, true);
+}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+pub fn frobnicate(y: bool, x: i32) {}
+//- /foo.rs
+fn bar() {
+    crate::frobnicate(true, 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_function_references() {
+        check_assist_not_applicable(
+            change_signature,
+            r#"
+fn frobnicate($0x: i32, y: i32) -> i32 { x + y }
+
+fn main() {
+    let f = frobnicate;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn single_param() {
+        check_assist_not_applicable(change_signature, r#"fn frobnicate($0x: i32) {}"#);
+    }
+}
@@ -0,0 +1,753 @@
+use hir::{ModuleDef, ScopeDef};
+use ide_db::{defs::Definition, helpers::mod_path_to_ast, search::FileReference};
+use rustc_hash::FxHashMap;
+use stdx::to_camel_case;
+use syntax::{
+    ast::{self, edit::IndentLevel, edit_in_place::Indent, make, HasArgList, HasVisibility},
+    match_ast,
+    ted::{self, Position},
+    AstNode, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_bool_to_enum
+//
+// Replaces a boolean local, parameter or field by a two-variant enum, rewriting the values
+// assigned to it and the places reading it.
+//
+// ```
+// fn main() {
+//     let $0verbose = true;
+//     if verbose {
+//         println!("hello");
+//     }
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// enum Verbose {
+//     True,
+//     False,
+// }
+//
+// fn main() {
+//     let verbose = Verbose::True;
+//     if verbose == Verbose::True {
+//         println!("hello");
+//     }
+// }
+// ```
+pub(crate) fn convert_bool_to_enum(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let target = find_target(ctx, &name)?;
+    let enum_name = to_camel_case(&name.text());
+    let item = target.syntax().ancestors().filter_map(ast::Item::cast).find(|it| {
+        it.syntax().parent().map_or(false, |parent| {
+            matches!(parent.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST)
+        })
+    })?;
+    if type_name_taken(ctx, item.syntax(), &enum_name) {
+        return None;
+    }
+
+    let def_module = ctx.sema.scope(target.syntax()).module()?;
+    let enum_path = |node: &SyntaxNode| -> Option<String> {
+        let module = ctx.sema.scope(node).module()?;
+        if module == def_module {
+            return Some(enum_name.clone());
+        }
+        let module_path = module.find_use_path(ctx.db(), hir::ModuleDef::Module(def_module))?;
+        Some(format!("{}::{}", mod_path_to_ast(&module_path), enum_name))
+    };
+
+    let is_field = matches!(target, Target::Field { .. });
+    let mut sites = FxHashMap::default();
+    // References from within macro calls can't be edited.
+    let mut macro_uses = 0;
+    for (file_id, references) in target.definition().usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(file_id);
+        let file_sites: &mut Vec<(Site, String)> = sites.entry(file_id).or_default();
+        for FileReference { name, .. } in references {
+            if name.syntax().ancestors().last().as_ref() != Some(source_file.syntax()) {
+                macro_uses += 1;
+                continue;
+            }
+            let path = enum_path(name.syntax())?;
+            let mut name_sites = Vec::new();
+            classify_usage(&name, is_field, &mut name_sites)?;
+            file_sites.extend(name_sites.into_iter().map(|site| (site, path.clone())));
+        }
+    }
+    if let Target::Param { func, index, .. } = &target {
+        for (file_id, references) in Definition::Function(*func).usages(&ctx.sema).all() {
+            let file_sites: &mut Vec<(Site, String)> = sites.entry(file_id).or_default();
+            for FileReference { name, .. } in references {
+                if let Some(site) = call_site(ctx, &name, *func, *index) {
+                    file_sites.push((site, enum_path(name.syntax())?));
+                }
+            }
+        }
+    }
+
+    let label = match macro_uses {
+        0 => "Convert boolean to enum".to_owned(),
+        1 => "Convert boolean to enum, leaving 1 use in macro calls".to_owned(),
+        n => format!("Convert boolean to enum, leaving {} uses in macro calls", n),
+    };
+    acc.add(
+        AssistId("convert_bool_to_enum", AssistKind::RefactorRewrite),
+        label,
+        name.syntax().text_range(),
+        |builder| {
+            let def_file_sites = sites.remove(&ctx.file_id()).unwrap_or_default();
+            for (file_id, file_sites) in sites {
+                builder.edit_file(file_id);
+                let file_sites: Vec<_> = file_sites
+                    .into_iter()
+                    .map(|(site, path)| (site.make_mut(builder), path))
+                    .collect();
+                edit_sites(file_sites);
+            }
+
+            builder.edit_file(ctx.file_id());
+            let mut file_sites: Vec<_> = def_file_sites
+                .into_iter()
+                .map(|(site, path)| (site.make_mut(builder), path))
+                .collect();
+            let item = builder.make_mut(item);
+            match target {
+                Target::Local { ref pat, .. } | Target::Param { ref pat, .. } => {
+                    if let Some(let_stmt) = pat.syntax().parent().and_then(ast::LetStmt::cast) {
+                        let let_stmt = builder.make_mut(let_stmt);
+                        if let Some(ty) = let_stmt.ty() {
+                            file_sites.push((Site::Type(ty), enum_name.clone()));
+                        }
+                        file_sites
+                            .push((Site::Write(let_stmt.syntax().clone()), enum_name.clone()));
+                    }
+                    if let Some(param) = pat.syntax().parent().and_then(ast::Param::cast) {
+                        let ty = builder.make_mut(param.ty().unwrap());
+                        file_sites.push((Site::Type(ty), enum_name.clone()));
+                    }
+                }
+                Target::Field { ref field, .. } => {
+                    let ty = builder.make_mut(field.ty().unwrap());
+                    file_sites.push((Site::Type(ty), enum_name.clone()));
+                }
+            }
+            edit_sites(file_sites);
+
+            let indent = IndentLevel::from_node(item.syntax());
+            let variants = ["True", "False"].iter().map(|it| make::variant(make::name(it), None));
+            let enum_ = make::enum_(
+                target.visibility(),
+                make::name(&enum_name),
+                make::variant_list(variants),
+            )
+            .clone_for_update();
+            let derive = make::ext::derive_attr(["Debug", "Clone", "Copy", "PartialEq", "Eq"]);
+            ted::insert_all(
+                Position::first_child_of(enum_.syntax()),
+                vec![
+                    derive.clone_for_update().syntax().clone().into(),
+                    make::tokens::single_newline().into(),
+                ],
+            );
+            enum_.reindent_to(indent);
+            ted::insert_all(
+                Position::before(item.syntax()),
+                vec![
+                    enum_.syntax().clone().into(),
+                    make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                ],
+            );
+        },
+    )
+}
+
+/// Whether `name` already refers to a type where the enum is defined.
+fn type_name_taken(ctx: &AssistContext, node: &SyntaxNode, name: &str) -> bool {
+    let mut taken = false;
+    ctx.sema.scope(node).process_all_names(&mut |it, def| {
+        let is_type = matches!(
+            def,
+            ScopeDef::ModuleDef(
+                ModuleDef::Adt(_)
+                    | ModuleDef::Trait(_)
+                    | ModuleDef::TypeAlias(_)
+                    | ModuleDef::BuiltinType(_)
+                    | ModuleDef::Module(_)
+            ) | ScopeDef::GenericParam(_)
+        );
+        if is_type && it.to_smol_str() == name {
+            taken = true;
+        }
+    });
+    taken
+}
+
+enum Target {
+    Local { local: hir::Local, pat: ast::IdentPat },
+    Param { local: hir::Local, pat: ast::IdentPat, func: hir::Function, index: usize },
+    Field { def: hir::Field, field: ast::RecordField },
+}
+
+impl Target {
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Target::Local { pat, .. } | Target::Param { pat, .. } => pat.syntax(),
+            Target::Field { field, .. } => field.syntax(),
+        }
+    }
+
+    fn definition(&self) -> Definition {
+        match self {
+            Target::Local { local, .. } | Target::Param { local, .. } => Definition::Local(*local),
+            Target::Field { def, .. } => Definition::Field(*def),
+        }
+    }
+
+    /// The enum is visible wherever the function or field is.
+    fn visibility(&self) -> Option<ast::Visibility> {
+        match self {
+            Target::Local { .. } => None,
+            Target::Param { pat, .. } => {
+                pat.syntax().ancestors().find_map(ast::Fn::cast).and_then(|it| it.visibility())
+            }
+            Target::Field { field, .. } => field.visibility(),
+        }
+    }
+}
+
+fn find_target(ctx: &AssistContext, name: &ast::Name) -> Option<Target> {
+    let parent = name.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::IdentPat(pat) => {
+                if pat.at_token().is_some() || pat.ref_token().is_some() {
+                    return None;
+                }
+                if !ctx.sema.type_of_pat(&pat.clone().into())?.original.is_bool() {
+                    return None;
+                }
+                let local = ctx.sema.to_def(&pat)?;
+                let binding = pat.syntax().parent()?;
+                if ast::LetStmt::can_cast(binding.kind()) {
+                    return Some(Target::Local { local, pat });
+                }
+                let param = ast::Param::cast(binding)?;
+                param.ty()?;
+                let fn_ = param.syntax().ancestors().find_map(ast::Fn::cast)?;
+                let index = fn_.param_list()?.params().position(|it| it == param)?;
+                let func = ctx.sema.to_def(&fn_)?;
+                Some(Target::Param { local, pat, func, index })
+            },
+            ast::RecordField(field) => {
+                let def = ctx.sema.to_def(&field)?;
+                if !def.ty(ctx.db()).is_bool() {
+                    return None;
+                }
+                Some(Target::Field { def, field })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A place of the boolean in the code which changes with the conversion.
+enum Site {
+    /// A `bool` type annotation.
+    Type(ast::Type),
+    /// A place reading the value of the boolean, `flag` or `s.flag`.
+    Read(ast::Expr),
+    /// A place storing a value into it: a `let`, an assignment, a call argument or a record
+    /// field. The node owning the value is kept, as the value itself may contain reads.
+    Write(SyntaxNode),
+    /// Call arguments for a parameter, with the index of the argument.
+    Arg(ast::ArgList, usize),
+    /// A shorthand record field reading the boolean, `S { flag }`.
+    ShorthandRead(ast::RecordExprField),
+    /// A `true` or `false` pattern.
+    Pat(ast::LiteralPat),
+}
+
+impl Site {
+    fn make_mut(self, builder: &mut crate::assist_context::AssistBuilder) -> Site {
+        match self {
+            Site::Type(it) => Site::Type(builder.make_mut(it)),
+            Site::Read(it) => Site::Read(builder.make_mut(it)),
+            Site::Write(it) => Site::Write(builder.make_syntax_mut(it)),
+            Site::Arg(it, index) => Site::Arg(builder.make_mut(it), index),
+            Site::ShorthandRead(it) => Site::ShorthandRead(builder.make_mut(it)),
+            Site::Pat(it) => Site::Pat(builder.make_mut(it)),
+        }
+    }
+}
+
+/// Collects the sites of a reference to the boolean. Returns `None` for uses which can't be
+/// converted, like taking a reference to it.
+fn classify_usage(name: &ast::NameLike, is_field: bool, sites: &mut Vec<Site>) -> Option<()> {
+    let name_ref = match name {
+        ast::NameLike::NameRef(it) => it,
+        // A shorthand field pattern would bind the enum.
+        _ => return None,
+    };
+    if let Some(field) = ast::RecordExprField::for_field_name(name_ref) {
+        if is_field {
+            sites.push(Site::Write(field.syntax().clone()));
+            return Some(());
+        }
+        if field.name_ref().is_none() {
+            sites.push(Site::ShorthandRead(field));
+            return Some(());
+        }
+    }
+    if let Some(field) = ast::RecordPatField::for_field_name_ref(name_ref) {
+        match field.pat()? {
+            ast::Pat::LiteralPat(it) => sites.push(Site::Pat(it)),
+            ast::Pat::WildcardPat(_) => (),
+            _ => return None,
+        }
+        return Some(());
+    }
+    let expr = name_ref.syntax().ancestors().find_map(ast::Expr::cast)?;
+    if !matches!(expr, ast::Expr::PathExpr(_) | ast::Expr::FieldExpr(_)) {
+        return None;
+    }
+    let parent = expr.syntax().parent()?;
+    let site = match_ast! {
+        match parent {
+            ast::BinExpr(bin) => match bin.op_kind()? {
+                ast::BinaryOp::Assignment { op: None } if bin.lhs()? == expr => {
+                    Site::Write(bin.syntax().clone())
+                },
+                ast::BinaryOp::Assignment { .. } if bin.lhs()? == expr => return None,
+                _ => Site::Read(expr),
+            },
+            ast::RefExpr(_) => return None,
+            ast::PrefixExpr(prefix) => match prefix.op_kind()? {
+                ast::UnaryOp::Not => Site::Read(prefix.into()),
+                _ => return None,
+            },
+            _ => Site::Read(expr),
+        }
+    };
+    sites.push(site);
+    Some(())
+}
+
+fn call_site(
+    ctx: &AssistContext,
+    name: &ast::NameLike,
+    func: hir::Function,
+    index: usize,
+) -> Option<Site> {
+    let name_ref = name.as_name_ref()?;
+    if let Some(call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        return Some(Site::Arg(call.arg_list()?, index));
+    }
+    let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    if call.expr()?.syntax() != path_expr.syntax() {
+        return None;
+    }
+    let index = if func.self_param(ctx.db()).is_some() { index + 1 } else { index };
+    Some(Site::Arg(call.arg_list()?, index))
+}
+
+/// Edits the sites, each with the path to the enum from there.
+fn edit_sites(mut sites: Vec<(Site, String)>) {
+    // Reads are contained in the values of writes, so they are converted first, and nested
+    // writes are converted before the ones containing them.
+    sites.sort_by_key(|(site, _)| match site {
+        Site::Write(it) => (1, it.text_range().len()),
+        Site::Arg(it, _) => (1, it.syntax().text_range().len()),
+        _ => (0, 0.into()),
+    });
+    for (site, enum_path) in sites {
+        let variant = |value: bool| {
+            let variant = if value { "True" } else { "False" };
+            make::path_from_text(&format!("{}::{}", enum_path, variant))
+        };
+        match site {
+            Site::Type(ty) => {
+                let new_ty = make::ty(&enum_path).clone_for_update();
+                ted::replace(ty.syntax(), new_ty.syntax());
+            }
+            Site::Read(expr) => {
+                let (read, value) = match &expr {
+                    ast::Expr::PrefixExpr(prefix) => match prefix.expr() {
+                        Some(inner) => (inner, false),
+                        None => continue,
+                    },
+                    _ => (expr.clone(), true),
+                };
+                let eq = compare(read, variant(value));
+                let eq = if needs_parens(&expr) { make::expr_paren(eq) } else { eq };
+                ted::replace(expr.syntax(), eq.clone_for_update().syntax());
+            }
+            Site::ShorthandRead(field) => {
+                let (name, expr) = match (field.field_name(), field.expr()) {
+                    (Some(name), Some(expr)) => (name, expr),
+                    _ => continue,
+                };
+                let new_field = make::record_expr_field(name, Some(compare(expr, variant(true))));
+                ted::replace(field.syntax(), new_field.clone_for_update().syntax());
+            }
+            Site::Write(node) => {
+                let value = match_ast! {
+                    match node {
+                        ast::LetStmt(it) => it.initializer(),
+                        ast::BinExpr(it) => it.rhs(),
+                        ast::RecordExprField(it) => {
+                            // `S { flag }` becomes `S { flag: if flag { .. } else { .. } }`.
+                            if it.name_ref().is_none() {
+                                if let Some(name) = it.field_name() {
+                                    ted::insert_all_raw(
+                                        Position::first_child_of(it.syntax()),
+                                        vec![
+                                            make::name_ref(&name.text()).clone_for_update().syntax().clone().into(),
+                                            make::token(T![:]).into(),
+                                            make::tokens::single_space().into(),
+                                        ],
+                                    );
+                                }
+                            }
+                            it.expr()
+                        },
+                        _ => None,
+                    }
+                };
+                if let Some(value) = value {
+                    convert_value(value, &variant);
+                }
+            }
+            Site::Arg(arg_list, index) => {
+                if let Some(value) = arg_list.args().nth(index) {
+                    convert_value(value, &variant);
+                }
+            }
+            Site::Pat(pat) => {
+                let value = match pat.literal().map(|it| it.kind()) {
+                    Some(ast::LiteralKind::Bool(value)) => value,
+                    _ => continue,
+                };
+                let new_pat = make::path_pat(variant(value)).clone_for_update();
+                ted::replace(pat.syntax(), new_pat.syntax());
+            }
+        }
+    }
+}
+
+fn compare(expr: ast::Expr, variant: ast::Path) -> ast::Expr {
+    make::expr_bin_op(
+        expr,
+        ast::BinaryOp::CmpOp(ast::CmpOp::Eq { negated: false }),
+        make::expr_path(variant),
+    )
+}
+
+/// Replaces a boolean value by the corresponding variant.
+fn convert_value(value: ast::Expr, variant: &dyn Fn(bool) -> ast::Path) {
+    let new_value = match &value {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::Bool(value) => make::expr_path(variant(value)),
+            _ => return,
+        },
+        _ => {
+            let block = |value| make::block_expr(None, Some(make::expr_path(variant(value))));
+            make::expr_if(value.clone(), block(true), Some(ast::ElseBranch::Block(block(false))))
+        }
+    };
+    let new_value = new_value.clone_for_update();
+    new_value.reindent_to(IndentLevel::from_node(value.syntax()));
+    ted::replace(value.syntax(), new_value.syntax());
+}
+
+/// Whether the comparison replacing `expr` has to be parenthesized.
+fn needs_parens(expr: &ast::Expr) -> bool {
+    let parent = match expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    match parent {
+        ast::Expr::BinExpr(bin) => !matches!(
+            bin.op_kind(),
+            Some(ast::BinaryOp::LogicOp(_)) | Some(ast::BinaryOp::Assignment { .. })
+        ),
+        ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::PrefixExpr(_)
+        | ast::Expr::CastExpr(_)
+        | ast::Expr::RefExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::IndexExpr(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_local() {
+        check_assist(
+            convert_bool_to_enum,
+            r#"
+fn main() {
+    let mut $0is_done = false;
+    if !is_done && 1 > 2 {
+        is_done = 2 > 1;
+    }
+    let done = is_done;
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IsDone {
+    True,
+    False,
+}
+
+fn main() {
+    let mut is_done = IsDone::False;
+    if is_done == IsDone::False && 1 > 2 {
+        is_done = if 2 > 1 {
+            IsDone::True
+        } else {
+            IsDone::False
+        };
+    }
+    let done = is_done == IsDone::True;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_local_in_shorthand_field() {
+        check_assist(
+            convert_bool_to_enum,
+            r#"
+struct S { flag: bool }
+fn main() {
+    let $0flag = true;
+    let s = S { flag };
+}
+"#,
+            r#"
+struct S { flag: bool }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flag {
+    True,
+    False,
+}
+
+fn main() {
+    let flag = Flag::True;
+    let s = S { flag: flag == Flag::True };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_param() {
+        check_assist(
+            convert_bool_to_enum,
+            r#"
+pub fn run(name: &str, $0verbose: bool) {
+    if verbose {
+        let _ = name;
+    }
+}
+fn main() {
+    run("a", true);
+    run("b", 1 == 2);
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbose {
+    True,
+    False,
+}
+
+pub fn run(name: &str, verbose: Verbose) {
+    if verbose == Verbose::True {
+        let _ = name;
+    }
+}
+fn main() {
+    run("a", Verbose::True);
+    run("b", if 1 == 2 {
+        Verbose::True
+    } else {
+        Verbose::False
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_method_param() {
+        check_assist(
+            convert_bool_to_enum,
+            r#"
+struct S;
+impl S {
+    fn f(&self, $0fast: bool) -> bool {
+        fast
+    }
+}
+fn main() {
+    S.f(false);
+    S::f(&S, true);
+}
+"#,
+            r#"
+struct S;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fast {
+    True,
+    False,
+}
+
+impl S {
+    fn f(&self, fast: Fast) -> bool {
+        fast == Fast::True
+    }
+}
+fn main() {
+    S.f(Fast::False);
+    S::f(&S, Fast::True);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_field() {
+        check_assist(
+            convert_bool_to_enum,
+            r#"
+//- /main.rs
+mod foo;
+use foo::Config;
+fn main() {
+    let cfg = Config { enabled: true };
+    match cfg {
+        Config { enabled: true } => {}
+        Config { enabled: false } => {}
+    }
+}
+//- /foo.rs
+pub struct Config {
+    pub $0enabled: bool,
+}
+fn check(cfg: &Config) -> bool {
+    !cfg.enabled
+}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+use foo::Config;
+fn main() {
+    let cfg = Config { enabled: foo::Enabled::True };
+    match cfg {
+        Config { enabled: foo::Enabled::True } => {}
+        Config { enabled: foo::Enabled::False } => {}
+    }
+}
+//- /foo.rs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enabled {
+    True,
+    False,
+}
+
+pub struct Config {
+    pub enabled: Enabled,
+}
+fn check(cfg: &Config) -> bool {
+    cfg.enabled == Enabled::False
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_bool() {
+        check_assist_not_applicable(
+            convert_bool_to_enum,
+            r#"
+fn main() {
+    let $0x = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_referenced() {
+        check_assist_not_applicable(
+            convert_bool_to_enum,
+            r#"
+fn take(_: &bool) {}
+fn main() {
+    let $0x = true;
+    take(&x);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_enum_name_is_taken() {
+        check_assist_not_applicable(
+            convert_bool_to_enum,
+            r#"
+struct Verbose;
+fn main() {
+    let $0verbose = true;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_uses_in_macro_calls() {
+        check_assist_by_label(
+            convert_bool_to_enum,
+            r#"
+macro_rules! id { ($e:expr) => { $e }; }
+fn main() {
+    let $0verbose = true;
+    let _ = id!(verbose);
+}
+"#,
+            r#"
+macro_rules! id { ($e:expr) => { $e }; }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verbose {
+    True,
+    False,
+}
+
+fn main() {
+    let verbose = Verbose::True;
+    let _ = id!(verbose);
+}
+"#,
+            "Convert boolean to enum, leaving 1 use in macro calls",
+        );
+    }
+}
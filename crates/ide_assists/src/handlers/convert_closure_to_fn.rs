@@ -0,0 +1,746 @@
+use hir::{HirDisplay, PathResolution, ScopeDef};
+use ide_db::{
+    base_db::FileRange,
+    defs::Definition,
+    search::{FileReference, SearchScope},
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, HasGenericParams, HasName,
+    },
+    ted, AstNode, SyntaxNode, TextRange, T,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists, TreeMutator},
+    handlers::extract_function::expr_require_exclusive_access,
+    AssistId, AssistKind, GroupLabel,
+};
+
+// Assist: convert_closure_to_fn
+//
+// Converts a closure into a named function. Captured variables become parameters, passed by
+// a wrapping closure.
+//
+// ```
+// fn main() {
+//     let square = $0|x: u64| x * x;
+//     square(3);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     fn square(x: u64) -> u64 {
+//         x * x
+//     }
+//     square(3);
+// }
+// ```
+pub(crate) fn convert_closure_to_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let closure = ctx.find_node_at_offset::<ast::ClosureExpr>()?;
+    let body = closure.body()?;
+    // Only offered on the header of the closure, not from deep inside its body.
+    if ctx.offset() > body.syntax().text_range().start() {
+        return None;
+    }
+    if closure.async_token().is_some() || closure.static_token().is_some() {
+        return None;
+    }
+    let module = ctx.sema.scope(closure.syntax()).module()?;
+    let callable = ctx
+        .sema
+        .type_of_expr(&ast::Expr::ClosureExpr(closure.clone()))?
+        .original
+        .as_callable(ctx.db())?;
+    let generic_params = outer_generic_params(closure.syntax());
+    let display = |ty: &hir::Type| {
+        if ty.contains_unknown() {
+            return None;
+        }
+        let ty = ty.display_source_code(ctx.db(), module.into()).ok()?;
+        // Items can't refer to the generic parameters of the items around them.
+        let uses_generics = make::ty(&ty)
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|it| generic_params.contains(&it.to_string()));
+        (!uses_generics).then(|| ty)
+    };
+
+    let closure_params: Vec<_> = closure.param_list()?.params().collect();
+    let types = callable.params(ctx.db());
+    if closure_params.len() != types.len() {
+        return None;
+    }
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for (idx, (param, (_, ty))) in closure_params.iter().zip(&types).enumerate() {
+        let pat = param.pat()?;
+        let ty = match param.ty() {
+            Some(it) => it.to_string(),
+            None => display(ty)?,
+        };
+        params.push(format!("{}: {}", pat, ty));
+        args.push(match &pat {
+            ast::Pat::IdentPat(it) if it.ref_token().is_none() && it.mut_token().is_none() => {
+                it.name()?.to_string()
+            }
+            _ => format!("arg{}", idx),
+        });
+    }
+    let ret_ty = match closure.ret_type().and_then(|it| it.ty()) {
+        Some(it) => Some(it.to_string()),
+        None => {
+            let ty = callable.return_type();
+            if ty.is_unit() {
+                None
+            } else {
+                Some(display(&ty)?)
+            }
+        }
+    };
+
+    let captures = captures(ctx, &closure, &body)?;
+    for capture in &captures {
+        params.push(format!("{}: {}", capture.name, capture.kind.param_ty(&display(&capture.ty)?)));
+    }
+
+    let let_stmt = closure.syntax().parent().and_then(ast::LetStmt::cast).filter(|it| {
+        matches!(it.pat(), Some(ast::Pat::IdentPat(pat)) if pat.is_simple_ident())
+            && it.ty().is_none()
+    });
+    let group = GroupLabel("Convert closure to function".to_owned());
+    for target in [Target::Nested, Target::Module] {
+        let anchor = match target.anchor(closure.syntax()) {
+            Some(it) => it,
+            None => continue,
+        };
+        // A closure bound with `let` is called like a function of the same name.
+        let replaced_let = let_stmt
+            .as_ref()
+            .filter(|_| captures.is_empty() && matches!(target, Target::Nested))
+            .filter(|it| it.syntax() == &anchor);
+        let name = match replaced_let.and_then(|it| it.pat()) {
+            Some(ast::Pat::IdentPat(pat)) => pat.name()?.to_string(),
+            _ => "fun_name".to_owned(),
+        };
+        let label = match target {
+            Target::Nested => "Convert closure to nested function",
+            Target::Module => "Convert closure to module-level function",
+        };
+        acc.add_group(
+            &group,
+            AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+            label,
+            closure.syntax().text_range(),
+            |builder| {
+                let indent = IndentLevel::from_node(&anchor);
+                let body = fn_body(&body, &captures).reset_indent().indent(indent);
+                let mut fun = String::from("fn ");
+                if ctx.config.snippet_cap.is_some() && name == "fun_name" {
+                    fun.push_str("$0");
+                }
+                format_to!(fun, "{}({})", name, params.iter().format(", "));
+                if let Some(ret_ty) = &ret_ty {
+                    format_to!(fun, " -> {}", ret_ty);
+                }
+                format_to!(fun, " {}", body);
+
+                let (range, text) = match replaced_let {
+                    Some(let_stmt) => (let_stmt.syntax().text_range(), fun),
+                    None => {
+                        let call = if captures.is_empty() {
+                            name.clone()
+                        } else {
+                            let move_ = if closure.move_token().is_some() { "move " } else { "" };
+                            let capture_args = captures.iter().map(Capture::arg);
+                            format!(
+                                "{}|{}| {}({})",
+                                move_,
+                                args.iter().format(", "),
+                                name,
+                                args.iter().cloned().chain(capture_args).format(", ")
+                            )
+                        };
+                        match target {
+                            Target::Nested => {
+                                let offset = anchor.text_range().start();
+                                // The closure may be the statement the function is inserted before.
+                                if offset == closure.syntax().text_range().start() {
+                                    (
+                                        closure.syntax().text_range(),
+                                        format!("{}\n{}{}", fun, indent, call),
+                                    )
+                                } else {
+                                    builder.replace(closure.syntax().text_range(), call);
+                                    (TextRange::empty(offset), format!("{}\n{}", fun, indent))
+                                }
+                            }
+                            Target::Module => {
+                                builder.replace(closure.syntax().text_range(), call);
+                                let offset = anchor.text_range().end();
+                                (TextRange::empty(offset), format!("\n\n{}{}", indent, fun))
+                            }
+                        }
+                    }
+                };
+                match ctx.config.snippet_cap {
+                    Some(cap) => builder.replace_snippet(cap, range, text),
+                    None => builder.replace(range, text),
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+// Assist: convert_fn_to_closure
+//
+// Inlines a function passed as an argument into a closure.
+//
+// ```
+// fn double(x: i32) -> i32 { x * 2 }
+// fn main() {
+//     let v: Vec<i32> = vec![1, 2];
+//     v.into_iter().map(double$0);
+// }
+// ```
+// ->
+// ```
+// fn double(x: i32) -> i32 { x * 2 }
+// fn main() {
+//     let v: Vec<i32> = vec![1, 2];
+//     v.into_iter().map(|x: i32| x * 2);
+// }
+// ```
+pub(crate) fn convert_fn_to_closure(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_expr = ctx.find_node_at_offset::<ast::PathExpr>()?;
+    if !path_expr.syntax().parent().map_or(false, |it| ast::ArgList::can_cast(it.kind())) {
+        return None;
+    }
+    let func = match ctx.sema.resolve_path(&path_expr.path()?)? {
+        PathResolution::Def(hir::ModuleDef::Function(it)) => it,
+        _ => return None,
+    };
+    if func.self_param(ctx.db()).is_some() || func.is_async(ctx.db()) || func.is_unsafe(ctx.db()) {
+        return None;
+    }
+    // The body refers to items as seen from the module of the function.
+    let module = ctx.sema.scope(path_expr.syntax()).module()?;
+    if func.module(ctx.db()) != module {
+        return None;
+    }
+    let source = ctx.sema.source(func)?;
+    if source.file_id != ctx.file_id().into() {
+        return None;
+    }
+    let fn_ = source.value;
+    if fn_.generic_param_list().is_some() {
+        return None;
+    }
+    let body = fn_.body()?;
+    let is_recursive = body.syntax().descendants().filter_map(ast::Path::cast).any(|path| {
+        matches!(
+            ctx.sema.resolve_path(&path),
+            Some(PathResolution::Def(hir::ModuleDef::Function(it))) if it == func
+        )
+    });
+    if is_recursive {
+        return None;
+    }
+    // The types are kept, the closure's caller may not be enough to infer them.
+    let params: Vec<_> = fn_.param_list()?.params().collect();
+
+    let target = path_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_fn_to_closure", AssistKind::RefactorInline),
+        format!("Inline `{}` into closure", func.name(ctx.db())),
+        target,
+        |builder| {
+            let stmt_list = body.stmt_list();
+            let tail = stmt_list
+                .as_ref()
+                .filter(|it| it.statements().next().is_none())
+                .and_then(|it| it.tail_expr());
+            let body = match tail {
+                Some(it) => it.to_string(),
+                None => body
+                    .reset_indent()
+                    .indent(IndentLevel::from_node(path_expr.syntax()))
+                    .to_string(),
+            };
+            builder.replace(target, format!("|{}| {}", params.iter().format(", "), body));
+        },
+    )
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    /// A function declared in the block the closure is in.
+    Nested,
+    /// A function following the item the closure is in.
+    Module,
+}
+
+impl Target {
+    /// The statement the new function is inserted before, or the item it's inserted after.
+    fn anchor(self, closure: &SyntaxNode) -> Option<SyntaxNode> {
+        match self {
+            Target::Nested => closure
+                .ancestors()
+                .find(|it| it.parent().map_or(false, |it| ast::StmtList::can_cast(it.kind()))),
+            Target::Module => closure.ancestors().find(|it| {
+                it.parent().map_or(false, |parent| {
+                    ast::SourceFile::can_cast(parent.kind())
+                        || ast::ItemList::can_cast(parent.kind())
+                })
+            }),
+        }
+    }
+}
+
+struct Capture {
+    name: String,
+    ty: hir::Type,
+    kind: CaptureKind,
+    usages: Vec<FileReference>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaptureKind {
+    Value,
+    SharedRef,
+    MutRef,
+}
+
+impl CaptureKind {
+    fn param_ty(self, ty: &str) -> String {
+        match self {
+            CaptureKind::Value => ty.to_owned(),
+            CaptureKind::SharedRef => format!("&{}", ty),
+            CaptureKind::MutRef => format!("&mut {}", ty),
+        }
+    }
+}
+
+impl Capture {
+    fn arg(&self) -> String {
+        match self.kind {
+            CaptureKind::Value => self.name.clone(),
+            CaptureKind::SharedRef => format!("&{}", self.name),
+            CaptureKind::MutRef => format!("&mut {}", self.name),
+        }
+    }
+}
+
+/// The locals from outside the closure its body uses, in the order of their first use.
+fn captures(
+    ctx: &AssistContext,
+    closure: &ast::ClosureExpr,
+    body: &ast::Expr,
+) -> Option<Vec<Capture>> {
+    let mut locals = Vec::new();
+    ctx.sema.scope(closure.syntax()).process_all_names(&mut |_, def| {
+        if let ScopeDef::Local(local) = def {
+            locals.push(local);
+        }
+    });
+    let search_scope = SearchScope::file_range(FileRange {
+        file_id: ctx.file_id(),
+        range: body.syntax().text_range(),
+    });
+    let mut captures = Vec::new();
+    for local in locals {
+        let usages: Vec<_> = Definition::Local(local)
+            .usages(&ctx.sema)
+            .in_scope(search_scope.clone())
+            .all()
+            .references
+            .remove(&ctx.file_id())
+            .unwrap_or_default();
+        if usages.is_empty() {
+            continue;
+        }
+        if local.is_self(ctx.db()) {
+            return None;
+        }
+        let ty = local.ty(ctx.db());
+        let exprs: Vec<_> = usages.iter().filter_map(usage_expr).collect();
+        let is_exclusive =
+            exprs.iter().any(|it| expr_require_exclusive_access(ctx, it).unwrap_or(false));
+        // A closure using a capture by value takes it by value as well.
+        let is_moved = exprs.iter().any(|it| moves_value(ctx, it));
+        let kind = if ty.is_reference() || is_moved || (ty.is_copy(ctx.db()) && !is_exclusive) {
+            CaptureKind::Value
+        } else if is_exclusive {
+            CaptureKind::MutRef
+        } else {
+            CaptureKind::SharedRef
+        };
+        let name = local.name(ctx.db()).to_string();
+        captures.push(Capture { name, ty, kind, usages });
+    }
+    captures.sort_by_key(|it| it.usages.iter().map(|usage| usage.range.start()).min());
+    Some(captures)
+}
+
+/// Whether `expr` is used by value, moving it or one of its fields out.
+fn moves_value(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let ty = match ctx.sema.type_of_expr(expr) {
+        Some(it) => it.original,
+        None => return true,
+    };
+    if ty.is_reference() || ty.is_copy(ctx.db()) {
+        return false;
+    }
+    let parent = match expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(it) => it,
+        None => return true,
+    };
+    match &parent {
+        ast::Expr::ParenExpr(_) | ast::Expr::FieldExpr(_) => moves_value(ctx, &parent),
+        ast::Expr::MethodCallExpr(call) if call.receiver().as_ref() == Some(expr) => {
+            let self_param =
+                ctx.sema.resolve_method_call(call).and_then(|it| it.self_param(ctx.db()));
+            self_param.map_or(true, |it| matches!(it.access(ctx.db()), hir::Access::Owned))
+        }
+        ast::Expr::IndexExpr(it) => it.base().as_ref() != Some(expr),
+        ast::Expr::CallExpr(it) => it.expr().as_ref() != Some(expr),
+        ast::Expr::RefExpr(_) => false,
+        ast::Expr::BinExpr(it) => match it.op_kind() {
+            Some(ast::BinaryOp::CmpOp(_)) => false,
+            Some(ast::BinaryOp::Assignment { .. }) => it.lhs().as_ref() != Some(expr),
+            _ => true,
+        },
+        _ => true,
+    }
+}
+
+/// The path expression of a usage of a local, if it isn't inside a macro call.
+fn usage_expr(usage: &FileReference) -> Option<ast::Expr> {
+    let name_ref = usage.name.as_name_ref()?;
+    let path = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path();
+    let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+    let in_file = path_expr.syntax().ancestors().any(|it| ast::SourceFile::can_cast(it.kind()));
+    in_file.then(|| ast::Expr::PathExpr(path_expr))
+}
+
+/// The body of the function, dereferencing the captures passed by reference.
+fn fn_body(body: &ast::Expr, captures: &[Capture]) -> ast::BlockExpr {
+    let tm = TreeMutator::new(body.syntax());
+    let mut derefs = Vec::new();
+    let mut shorthands = Vec::new();
+    for capture in captures.iter().filter(|it| it.kind != CaptureKind::Value) {
+        for usage in &capture.usages {
+            if let Some(expr) = usage_expr(usage) {
+                derefs.push((capture, tm.make_mut(&expr)));
+                continue;
+            }
+            let field = usage
+                .name
+                .as_name_ref()
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::RecordExprField::cast)
+                .filter(|it| it.expr().is_none());
+            if let Some(field) = field {
+                shorthands.push((capture, tm.make_mut(&field)));
+            }
+        }
+    }
+    let body = tm.make_mut(body);
+    for (capture, expr) in derefs {
+        let name = make::expr_path(make::ext::ident_path(&capture.name));
+        match expr.syntax().parent().and_then(ast::Expr::cast) {
+            // Those auto-dereference.
+            Some(ast::Expr::MethodCallExpr(_) | ast::Expr::FieldExpr(_)) => (),
+            Some(ast::Expr::IndexExpr(it)) if it.base().as_ref() == Some(&expr) => (),
+            Some(ast::Expr::CallExpr(it)) if it.expr().as_ref() == Some(&expr) => (),
+            Some(ast::Expr::RefExpr(it))
+                if it.mut_token().is_some() == (capture.kind == CaptureKind::MutRef) =>
+            {
+                ted::replace(it.syntax(), name.clone_for_update().syntax())
+            }
+            _ => ted::replace(
+                expr.syntax(),
+                make::expr_prefix(T![*], name).clone_for_update().syntax(),
+            ),
+        }
+    }
+    for (capture, field) in shorthands {
+        if let Some(name_ref) = field.name_ref() {
+            let value =
+                make::expr_prefix(T![*], make::expr_path(make::ext::ident_path(&capture.name)));
+            ted::replace(
+                field.syntax(),
+                make::record_expr_field(name_ref, Some(value)).clone_for_update().syntax(),
+            );
+        }
+    }
+    match body {
+        ast::Expr::BlockExpr(it) if it.modifier().is_none() => it,
+        expr => make::block_expr(None, Some(expr)),
+    }
+}
+
+/// The names of the generic parameters of the items around `node`.
+fn outer_generic_params(node: &SyntaxNode) -> FxHashSet<String> {
+    node.ancestors()
+        .filter_map(|it| {
+            ast::Fn::cast(it.clone())
+                .and_then(|it| it.generic_param_list())
+                .or_else(|| ast::Impl::cast(it.clone()).and_then(|it| it.generic_param_list()))
+                .or_else(|| ast::Trait::cast(it).and_then(|it| it.generic_param_list()))
+        })
+        .flat_map(|it| it.type_or_const_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn closure_without_captures() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+//- minicore: fn
+fn apply(f: impl Fn(i32) -> i32) -> i32 { f(1) }
+fn main() {
+    apply($0|x| {
+        let y = x + 1;
+        y * 2
+    });
+}
+"#,
+            r#"
+fn apply(f: impl Fn(i32) -> i32) -> i32 { f(1) }
+fn main() {
+    apply(fun_name);
+}
+
+fn $0fun_name(x: i32) -> i32 {
+    let y = x + 1;
+    y * 2
+}
+"#,
+            "Convert closure to module-level function",
+        );
+    }
+
+    #[test]
+    fn closure_with_captures() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+//- minicore: fn, copy
+struct Stats { count: usize }
+struct Names;
+impl Names { fn len(&self) -> usize { 0 } }
+fn each(f: impl FnMut(u32)) {}
+fn main() {
+    let mut total = 0;
+    let names = Names;
+    let offset = 2;
+    each($0|n| {
+        total += n + offset;
+        names.len();
+        let s = Stats { count: names.len() };
+    });
+}
+"#,
+            r#"
+struct Stats { count: usize }
+struct Names;
+impl Names { fn len(&self) -> usize { 0 } }
+fn each(f: impl FnMut(u32)) {}
+fn main() {
+    let mut total = 0;
+    let names = Names;
+    let offset = 2;
+    fn $0fun_name(n: u32, total: &mut u32, offset: u32, names: &Names) {
+        *total += n + offset;
+        names.len();
+        let s = Stats { count: names.len() };
+    }
+    each(|n| fun_name(n, &mut total, offset, &names));
+}
+"#,
+            "Convert closure to nested function",
+        );
+    }
+
+    #[test]
+    fn moved_capture_is_passed_by_value() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+//- minicore: fn, copy
+struct Names;
+impl Names {
+    fn len(&self) -> usize { 0 }
+    fn into_first(self) -> u32 { 0 }
+}
+fn take(names: Names) {}
+fn main() {
+    let names = Names;
+    let other = Names;
+    let f = $0|| {
+        other.len();
+        take(names);
+        other.into_first()
+    };
+}
+"#,
+            r#"
+struct Names;
+impl Names {
+    fn len(&self) -> usize { 0 }
+    fn into_first(self) -> u32 { 0 }
+}
+fn take(names: Names) {}
+fn main() {
+    let names = Names;
+    let other = Names;
+    fn $0fun_name(other: Names, names: Names) -> u32 {
+        other.len();
+        take(names);
+        other.into_first()
+    }
+    let f = || fun_name(other, names);
+}
+"#,
+            "Convert closure to nested function",
+        );
+    }
+
+    #[test]
+    fn returned_move_closure() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+//- minicore: fn, copy
+fn adder(k: i32) -> impl Fn(i32) -> i32 {
+    move $0|x: i32| x + k
+}
+"#,
+            r#"
+fn adder(k: i32) -> impl Fn(i32) -> i32 {
+    fn $0fun_name(x: i32, k: i32) -> i32 {
+        x + k
+    }
+    move |x| fun_name(x, k)
+}
+"#,
+            "Convert closure to nested function",
+        );
+    }
+
+    #[test]
+    fn closure_bound_with_let() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let square = $0|x: u64| x * x;
+    square(3);
+}
+"#,
+            r#"
+fn main() {
+    fn square(x: u64) -> u64 {
+        x * x
+    }
+    square(3);
+}
+"#,
+            "Convert closure to nested function",
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_outer_generics_or_self() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn f<T: Copy>(t: T) {
+    let g = $0|| t;
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+struct S(u32);
+impl S {
+    fn f(&self) {
+        let g = $0|| self.0;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_fn_into_closure() {
+        check_assist(
+            convert_fn_to_closure,
+            r#"
+fn apply(f: impl Fn(i32) -> i32) -> i32 { f(1) }
+fn shift(x: i32) -> i32 {
+    let y = x + 1;
+    y << 2
+}
+fn main() {
+    apply(shi$0ft);
+}
+"#,
+            r#"
+fn apply(f: impl Fn(i32) -> i32) -> i32 { f(1) }
+fn shift(x: i32) -> i32 {
+    let y = x + 1;
+    y << 2
+}
+fn main() {
+    apply(|x: i32| {
+        let y = x + 1;
+        y << 2
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_recursive_fn_or_calls() {
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn apply(f: impl Fn(u32) -> u32) -> u32 { f(1) }
+fn fact(n: u32) -> u32 { if n == 0 { 1 } else { n * fact(n - 1) } }
+fn main() {
+    apply(fact$0);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn main() {
+    double$0(1);
+}
+"#,
+        );
+    }
+}
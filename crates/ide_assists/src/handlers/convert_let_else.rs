@@ -0,0 +1,561 @@
+use ide_db::syntax_helpers::node_ext::{is_pattern_cond, single_let};
+use itertools::Itertools;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        HasName,
+    },
+    AstNode, SyntaxNode, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_to_let_else
+//
+// Converts a `let` whose value is extracted by a `match` or an `if let` with a diverging
+// fallback into a `let`-`else`.
+//
+// ```
+// fn main(opt: Option<u32>) {
+//     let x = $0match opt {
+//         Some(x) => x,
+//         _ => return,
+//     };
+// }
+// ```
+// ->
+// ```
+// fn main(opt: Option<u32>) {
+//     let Some(x) = opt else { return };
+// }
+// ```
+pub(crate) fn convert_to_let_else(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.let_else().is_some() || let_stmt.ty().is_some() {
+        return None;
+    }
+    let let_pat = let_stmt.pat()?;
+    let init = let_stmt.initializer()?;
+    // Don't offer the assist from deep inside the value.
+    let head =
+        TextRange::new(let_stmt.syntax().text_range().start(), init.syntax().text_range().start());
+    let on_keyword = match &init {
+        ast::Expr::MatchExpr(it) => it.match_token(),
+        ast::Expr::IfExpr(it) => it.if_token(),
+        _ => None,
+    };
+    let on_head = head.contains_inclusive(ctx.offset())
+        || on_keyword.map_or(false, |it| it.text_range().contains_inclusive(ctx.offset()));
+    if !on_head {
+        return None;
+    }
+
+    let Extraction { pat, scrutinee, value, diverging } = match &init {
+        ast::Expr::MatchExpr(it) => from_match(ctx, it)?,
+        ast::Expr::IfExpr(it) => from_if_let(ctx, it)?,
+        _ => return None,
+    };
+    if !let_else_supported(ctx, let_stmt.syntax()) {
+        return None;
+    }
+
+    // The bindings of the pattern, renamed to those of the `let`.
+    let mut renames = Vec::new();
+    match_bindings(&value, &let_pat, &mut renames)?;
+    let bindings = bindings(ctx, &pat);
+    if bindings.len() != renames.len() {
+        return None;
+    }
+    let mut edits = Vec::new();
+    for binding in &bindings {
+        if binding.ref_token().is_some() || binding.at_token().is_some() {
+            return None;
+        }
+        let name = binding.name()?.to_string();
+        let (_, new_binding) = renames.iter().find(|(it, _)| *it == name)?;
+        edits.push((binding.syntax().text_range(), new_binding.to_string()));
+    }
+    let pat_start = pat.syntax().text_range().start();
+    let mut new_pat = pat.syntax().to_string();
+    for (range, text) in edits.into_iter().rev() {
+        let range = range - pat_start;
+        new_pat.replace_range(usize::from(range.start())..usize::from(range.end()), &text);
+    }
+
+    let indent = IndentLevel::from_node(let_stmt.syntax());
+    let else_block = match diverging {
+        ast::Expr::BlockExpr(block) => block.reset_indent().indent(indent).to_string(),
+        expr => format!("{{ {} }}", expr),
+    };
+    // An expression ending with `}` would be ambiguous before `else`.
+    let scrutinee = match scrutinee.syntax().last_token() {
+        Some(it) if it.kind() == syntax::T!['}'] => format!("({})", scrutinee),
+        _ => scrutinee.to_string(),
+    };
+
+    acc.add(
+        AssistId("convert_to_let_else", AssistKind::RefactorRewrite),
+        "Convert to let-else",
+        let_stmt.syntax().text_range(),
+        |builder| {
+            builder.replace(
+                let_stmt.syntax().text_range(),
+                format!("let {} = {} else {};", new_pat, scrutinee, else_block),
+            )
+        },
+    )
+}
+
+// Assist: convert_let_else_to_match
+//
+// Converts a `let`-`else` into a `let` whose value is extracted by a `match`.
+//
+// ```
+// fn main(opt: Option<u32>) {
+//     $0let Some(x) = opt else { return };
+// }
+// ```
+// ->
+// ```
+// fn main(opt: Option<u32>) {
+//     let x = match opt {
+//         Some(x) => x,
+//         _ => return,
+//     };
+// }
+// ```
+pub(crate) fn convert_let_else_to_match(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let let_else = let_stmt.let_else()?;
+    let else_block = let_else.block_expr()?;
+    if else_block.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let pat = let_stmt.pat()?;
+    let scrutinee = let_stmt.initializer()?;
+    let bindings = bindings(ctx, &pat);
+    if bindings.is_empty() {
+        return None;
+    }
+
+    // `mut` moves from the pattern to the new `let`.
+    let pat_start = pat.syntax().text_range().start();
+    let mut arm_pat = pat.syntax().to_string();
+    let mut_ranges: Vec<_> = bindings
+        .iter()
+        .filter_map(|binding| {
+            let mut_token = binding.mut_token()?;
+            let end = match mut_token.next_token() {
+                Some(ws) if ws.kind() == syntax::SyntaxKind::WHITESPACE => ws.text_range().end(),
+                _ => mut_token.text_range().end(),
+            };
+            Some(TextRange::new(mut_token.text_range().start(), end) - pat_start)
+        })
+        .collect();
+    for range in mut_ranges.into_iter().rev() {
+        arm_pat.replace_range(usize::from(range.start())..usize::from(range.end()), "");
+    }
+    let names: Vec<_> = bindings.iter().filter_map(|it| it.name()).collect();
+    let let_pats = bindings.iter().zip(&names).map(|(binding, name)| {
+        if binding.mut_token().is_some() {
+            format!("mut {}", name)
+        } else {
+            name.to_string()
+        }
+    });
+    let (let_pat, value) = if names.len() == 1 {
+        (let_pats.collect::<String>(), names[0].to_string())
+    } else {
+        (format!("({})", let_pats.format(", ")), format!("({})", names.iter().format(", ")))
+    };
+
+    let indent = IndentLevel::from_node(let_stmt.syntax());
+    let arm_indent = indent + 1;
+    let fallback = match diverging_expr(&else_block) {
+        Some(expr) => format!("{},", expr),
+        None => else_block.reset_indent().indent(arm_indent).to_string(),
+    };
+
+    acc.add(
+        AssistId("convert_let_else_to_match", AssistKind::RefactorRewrite),
+        "Convert let-else to match",
+        let_stmt.syntax().text_range(),
+        |builder| {
+            builder.replace(
+                let_stmt.syntax().text_range(),
+                format!(
+                    "let {} = match {} {{\n{}{} => {},\n{}_ => {}\n{}}};",
+                    let_pat, scrutinee, arm_indent, arm_pat, value, arm_indent, fallback, indent
+                ),
+            )
+        },
+    )
+}
+
+struct Extraction {
+    /// The pattern extracting the value.
+    pat: ast::Pat,
+    scrutinee: ast::Expr,
+    /// The value computed from the bindings of `pat`.
+    value: ast::Expr,
+    /// The fallback when `scrutinee` doesn't match `pat`.
+    diverging: ast::Expr,
+}
+
+/// `match e { Pat(x) => x, _ => return }`.
+fn from_match(ctx: &AssistContext, match_expr: &ast::MatchExpr) -> Option<Extraction> {
+    let arms: Vec<_> = match_expr.match_arm_list()?.arms().collect();
+    if arms.len() != 2 || arms.iter().any(|arm| arm.guard().is_some()) {
+        return None;
+    }
+    let (value_arm, fallback_arm) = match (arms[0].pat()?, arms[1].pat()?) {
+        (_, ast::Pat::WildcardPat(_)) => (&arms[0], &arms[1]),
+        (ast::Pat::WildcardPat(_), _) => (&arms[1], &arms[0]),
+        _ => return None,
+    };
+    let diverging = fallback_arm.expr()?;
+    if !is_diverging(ctx, &diverging) {
+        return None;
+    }
+    Some(Extraction {
+        pat: value_arm.pat()?,
+        scrutinee: match_expr.expr()?,
+        value: value_arm.expr()?,
+        diverging,
+    })
+}
+
+/// `if let Pat(x) = e { x } else { return }`.
+fn from_if_let(ctx: &AssistContext, if_expr: &ast::IfExpr) -> Option<Extraction> {
+    let cond = if_expr.condition()?;
+    if !is_pattern_cond(cond.clone()) {
+        return None;
+    }
+    let let_expr = single_let(cond)?;
+    let then_branch = if_expr.then_branch()?;
+    let stmt_list = then_branch.stmt_list()?;
+    if stmt_list.statements().next().is_some() {
+        return None;
+    }
+    let diverging = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => ast::Expr::BlockExpr(it),
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    if !is_diverging(ctx, &diverging) {
+        return None;
+    }
+    Some(Extraction {
+        pat: let_expr.pat()?,
+        scrutinee: let_expr.expr()?,
+        value: stmt_list.tail_expr()?,
+        diverging,
+    })
+}
+
+fn is_diverging(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    if ctx.sema.type_of_expr(expr).map_or(false, |it| it.original.is_never()) {
+        return true;
+    }
+    // A block ending with a diverging statement takes the type expected of it.
+    let stmt_list = match expr {
+        ast::Expr::BlockExpr(it) if it.modifier().is_none() => it.stmt_list(),
+        _ => None,
+    };
+    let stmt_list = match stmt_list {
+        Some(it) => it,
+        None => return false,
+    };
+    match (stmt_list.tail_expr(), stmt_list.statements().last()) {
+        (Some(tail), _) => is_diverging(ctx, &tail),
+        (None, Some(ast::Stmt::ExprStmt(stmt))) => {
+            stmt.expr().map_or(false, |it| is_diverging(ctx, &it))
+        }
+        _ => false,
+    }
+}
+
+/// The single expression of a `{ return; }` or `{ return }` block.
+fn diverging_expr(block: &ast::BlockExpr) -> Option<ast::Expr> {
+    let stmt_list = block.stmt_list()?;
+    let mut statements = stmt_list.statements();
+    match (statements.next(), stmt_list.tail_expr()) {
+        (None, Some(tail)) => Some(tail),
+        (Some(ast::Stmt::ExprStmt(stmt)), None) if statements.next().is_none() => stmt.expr(),
+        _ => None,
+    }
+}
+
+/// Pairs the names `value` is built from with the bindings of `let_pat` they are stored into.
+fn match_bindings(
+    value: &ast::Expr,
+    let_pat: &ast::Pat,
+    renames: &mut Vec<(String, ast::IdentPat)>,
+) -> Option<()> {
+    match (value, let_pat) {
+        (ast::Expr::PathExpr(path_expr), ast::Pat::IdentPat(binding)) => {
+            if binding.at_token().is_some() {
+                return None;
+            }
+            let path = path_expr.path()?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            let name = path.segment()?.name_ref()?.to_string();
+            if renames.iter().any(|(it, _)| *it == name) {
+                return None;
+            }
+            renames.push((name, binding.clone()));
+        }
+        (ast::Expr::TupleExpr(tuple), ast::Pat::TuplePat(tuple_pat)) => {
+            let fields: Vec<_> = tuple_pat.fields().collect();
+            if tuple.fields().count() != fields.len() {
+                return None;
+            }
+            for (value, pat) in tuple.fields().zip(fields) {
+                match_bindings(&value, &pat, renames)?;
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// The bindings introduced by `pat`, leaving out the names of constants.
+fn bindings(ctx: &AssistContext, pat: &ast::Pat) -> Vec<ast::IdentPat> {
+    pat.syntax()
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|it| ctx.sema.resolve_bind_pat_to_const(it).is_none())
+        .collect()
+}
+
+/// `let`-`else` is stable since Rust 1.65, in every edition, so only a minimum supported Rust
+/// version declared by the crate can rule it out.
+fn let_else_supported(ctx: &AssistContext, node: &SyntaxNode) -> bool {
+    let krate = match ctx.sema.scope(node).krate() {
+        Some(it) => it,
+        None => return true,
+    };
+    let rust_version = match krate.rust_version(ctx.db()) {
+        Some(it) => it,
+        None => return true,
+    };
+    let mut parts = rust_version.split('.').map(|it| it.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (1, 65)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_match() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+fn log(_: &str) {}
+fn f(opt: Option<(u32, u32)>) -> u32 {
+    let mut x = $0match opt {
+        Some((a, _)) => a,
+        _ => {
+            log("none");
+            return 0;
+        }
+    };
+    x
+}
+"#,
+            r#"
+fn log(_: &str) {}
+fn f(opt: Option<(u32, u32)>) -> u32 {
+    let Some((mut x, _)) = opt else {
+        log("none");
+        return 0;
+    };
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_if_let_with_tuple() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+enum E { Pair(u32, u32), Other }
+fn f(e: E) {
+    loop {
+        $0let (a, b) = if let E::Pair(x, y) = e { (x, y) } else { continue };
+    }
+}
+"#,
+            r#"
+enum E { Pair(u32, u32), Other }
+fn f(e: E) {
+    loop {
+        let E::Pair(a, b) = e else { continue };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wraps_scrutinee_ending_with_brace() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+fn f() {
+    let x = $0match { Some(1) } {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#,
+            r#"
+fn f() {
+    let Some(x) = ({ Some(1) }) else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_divergence_or_with_extra_bindings() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+fn f(opt: Option<u32>) {
+    let x = $0match opt {
+        Some(x) => x,
+        _ => 0,
+    };
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+fn f(opt: Option<(u32, u32)>) {
+    let x = $0match opt {
+        Some((x, y)) => x,
+        _ => return,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_below_msrv() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+//- /main.rs crate:main rust-version:1.60
+fn f(opt: Option<u32>) {
+    let x = $0match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn applicable_from_msrv() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+//- /main.rs crate:main rust-version:1.65.0
+fn f(opt: Option<u32>) {
+    let x = $0match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#,
+            r#"
+fn f(opt: Option<u32>) {
+    let Some(x) = opt else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_let_else_single_binding() {
+        check_assist(
+            convert_let_else_to_match,
+            r#"
+//- minicore: option
+fn f(opt: Option<u32>) {
+    let Some(mut x) = $0opt else { return; };
+}
+"#,
+            r#"
+fn f(opt: Option<u32>) {
+    let mut x = match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_let_else_multiple_bindings() {
+        check_assist(
+            convert_let_else_to_match,
+            r#"
+enum E { Pair(u32, u32), Other }
+const C: u32 = 0;
+fn f(e: E) {
+    $0let E::Pair(a, b) = e else {
+        let msg = "other";
+        panic!("{}", msg)
+    };
+}
+"#,
+            r#"
+enum E { Pair(u32, u32), Other }
+const C: u32 = 0;
+fn f(e: E) {
+    let (a, b) = match e {
+        E::Pair(a, b) => (a, b),
+        _ => {
+            let msg = "other";
+            panic!("{}", msg)
+        }
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_else_block() {
+        check_assist_not_applicable(
+            convert_let_else_to_match,
+            r#"
+//- minicore: option
+fn f(opt: Option<u32>) {
+    let Some(x) = opt else { $0return };
+}
+"#,
+        );
+    }
+}
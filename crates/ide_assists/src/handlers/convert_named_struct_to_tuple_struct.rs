@@ -0,0 +1,498 @@
+use either::Either;
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, make, AstNode, HasAttrs, HasGenericParams, HasVisibility},
+    match_ast,
+    ted::{self, Position},
+    SyntaxElement, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts struct with named fields to tuple struct, and analogously for enum variants with named
+// fields.
+//
+// ```
+// struct Point$0 { x: f32, y: f32 }
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point { x, y }
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.x
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point(x, y)
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.0
+//     }
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx
+        .find_node_at_offset::<ast::Struct>()
+        .map(Either::Left)
+        .or_else(|| ctx.find_node_at_offset::<ast::Variant>().map(Either::Right))?;
+    let field_list = strukt.as_ref().either(|s| s.field_list(), |v| v.field_list())?;
+    let record_fields = match field_list {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    // Fields have their own assists, don't clutter them.
+    if record_fields.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let strukt_def = match &strukt {
+        Either::Left(s) => Definition::Adt(hir::Adt::Struct(ctx.sema.to_def(s)?)),
+        Either::Right(v) => Definition::Variant(ctx.sema.to_def(v)?),
+    };
+    let fields = record_fields
+        .fields()
+        .map(|it| ctx.sema.to_def(&it))
+        .collect::<Option<Vec<hir::Field>>>()?;
+    let field_names: Vec<_> = fields.iter().map(|it| it.name(ctx.db()).to_string()).collect();
+    let target = strukt.as_ref().either(|s| s.syntax(), |v| v.syntax()).text_range();
+
+    // The usages are needed up front to report the ones in macro calls, which can't be edited.
+    let (mut usages, macro_uses) = usages(ctx, strukt_def, &fields);
+    let label = match macro_uses {
+        0 => "Convert to tuple struct".to_owned(),
+        1 => "Convert to tuple struct, leaving 1 use in macro calls".to_owned(),
+        n => format!("Convert to tuple struct, leaving {} uses in macro calls", n),
+    };
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        label,
+        target,
+        |builder| {
+            let def_file_usages = usages.remove(&ctx.file_id()).unwrap_or_default();
+            for (file_id, nodes) in usages {
+                builder.edit_file(file_id);
+                let nodes: Vec<_> =
+                    nodes.into_iter().map(|it| builder.make_syntax_mut(it)).collect();
+                for node in nodes {
+                    edit_usage(node, &field_names);
+                }
+            }
+
+            builder.edit_file(ctx.file_id());
+            let nodes: Vec<_> =
+                def_file_usages.into_iter().map(|it| builder.make_syntax_mut(it)).collect();
+            let strukt = match strukt {
+                Either::Left(it) => Either::Left(builder.make_mut(it)),
+                Either::Right(it) => Either::Right(builder.make_mut(it)),
+            };
+            let record_fields = builder.make_mut(record_fields);
+            for node in nodes {
+                edit_usage(node, &field_names);
+            }
+            edit_struct_def(&strukt, &record_fields);
+        },
+    )
+}
+
+fn edit_struct_def(
+    strukt: &Either<ast::Struct, ast::Variant>,
+    record_fields: &ast::RecordFieldList,
+) {
+    let tuple_fields = record_fields.fields().filter_map(|field| {
+        let tuple_field = make::tuple_field(field.visibility(), field.ty()?).clone_for_update();
+        let attrs: Vec<_> = field
+            .attrs()
+            .flat_map(|attr| {
+                [
+                    attr.syntax().clone_subtree().clone_for_update().into(),
+                    make::tokens::single_space().into(),
+                ]
+            })
+            .collect();
+        ted::insert_all(Position::first_child_of(tuple_field.syntax()), attrs);
+        Some(tuple_field)
+    });
+    let tuple_fields = make::tuple_field_list(tuple_fields).clone_for_update();
+
+    remove_preceding_whitespace(record_fields.syntax());
+    match strukt {
+        Either::Left(strukt) => {
+            // `struct S<T> where T: X { .. }` becomes `struct S<T>(T) where T: X;`.
+            let where_clause = strukt.where_clause();
+            let mut tail: Vec<SyntaxElement> = Vec::new();
+            if let Some(where_clause) = &where_clause {
+                let ws = where_clause
+                    .syntax()
+                    .prev_sibling_or_token()
+                    .filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+                if let Some(ws) = ws {
+                    ted::remove(&ws);
+                    tail.push(ws);
+                }
+                ted::remove(where_clause.syntax());
+                let comma = where_clause.syntax().last_token().filter(|it| it.kind() == T![,]);
+                if let Some(comma) = comma {
+                    ted::remove(comma);
+                }
+                tail.push(where_clause.syntax().clone().into());
+            }
+            ted::replace(record_fields.syntax(), tuple_fields.syntax());
+            tail.push(make::token(T![;]).into());
+            ted::insert_all(Position::after(tuple_fields.syntax()), tail);
+        }
+        Either::Right(_) => ted::replace(record_fields.syntax(), tuple_fields.syntax()),
+    }
+}
+
+fn remove_preceding_whitespace(node: &SyntaxNode) {
+    if let Some(ws) = node.prev_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+    {
+        ted::remove(ws);
+    }
+}
+
+/// The record expressions and patterns of the struct and the accesses to its fields, innermost
+/// first, so that converting one doesn't invalidate those nested in it, along with the number of
+/// references from within macro calls, which can't be edited.
+fn usages(
+    ctx: &AssistContext,
+    strukt: Definition,
+    fields: &[hir::Field],
+) -> (FxHashMap<FileId, Vec<SyntaxNode>>, usize) {
+    let mut res: FxHashMap<FileId, Vec<SyntaxNode>> = FxHashMap::default();
+    let mut macro_uses = 0;
+    let mut add = |file_id: FileId, references: Vec<FileReference>, field: bool| {
+        let source_file = ctx.sema.parse(file_id);
+        for FileReference { name, .. } in references {
+            if name.syntax().ancestors().last().as_ref() != Some(source_file.syntax()) {
+                macro_uses += 1;
+                continue;
+            }
+            let node = match name {
+                ast::NameLike::NameRef(name_ref) if field => field_usage(&name_ref),
+                // The field of a shorthand pattern, `S { field }`.
+                ast::NameLike::Name(name) if field => {
+                    name.syntax().ancestors().find(|it| ast::RecordPat::can_cast(it.kind()))
+                }
+                ast::NameLike::NameRef(name_ref) => {
+                    let path = name_ref
+                        .syntax()
+                        .ancestors()
+                        .skip_while(|it| !ast::Path::can_cast(it.kind()))
+                        .take_while(|it| ast::Path::can_cast(it.kind()))
+                        .last();
+                    path.and_then(|it| it.parent()).filter(|it| {
+                        ast::RecordExpr::can_cast(it.kind()) || ast::RecordPat::can_cast(it.kind())
+                    })
+                }
+                _ => None,
+            };
+            if let Some(node) = node {
+                let nodes = res.entry(file_id).or_default();
+                if !nodes.contains(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+    };
+    for (file_id, references) in strukt.usages(&ctx.sema).include_self_refs().all() {
+        add(file_id, references, false);
+    }
+    for &field in fields {
+        for (file_id, references) in Definition::Field(field).usages(&ctx.sema).all() {
+            add(file_id, references, true);
+        }
+    }
+    for nodes in res.values_mut() {
+        nodes.sort_by_key(|it| it.text_range().len());
+    }
+    (res, macro_uses)
+}
+
+fn field_usage(name_ref: &ast::NameRef) -> Option<SyntaxNode> {
+    let parent = name_ref.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::FieldExpr(_) => Some(name_ref.syntax().clone()),
+            ast::RecordExprField(it) => {
+                it.syntax().ancestors().find(|it| ast::RecordExpr::can_cast(it.kind()))
+            },
+            ast::RecordPatField(it) => {
+                it.syntax().ancestors().find(|it| ast::RecordPat::can_cast(it.kind()))
+            },
+            _ => None,
+        }
+    }
+}
+
+fn edit_usage(node: SyntaxNode, field_names: &[String]) -> Option<()> {
+    let index_of = |name: &str| field_names.iter().position(|it| it == name);
+    match_ast! {
+        match node {
+            ast::NameRef(name_ref) => {
+                let index = index_of(&name_ref.text())?;
+                ted::replace(name_ref.syntax(), index_name_ref(index).syntax());
+            },
+            ast::RecordExpr(record) => {
+                let fields = record.record_expr_field_list()?;
+                let indices = fields
+                    .fields()
+                    .map(|field| index_of(&field.field_name()?.text()))
+                    .collect::<Option<Vec<_>>>()?;
+                // Reordering the fields into a call would change the order in which their
+                // values are evaluated.
+                let reorders_effects = indices.windows(2).any(|it| it[0] > it[1])
+                    && fields.fields().filter_map(|it| it.expr()).any(|it| {
+                        !matches!(it, ast::Expr::PathExpr(_) | ast::Expr::Literal(_))
+                    });
+                if fields.spread().is_some() || reorders_effects {
+                    // `S { 0: x, ..base }` is the only way to update a tuple struct, and
+                    // `S { 1: y(), 0: x() }` keeps the order of evaluation.
+                    for field in fields.fields() {
+                        let index = index_of(&field.field_name()?.text())?;
+                        match field.name_ref() {
+                            Some(name_ref) => {
+                                ted::replace(name_ref.syntax(), index_name_ref(index).syntax())
+                            }
+                            None => ted::insert_all_raw(
+                                Position::first_child_of(field.syntax()),
+                                vec![
+                                    index_name_ref(index).syntax().clone().into(),
+                                    make::token(T![:]).into(),
+                                    make::tokens::single_space().into(),
+                                ],
+                            ),
+                        }
+                    }
+                    return Some(());
+                }
+                let mut args = vec![None; field_names.len()];
+                for field in fields.fields() {
+                    let index = index_of(&field.field_name()?.text())?;
+                    args[index] = Some(field.expr()?);
+                }
+                let args = args.into_iter().collect::<Option<Vec<_>>>()?;
+                let call = make::expr_call(make::expr_path(record.path()?), make::arg_list(args));
+                ted::replace(record.syntax(), call.clone_for_update().syntax());
+            },
+            ast::RecordPat(record) => {
+                let fields = record.record_pat_field_list()?;
+                let mut pats = vec![None; field_names.len()];
+                for field in fields.fields() {
+                    let index = index_of(&field.field_name()?.to_string())?;
+                    pats[index] = Some(field.pat()?);
+                }
+                let mut pats: Vec<_> = pats
+                    .into_iter()
+                    .map(|it| it.unwrap_or_else(|| make::wildcard_pat().into()))
+                    .collect();
+                if let Some(rest) = fields.rest_pat() {
+                    while matches!(pats.last(), Some(ast::Pat::WildcardPat(_))) {
+                        pats.pop();
+                    }
+                    pats.push(ast::Pat::RestPat(rest));
+                }
+                let pat = make::tuple_struct_pat(record.path()?, pats);
+                ted::replace(record.syntax(), pat.clone_for_update().syntax());
+            },
+            _ => (),
+        }
+    }
+    Some(())
+}
+
+fn index_name_ref(index: usize) -> ast::NameRef {
+    let expr = make::expr_field(make::expr_unit(), &index.to_string()).clone_for_update();
+    match expr {
+        ast::Expr::FieldExpr(it) => it.name_ref().unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_other_than_record_struct() {
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0(u32);"#);
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0;"#);
+    }
+
+    #[test]
+    fn convert_struct_and_uses() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Inner;
+struct A$0 { #[allow(dead_code)] pub(crate) inner: Inner, n: u32 }
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A { n: 0, inner }
+    }
+
+    fn get(&self) -> &Inner {
+        let A { inner, .. } = self;
+        let Self { n: _, inner: _ } = self;
+        &self.inner
+    }
+
+    fn nested(self) -> A {
+        Self { inner: self.inner, n: A { n: 1, inner: Inner }.n }
+    }
+
+    fn update(self) -> A {
+        A { n: 2, ..self }
+    }
+}
+"#,
+            r#"
+struct Inner;
+struct A(#[allow(dead_code)] pub(crate) Inner, u32);
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A(inner, 0)
+    }
+
+    fn get(&self) -> &Inner {
+        let A(inner, ..) = self;
+        let Self(_, _) = self;
+        &self.0
+    }
+
+    fn nested(self) -> A {
+        Self(self.0, A(Inner, 1).1)
+    }
+
+    fn update(self) -> A {
+        A { 1: 2, ..self }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_evaluation_order_of_fields() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct A$0 { x: u32, y: u32 }
+fn next() -> u32 { 0 }
+fn f(x: u32) -> (A, A) {
+    (A { y: next(), x: next() }, A { y: 1, x })
+}
+"#,
+            r#"
+struct A(u32, u32);
+fn next() -> u32 { 0 }
+fn f(x: u32) -> (A, A) {
+    (A { 1: next(), 0: next() }, A(x, 1))
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_uses_in_macro_calls() {
+        check_assist_by_label(
+            convert_named_struct_to_tuple_struct,
+            r#"
+macro_rules! id { ($e:expr) => { $e }; }
+struct A$0 { x: u32 }
+fn f(a: A) -> u32 {
+    id!(a.x) + a.x
+}
+"#,
+            r#"
+macro_rules! id { ($e:expr) => { $e }; }
+struct A(u32);
+fn f(a: A) -> u32 {
+    id!(a.x) + a.0
+}
+"#,
+            "Convert to tuple struct, leaving 1 use in macro calls",
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_where_clause() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Wrap$0<T>
+where
+    T: Copy,
+{
+    value: T,
+}
+"#,
+            r#"
+struct Wrap<T>(T)
+where
+    T: Copy;
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant_across_files() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+//- /main.rs
+mod other;
+pub enum E {
+    V$0 { a: u8, b: bool },
+}
+//- /other.rs
+use crate::E;
+fn f(e: E) -> u8 {
+    match e {
+        E::V { b: true, a } => a,
+        E::V { .. } => 0,
+    }
+}
+fn g() -> E {
+    E::V { a: 1, b: false }
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+pub enum E {
+    V(u8, bool),
+}
+//- /other.rs
+use crate::E;
+fn f(e: E) -> u8 {
+    match e {
+        E::V(a, true) => a,
+        E::V(..) => 0,
+    }
+}
+fn g() -> E {
+    E::V(1, false)
+}
+"#,
+        );
+    }
+}
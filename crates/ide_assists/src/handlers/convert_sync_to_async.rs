@@ -0,0 +1,507 @@
+use hir::AsAssocItem;
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{ast, match_ast, AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_sync_to_async
+//
+// Makes a function `async`, awaiting the calls to it made from async code. Calls made from
+// synchronous functions can be converted too, by making their callers `async` in turn.
+//
+// ```
+// fn $0fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch();
+// }
+// ```
+// ->
+// ```
+// async fn fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch().await;
+// }
+// ```
+pub(crate) fn convert_sync_to_async(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_ = fn_at_signature(ctx)?;
+    let func = ctx.sema.to_def(&fn_)?;
+    if func.is_async(ctx.db())
+        || fn_.const_token().is_some()
+        || is_trait_item(ctx, func)
+        || is_main(ctx, func)
+    {
+        return None;
+    }
+
+    // Only the direct callers are searched up front, to tell about the calls which can't be
+    // awaited. Their own callers are left to the edit, as searching those for every code action
+    // request is too slow.
+    let calls = find_calls(ctx, func);
+    let sync_calls = calls.iter().filter(|it| !matches!(it.context, CallContext::Async)).count();
+    let label = match sync_calls {
+        0 => "Convert to async function".to_owned(),
+        1 => "Convert to async function, leaving 1 call outside async code".to_owned(),
+        n => format!("Convert to async function, leaving {} calls outside async code", n),
+    };
+    let target = fn_.syntax().text_range();
+    let converted = vec![(ctx.file_id(), fn_, func)];
+    acc.add(
+        AssistId("convert_sync_to_async", AssistKind::RefactorRewrite),
+        label,
+        target,
+        |builder| {
+            let edits = async_edits(&converted, &calls);
+            apply_edits(builder, edits);
+        },
+    );
+
+    if !calls.iter().any(|it| matches!(it.context, CallContext::SyncFn(..))) {
+        return None;
+    }
+    acc.add(
+        AssistId("convert_sync_to_async_with_callers", AssistKind::RefactorRewrite),
+        "Convert to async function and its callers",
+        target,
+        |builder| {
+            // Callers which can be made `async` in turn, and their own callers, transitively.
+            let mut converted = converted;
+            let mut seen: FxHashSet<_> = [func].into_iter().collect();
+            let mut all_calls = calls;
+            let mut idx = 0;
+            while idx < all_calls.len() {
+                if let CallContext::SyncFn(file_id, caller_fn, caller) = &all_calls[idx].context {
+                    if seen.insert(*caller) {
+                        converted.push((*file_id, caller_fn.clone(), *caller));
+                        let caller_calls = find_calls(ctx, *caller);
+                        all_calls.extend(caller_calls);
+                    }
+                }
+                idx += 1;
+            }
+            let edits = async_edits(&converted, &all_calls);
+            apply_edits(builder, edits);
+        },
+    )
+}
+
+// Assist: convert_async_to_sync
+//
+// Removes `async` from a function which doesn't await anything, and the `.await`s on the calls
+// to it.
+//
+// ```
+// async fn $0fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch().await;
+// }
+// ```
+// ->
+// ```
+// fn fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch();
+// }
+// ```
+pub(crate) fn convert_async_to_sync(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_ = fn_at_signature(ctx)?;
+    let async_token = fn_.async_token()?;
+    let func = ctx.sema.to_def(&fn_)?;
+    if is_trait_item(ctx, func) {
+        return None;
+    }
+    let body = fn_.body()?;
+    let awaits_itself = body
+        .syntax()
+        .descendants()
+        .filter(|it| it.kind() == SyntaxKind::AWAIT_EXPR)
+        .any(|it| matches!(async_context(&it), Some(context) if context == *fn_.syntax()));
+    if awaits_itself {
+        return None;
+    }
+
+    acc.add(
+        AssistId("convert_async_to_sync", AssistKind::RefactorRewrite),
+        "Convert to sync function",
+        fn_.syntax().text_range(),
+        |builder| {
+            let calls = find_calls(ctx, func);
+            let mut edits: FxHashMap<FileId, Vec<Edit>> = FxHashMap::default();
+            let async_end = match async_token.next_token() {
+                Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().end(),
+                _ => async_token.text_range().end(),
+            };
+            let async_range = TextRange::new(async_token.text_range().start(), async_end);
+            edits.entry(ctx.file_id()).or_default().push(Edit::Delete(async_range));
+            for call in &calls {
+                let await_expr = call.expr.parent().and_then(ast::AwaitExpr::cast);
+                if let Some(await_expr) = await_expr {
+                    let range = TextRange::new(
+                        call.expr.text_range().end(),
+                        await_expr.syntax().text_range().end(),
+                    );
+                    edits.entry(call.file_id).or_default().push(Edit::Delete(range));
+                }
+            }
+            apply_edits(builder, edits);
+        },
+    )
+}
+
+/// A call to a converted function.
+struct Call {
+    file_id: FileId,
+    /// The call or method call expression.
+    expr: SyntaxNode,
+    context: CallContext,
+}
+
+enum CallContext {
+    /// In an `async` function, block or closure, where the call can be awaited.
+    Async,
+    /// In a synchronous function, which could be made `async`.
+    SyncFn(FileId, ast::Fn, hir::Function),
+    /// Anywhere else, like in a synchronous closure or in a trait method.
+    Sync,
+}
+
+enum Edit {
+    Insert(TextSize, &'static str),
+    Delete(TextRange),
+}
+
+/// The function whose signature the cursor is on.
+fn fn_at_signature(ctx: &AssistContext) -> Option<ast::Fn> {
+    let fn_ = ctx.find_node_at_offset::<ast::Fn>()?;
+    if let Some(body) = fn_.body() {
+        if body.syntax().text_range().contains_inclusive(ctx.offset()) {
+            return None;
+        }
+    }
+    Some(fn_)
+}
+
+/// Trait methods can't be `async`, and changing an impl would break the trait's contract.
+fn is_trait_item(ctx: &AssistContext, func: hir::Function) -> bool {
+    func.as_assoc_item(ctx.db())
+        .and_then(|it| it.containing_trait_or_trait_impl(ctx.db()))
+        .is_some()
+}
+
+fn find_calls(ctx: &AssistContext, func: hir::Function) -> Vec<Call> {
+    let mut res = Vec::new();
+    for (file_id, references) in Definition::Function(func).usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(file_id);
+        for FileReference { name, .. } in references {
+            // References from within macro expansions can't be edited.
+            if name.syntax().ancestors().last().as_ref() != Some(source_file.syntax()) {
+                continue;
+            }
+            let expr = match name.as_name_ref().and_then(call_expr) {
+                Some(it) => it,
+                None => continue,
+            };
+            let context = match async_context(&expr) {
+                Some(node) => classify_context(ctx, file_id, node),
+                None => CallContext::Sync,
+            };
+            res.push(Call { file_id, expr, context });
+        }
+    }
+    res
+}
+
+/// The call expression `name_ref` is the callee of.
+fn call_expr(name_ref: &ast::NameRef) -> Option<SyntaxNode> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        return Some(call.syntax().clone());
+    }
+    let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    if call.expr()?.syntax() != path_expr.syntax() {
+        return None;
+    }
+    Some(call.syntax().clone())
+}
+
+/// The closest function, closure or async block around `node`, which decides whether an
+/// `.await` is allowed there.
+fn async_context(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors().skip(1).find(|it| {
+        match_ast! {
+            match it {
+                ast::Fn(_) => true,
+                ast::ClosureExpr(_) => true,
+                ast::BlockExpr(block) => block.async_token().is_some(),
+                ast::Const(_) => true,
+                ast::Static(_) => true,
+                _ => false,
+            }
+        }
+    })
+}
+
+fn classify_context(ctx: &AssistContext, file_id: FileId, node: SyntaxNode) -> CallContext {
+    match_ast! {
+        match node {
+            ast::Fn(fn_) => {
+                let func = match ctx.sema.to_def(&fn_) {
+                    Some(it) => it,
+                    None => return CallContext::Sync,
+                };
+                if func.is_async(ctx.db()) {
+                    CallContext::Async
+                } else if fn_.const_token().is_some() || is_trait_item(ctx, func) || is_main(ctx, func) {
+                    CallContext::Sync
+                } else {
+                    CallContext::SyncFn(file_id, fn_, func)
+                }
+            },
+            ast::ClosureExpr(closure) => {
+                if closure.async_token().is_some() { CallContext::Async } else { CallContext::Sync }
+            },
+            ast::BlockExpr(_) => CallContext::Async,
+            _ => CallContext::Sync,
+        }
+    }
+}
+
+/// `main` needs a runtime to be `async`.
+fn is_main(ctx: &AssistContext, func: hir::Function) -> bool {
+    func.name(ctx.db()).to_string() == "main" && func.module(ctx.db()).is_crate_root(ctx.db())
+}
+
+/// Adds `async` to the `converted` functions and `.await` to the `calls` made from async code.
+fn async_edits(
+    converted: &[(FileId, ast::Fn, hir::Function)],
+    calls: &[Call],
+) -> FxHashMap<FileId, Vec<Edit>> {
+    let mut edits: FxHashMap<FileId, Vec<Edit>> = FxHashMap::default();
+    for (file_id, fn_, _) in converted {
+        // `async` goes after `const` and before `unsafe` and `extern`.
+        let anchor = fn_
+            .unsafe_token()
+            .or_else(|| fn_.abi().and_then(|it| it.syntax().first_token()))
+            .or_else(|| fn_.fn_token());
+        if let Some(anchor) = anchor {
+            edits
+                .entry(*file_id)
+                .or_default()
+                .push(Edit::Insert(anchor.text_range().start(), "async "));
+        }
+    }
+    for call in calls {
+        let awaited = match &call.context {
+            CallContext::Async => true,
+            CallContext::SyncFn(_, _, caller) => converted.iter().any(|(_, _, it)| it == caller),
+            CallContext::Sync => false,
+        };
+        if awaited {
+            let offset = call.expr.text_range().end();
+            edits.entry(call.file_id).or_default().push(Edit::Insert(offset, ".await"));
+        }
+    }
+    edits
+}
+
+fn apply_edits(
+    builder: &mut crate::assist_context::AssistBuilder,
+    edits: FxHashMap<FileId, Vec<Edit>>,
+) {
+    for (file_id, edits) in edits {
+        builder.edit_file(file_id);
+        for edit in edits {
+            match edit {
+                Edit::Insert(offset, text) => builder.insert(offset, text),
+                Edit::Delete(range) => builder.delete(range),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable,
+        check_assist_not_applicable_by_label,
+    };
+
+    use super::*;
+
+    #[test]
+    fn awaits_calls_in_async_code() {
+        check_assist(
+            convert_sync_to_async,
+            r#"
+struct S;
+impl S {
+    pub unsafe fn $0get(&self) -> u32 { 0 }
+}
+async fn f(s: S) {
+    let x = unsafe { s.get() } + 1;
+    let fut = async move { unsafe { S::get(&s) } };
+    let c = async || unsafe { s.get() };
+}
+"#,
+            r#"
+struct S;
+impl S {
+    pub async unsafe fn get(&self) -> u32 { 0 }
+}
+async fn f(s: S) {
+    let x = unsafe { s.get().await } + 1;
+    let fut = async move { unsafe { S::get(&s).await } };
+    let c = async || unsafe { s.get().await };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_calls_outside_async_code() {
+        check_assist_by_label(
+            convert_sync_to_async,
+            r#"
+fn $0get() -> u32 { 0 }
+fn sync() -> u32 { get() }
+async fn run() {
+    let c = || get();
+    get();
+}
+"#,
+            r#"
+async fn get() -> u32 { 0 }
+fn sync() -> u32 { get() }
+async fn run() {
+    let c = || get();
+    get().await;
+}
+"#,
+            "Convert to async function, leaving 2 calls outside async code",
+        );
+        check_assist_not_applicable_by_label(
+            convert_sync_to_async,
+            r#"
+fn $0get() -> u32 { 0 }
+async fn run() {
+    let c = || get();
+}
+"#,
+            "Convert to async function and its callers",
+        );
+    }
+
+    #[test]
+    fn converts_callers_transitively() {
+        check_assist_by_label(
+            convert_sync_to_async,
+            r#"
+//- /main.rs
+mod util;
+fn $0get() -> u32 { 0 }
+fn twice() -> u32 { get() + get() }
+fn main() {
+    util::run();
+}
+//- /util.rs
+pub fn run() {
+    let x = crate::twice();
+}
+"#,
+            r#"
+//- /main.rs
+mod util;
+async fn get() -> u32 { 0 }
+async fn twice() -> u32 { get().await + get().await }
+fn main() {
+    util::run();
+}
+//- /util.rs
+pub async fn run() {
+    let x = crate::twice().await;
+}
+"#,
+            "Convert to async function and its callers",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_items_main_and_async_fns() {
+        check_assist_not_applicable(
+            convert_sync_to_async,
+            r#"
+trait T { fn $0f(); }
+"#,
+        );
+        check_assist_not_applicable(
+            convert_sync_to_async,
+            r#"
+fn $0main() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_sync_to_async,
+            r#"
+async fn $0f() {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_sync_to_async,
+            r#"
+fn f() { $0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_async_and_awaits() {
+        check_assist(
+            convert_async_to_sync,
+            r#"
+pub async fn $0get() -> u32 { 0 }
+async fn run() {
+    let x = get().await + 1;
+    let fut = get();
+}
+"#,
+            r#"
+pub fn get() -> u32 { 0 }
+async fn run() {
+    let x = get() + 1;
+    let fut = get();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_awaiting_fn() {
+        check_assist_not_applicable(
+            convert_async_to_sync,
+            r#"
+async fn other() {}
+async fn $0get() { other().await }
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_async_ignores_nested_async_blocks() {
+        check_assist(
+            convert_async_to_sync,
+            r#"
+async fn other() {}
+async fn $0get() { let fut = async { other().await }; }
+"#,
+            r#"
+async fn other() {}
+fn get() { let fut = async { other().await }; }
+"#,
+        );
+    }
+}
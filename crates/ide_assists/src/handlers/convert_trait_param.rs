@@ -0,0 +1,1089 @@
+use hir::{ModuleDef, PathResolution};
+use ide_db::{base_db::FileId, defs::Definition};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, HasArgList, HasGenericParams, HasName, HasTypeBounds},
+    AstNode, Direction, SyntaxElement, SyntaxKind, SyntaxNode, TextRange, T,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_param_to_impl_trait
+//
+// Converts a parameter taking a generic, `&dyn Trait` or `Box<dyn Trait>` into one taking
+// `impl Trait`, updating the arguments of all calls.
+//
+// ```
+// trait Draw {}
+// fn render(shape: $0&dyn Draw) {}
+// ```
+// ->
+// ```
+// trait Draw {}
+// fn render(shape: impl Draw) {}
+// ```
+pub(crate) fn convert_param_to_impl_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    convert_param(acc, ctx, ParamKind::ImplTrait)
+}
+
+// Assist: convert_param_to_generic
+//
+// Converts a parameter taking `impl Trait`, `&dyn Trait` or `Box<dyn Trait>` into one taking
+// a named generic bounded in a where clause, updating the arguments of all calls.
+//
+// ```
+// trait Draw {}
+// fn render(shape: $0Box<dyn Draw>) {}
+// ```
+// ->
+// ```
+// trait Draw {}
+// fn render<D>(shape: D) where D: Draw {}
+// ```
+pub(crate) fn convert_param_to_generic(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    convert_param(acc, ctx, ParamKind::Generic)
+}
+
+// Assist: convert_param_to_dyn_ref
+//
+// Converts a parameter taking `impl Trait`, a generic or `Box<dyn Trait>` into one taking
+// `&dyn Trait`, updating the arguments of all calls. The trait has to be object safe.
+//
+// ```
+// trait Draw {}
+// struct Circle;
+// impl Draw for Circle {}
+// fn render(shape: $0impl Draw) {}
+// fn main() {
+//     render(Circle);
+// }
+// ```
+// ->
+// ```
+// trait Draw {}
+// struct Circle;
+// impl Draw for Circle {}
+// fn render(shape: &dyn Draw) {}
+// fn main() {
+//     render(&Circle);
+// }
+// ```
+pub(crate) fn convert_param_to_dyn_ref(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    convert_param(acc, ctx, ParamKind::DynRef)
+}
+
+// Assist: convert_param_to_boxed_dyn
+//
+// Converts a parameter taking `impl Trait`, a generic or `&dyn Trait` into one taking
+// `Box<dyn Trait>`, updating the arguments of all calls. The trait has to be object safe.
+//
+// ```
+// trait Draw {}
+// struct Circle;
+// impl Draw for Circle {}
+// fn render<S>(shape: $0S) where S: Draw {}
+// fn main() {
+//     render(Circle);
+// }
+// ```
+// ->
+// ```
+// trait Draw {}
+// struct Circle;
+// impl Draw for Circle {}
+// fn render(shape: Box<dyn Draw>) {}
+// fn main() {
+//     render(Box::new(Circle));
+// }
+// ```
+pub(crate) fn convert_param_to_boxed_dyn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    convert_param(acc, ctx, ParamKind::BoxedDyn)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ParamKind {
+    ImplTrait,
+    Generic,
+    DynRef,
+    BoxedDyn,
+}
+
+/// A function parameter whose type is one of the [`ParamKind`] spellings of "something
+/// implementing these bounds".
+struct TraitParam {
+    kind: ParamKind,
+    ty: ast::Type,
+    bounds: Vec<ast::TypeBound>,
+    /// For [`ParamKind::Generic`], the declaration of the generic and its where clause bounds.
+    generic: Option<(ast::TypeParam, Vec<ast::WherePred>)>,
+}
+
+fn convert_param(acc: &mut Assists, ctx: &AssistContext, target: ParamKind) -> Option<()> {
+    let param: ast::Param = ctx.find_node_at_offset()?;
+    if !param.ty()?.syntax().text_range().contains_range(ctx.selection_trimmed()) {
+        return None;
+    }
+    let fn_ = param.syntax().ancestors().find_map(ast::Fn::cast)?;
+    // Changing the signature of a trait method would require updating all other impls.
+    let in_trait = fn_.syntax().ancestors().nth(2).map_or(false, |it| {
+        ast::Trait::can_cast(it.kind())
+            || ast::Impl::cast(it).map_or(false, |imp| imp.trait_().is_some())
+    });
+    if in_trait {
+        return None;
+    }
+
+    let trait_param = classify_param(ctx, &fn_, &param)?;
+    if trait_param.kind == target || trait_param.bounds.is_empty() {
+        return None;
+    }
+    if matches!(target, ParamKind::DynRef | ParamKind::BoxedDyn)
+        && !bounds_are_object_safe(ctx, &trait_param.bounds)
+    {
+        return None;
+    }
+
+    let mut param_position = fn_.param_list()?.params().position(|it| it == param)?;
+    if fn_.param_list()?.self_param().is_some() {
+        param_position += 1;
+    }
+    let fn_def = Definition::Function(ctx.sema.to_def(&fn_)?);
+    // The explicit generic arguments of the calls change along with the generic parameters.
+    let generic_args = match (&trait_param.generic, target) {
+        (Some((type_param, _)), _) => {
+            let index = fn_
+                .generic_param_list()?
+                .generic_params()
+                .filter(|it| !matches!(it, ast::GenericParam::LifetimeParam(_)))
+                .position(|it| it.syntax() == type_param.syntax())?;
+            GenericArgsChange::Remove(index)
+        }
+        (None, ParamKind::Generic) => GenericArgsChange::Append,
+        (None, _) => GenericArgsChange::None,
+    };
+    // Function references and unwrapping a `&` or a `Box` can't always be converted, so the
+    // usages are checked before offering the assist.
+    let usage_edits =
+        convert_usages(ctx, fn_def, param_position, trait_param.kind, target, generic_args)?;
+
+    let (id, label) = match target {
+        ParamKind::ImplTrait => {
+            ("convert_param_to_impl_trait", "Convert to `impl Trait` parameter")
+        }
+        ParamKind::Generic => ("convert_param_to_generic", "Convert to generic parameter"),
+        ParamKind::DynRef => ("convert_param_to_dyn_ref", "Convert to `&dyn Trait` parameter"),
+        ParamKind::BoxedDyn => {
+            ("convert_param_to_boxed_dyn", "Convert to `Box<dyn Trait>` parameter")
+        }
+    };
+    acc.add(
+        AssistId(id, AssistKind::RefactorRewrite),
+        label,
+        param.syntax().text_range(),
+        |builder| {
+            let bounds = trait_param.bounds.iter().map(|it| it.syntax().to_string()).join(" + ");
+            let new_ty = match target {
+                ParamKind::ImplTrait => format!("impl {}", bounds),
+                ParamKind::Generic => {
+                    let name = generic_name(&fn_, &trait_param.bounds);
+                    add_generic(builder, &fn_, &name, &bounds);
+                    name
+                }
+                ParamKind::DynRef if trait_param.bounds.len() > 1 => format!("&(dyn {})", bounds),
+                ParamKind::DynRef => format!("&dyn {}", bounds),
+                ParamKind::BoxedDyn => format!("Box<dyn {}>", bounds),
+            };
+            builder.replace(trait_param.ty.syntax().text_range(), new_ty);
+            if let Some((type_param, preds)) = &trait_param.generic {
+                remove_generic(builder, &fn_, type_param, preds);
+            }
+
+            for (file_id, edits) in usage_edits {
+                builder.edit_file(file_id);
+                for (range, text) in edits {
+                    builder.replace(range, text);
+                }
+            }
+        },
+    )
+}
+
+/// The range of an argument, or of a generic argument, and its new text.
+type UsageEdit = (TextRange, String);
+
+/// How the explicit generic arguments of a call, `f::<A, B>()`, change.
+#[derive(Copy, Clone)]
+enum GenericArgsChange {
+    None,
+    /// The generic parameter at the index, not counting lifetimes, is removed.
+    Remove(usize),
+    /// A generic parameter is added at the end, which calls leave to inference.
+    Append,
+}
+
+/// The edits to the calls of the function, or `None` if one of its usages can't be converted:
+/// a function reference, a call in a macro or an argument which can't be unwrapped.
+fn convert_usages(
+    ctx: &AssistContext,
+    fn_def: Definition,
+    param_position: usize,
+    from: ParamKind,
+    to: ParamKind,
+    generic_args: GenericArgsChange,
+) -> Option<Vec<(FileId, Vec<UsageEdit>)>> {
+    let mut res = Vec::new();
+    for (file_id, references) in fn_def.usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(file_id);
+        let mut edits = Vec::new();
+        for reference in references {
+            let name_ref = reference.name.as_name_ref()?;
+            if name_ref.syntax().ancestors().last().as_ref() != Some(source_file.syntax()) {
+                return None;
+            }
+            if name_ref.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind())) {
+                continue;
+            }
+            let call = Call::of(name_ref)?;
+            if let Some(arg) = call.argument(param_position) {
+                let text = convert_argument(ctx, &arg, from, to)?;
+                if arg.syntax().text() != text.as_str() {
+                    edits.push((arg.syntax().text_range(), text));
+                }
+            }
+            if let Some(generic_arg_list) = &call.generic_arg_list {
+                edits.extend(convert_generic_args(generic_arg_list, generic_args)?);
+            }
+        }
+        res.push((file_id, edits));
+    }
+    Some(res)
+}
+
+fn convert_generic_args(
+    generic_arg_list: &ast::GenericArgList,
+    change: GenericArgsChange,
+) -> Option<Option<UsageEdit>> {
+    let args: Vec<_> = generic_arg_list
+        .generic_args()
+        .filter(|it| !matches!(it, ast::GenericArg::LifetimeArg(_)))
+        .collect();
+    let edit = match change {
+        GenericArgsChange::None => None,
+        GenericArgsChange::Remove(index) => {
+            let arg = args.get(index)?;
+            let range = if generic_arg_list.generic_args().count() == 1 {
+                generic_arg_list.syntax().text_range()
+            } else {
+                range_with_comma(arg.syntax())
+            };
+            Some((range, String::new()))
+        }
+        GenericArgsChange::Append => {
+            let last = generic_arg_list.generic_args().last()?;
+            Some((TextRange::empty(last.syntax().text_range().end()), ", _".to_owned()))
+        }
+    };
+    Some(edit)
+}
+
+fn classify_param(ctx: &AssistContext, fn_: &ast::Fn, param: &ast::Param) -> Option<TraitParam> {
+    let ty = param.ty()?;
+    let (kind, bounds, generic) = match &ty {
+        ast::Type::ImplTraitType(it) => (ParamKind::ImplTrait, it.type_bound_list()?, None),
+        ast::Type::RefType(it) => match it.ty()? {
+            ast::Type::DynTraitType(dyn_) => (ParamKind::DynRef, dyn_.type_bound_list()?, None),
+            _ => return None,
+        },
+        ast::Type::PathType(it) => {
+            let path = it.path()?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            let segment = path.segment()?;
+            match segment.generic_arg_list() {
+                Some(args) if segment.name_ref()?.text() == "Box" => {
+                    match args.generic_args().exactly_one().ok()? {
+                        ast::GenericArg::TypeArg(arg) => match arg.ty()? {
+                            ast::Type::DynTraitType(dyn_) => {
+                                (ParamKind::BoxedDyn, dyn_.type_bound_list()?, None)
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+                Some(_) => return None,
+                None => {
+                    let name = segment.name_ref()?;
+                    let type_param = fn_
+                        .generic_param_list()?
+                        .generic_params()
+                        .filter_map(|it| match it {
+                            ast::GenericParam::TypeParam(it) => Some(it),
+                            _ => None,
+                        })
+                        .find(|it| it.name().map_or(false, |it| it.text() == name.text()))?;
+                    return classify_generic(ctx, fn_, ty.clone(), type_param);
+                }
+            }
+        }
+        _ => return None,
+    };
+    Some(TraitParam { kind, ty, bounds: bounds.bounds().collect(), generic })
+}
+
+/// A generic parameter can only be replaced if the parameter's type is its sole use.
+fn classify_generic(
+    ctx: &AssistContext,
+    fn_: &ast::Fn,
+    ty: ast::Type,
+    type_param: ast::TypeParam,
+) -> Option<TraitParam> {
+    if type_param.default_type().is_some() {
+        return None;
+    }
+    let name = type_param.name()?;
+    let preds = fn_
+        .where_clause()
+        .into_iter()
+        .flat_map(|it| it.predicates())
+        .filter(|pred| {
+            pred.for_token().is_none()
+                && pred.ty().map_or(false, |ty| ty.syntax().text() == name.text().as_str())
+        })
+        .collect::<Vec<_>>();
+    let bounds = type_param
+        .type_bound_list()
+        .into_iter()
+        .chain(preds.iter().filter_map(|it| it.type_bound_list()))
+        .flat_map(|it| it.bounds())
+        .collect();
+
+    let def = ctx.sema.to_def(&type_param)?;
+    let other_uses = Definition::GenericParam(def.into()).usages(&ctx.sema).all().into_iter().any(
+        |(_, references)| {
+            references.iter().any(|reference| {
+                !ty.syntax().text_range().contains_range(reference.range)
+                    && !preds
+                        .iter()
+                        .any(|it| it.syntax().text_range().contains_range(reference.range))
+            })
+        },
+    );
+    if other_uses {
+        return None;
+    }
+    Some(TraitParam { kind: ParamKind::Generic, ty, bounds, generic: Some((type_param, preds)) })
+}
+
+/// Whether `dyn` of the bounds is a valid type: object safe traits, of which at most one isn't
+/// an auto trait.
+fn bounds_are_object_safe(ctx: &AssistContext, bounds: &[ast::TypeBound]) -> bool {
+    let mut principal_traits = 0;
+    let all_object_safe = bounds.iter().all(|bound| {
+        if bound.lifetime().is_some() {
+            return true;
+        }
+        // `?Sized` and friends are meaningless on trait objects.
+        if bound.question_mark_token().is_some() {
+            return false;
+        }
+        let path = match bound.ty() {
+            Some(ast::Type::PathType(it)) => it.path(),
+            _ => None,
+        };
+        match path.and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Def(ModuleDef::Trait(trait_))) => {
+                if !trait_.is_auto(ctx.db()) {
+                    principal_traits += 1;
+                }
+                trait_.is_object_safe(ctx.db())
+            }
+            _ => false,
+        }
+    });
+    all_object_safe && principal_traits <= 1
+}
+
+fn generic_name(fn_: &ast::Fn, bounds: &[ast::TypeBound]) -> String {
+    let existing = fn_
+        .generic_param_list()
+        .into_iter()
+        .flat_map(|it| {
+            it.generic_params().filter_map(|it| match it {
+                ast::GenericParam::TypeParam(it) => Some(it),
+                _ => None,
+            })
+        })
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let initial = bounds
+        .iter()
+        .filter_map(|it| it.ty())
+        .find_map(|it| it.syntax().text().to_string().chars().find(|c| c.is_alphabetic()))
+        .map_or('T', |c| c.to_ascii_uppercase());
+    std::iter::once(initial.to_string())
+        .chain(std::iter::once("T".to_string()))
+        .chain((1..).map(|i| format!("T{}", i)))
+        .find(|it| !existing.contains(it))
+        .unwrap()
+}
+
+fn add_generic(builder: &mut AssistBuilder, fn_: &ast::Fn, name: &str, bounds: &str) {
+    match fn_.generic_param_list() {
+        Some(list) => match list.generic_params().last() {
+            Some(last) => builder.insert(last.syntax().text_range().end(), format!(", {}", name)),
+            None => builder.replace(list.syntax().text_range(), format!("<{}>", name)),
+        },
+        None => {
+            if let Some(fn_name) = fn_.name() {
+                builder.insert(fn_name.syntax().text_range().end(), format!("<{}>", name))
+            }
+        }
+    }
+    let pred = format!("{}: {}", name, bounds);
+    match fn_.where_clause() {
+        Some(where_clause) => match where_clause.predicates().last() {
+            Some(last) => builder.insert(last.syntax().text_range().end(), format!(", {}", pred)),
+            None => builder.insert(where_clause.syntax().text_range().end(), format!(" {}", pred)),
+        },
+        None => {
+            let anchor = match fn_.ret_type() {
+                Some(ret) => ret.syntax().text_range().end(),
+                None => match fn_.param_list() {
+                    Some(params) => params.syntax().text_range().end(),
+                    None => return,
+                },
+            };
+            builder.insert(anchor, format!(" where {}", pred))
+        }
+    }
+}
+
+fn remove_generic(
+    builder: &mut AssistBuilder,
+    fn_: &ast::Fn,
+    type_param: &ast::TypeParam,
+    preds: &[ast::WherePred],
+) {
+    if let Some(list) = fn_.generic_param_list() {
+        if list.generic_params().count() == 1 {
+            builder.delete(list.syntax().text_range());
+        } else {
+            builder.delete(range_with_comma(type_param.syntax()));
+        }
+    }
+    if let Some(where_clause) = fn_.where_clause() {
+        if where_clause.predicates().count() == preds.len() {
+            let start = where_clause
+                .syntax()
+                .prev_sibling_or_token()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .map_or(where_clause.syntax().text_range().start(), |it| it.text_range().start());
+            builder.delete(TextRange::new(start, where_clause.syntax().text_range().end()));
+        } else {
+            for pred in preds {
+                builder.delete(range_with_comma(pred.syntax()));
+            }
+        }
+    }
+}
+
+/// The range of a list element along with the comma separating it from its neighbours.
+fn range_with_comma(element: &SyntaxNode) -> TextRange {
+    let is_ws = |it: &SyntaxElement| it.kind() == SyntaxKind::WHITESPACE;
+    let mut preceding = element.siblings_with_tokens(Direction::Prev).skip(1).peekable();
+    let leading_ws = preceding.next_if(is_ws);
+    let preceding_comma = preceding.next().filter(|it| it.kind() == T![,]);
+
+    let mut following = element.siblings_with_tokens(Direction::Next).skip(1).skip_while(is_ws);
+    if let Some(comma) = following.next().filter(|it| it.kind() == T![,]) {
+        // Keep the layout of the list by removing the whitespace on one side only.
+        let (start, end) = match (&preceding_comma, leading_ws) {
+            (Some(_), Some(ws)) => (ws, comma),
+            _ => {
+                let end = following.take_while(is_ws).last().unwrap_or(comma);
+                (element.clone().into(), end)
+            }
+        };
+        return TextRange::new(start.text_range().start(), end.text_range().end());
+    }
+    let start = match preceding_comma {
+        Some(comma) => comma.text_range().start(),
+        None => element.text_range().start(),
+    };
+    TextRange::new(start, element.text_range().end())
+}
+
+/// A call of the function, `f::<A>(a)` or `x.f::<A>(a)`.
+struct Call {
+    arg_list: ast::ArgList,
+    generic_arg_list: Option<ast::GenericArgList>,
+    is_method_call: bool,
+}
+
+impl Call {
+    /// The call made with `name_ref`, or `None` if it is used otherwise, e.g. as a function
+    /// reference.
+    fn of(name_ref: &ast::NameRef) -> Option<Call> {
+        if let Some(method_call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+            return Some(Call {
+                arg_list: method_call.arg_list()?,
+                generic_arg_list: method_call.generic_arg_list(),
+                is_method_call: true,
+            });
+        }
+        let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+        let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+        if path_expr.path()?.segment()? != segment {
+            return None;
+        }
+        let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+        if call.expr()?.syntax() != path_expr.syntax() {
+            return None;
+        }
+        Some(Call {
+            arg_list: call.arg_list()?,
+            generic_arg_list: segment.generic_arg_list(),
+            is_method_call: false,
+        })
+    }
+
+    /// The argument passed for the parameter at `position`, counting `self`.
+    fn argument(&self, position: usize) -> Option<ast::Expr> {
+        let position = if self.is_method_call { position.checked_sub(1)? } else { position };
+        self.arg_list.args().nth(position)
+    }
+}
+
+/// The new text of the argument `arg`, or `None` if it can't be converted.
+fn convert_argument(
+    ctx: &AssistContext,
+    arg: &ast::Expr,
+    from: ParamKind,
+    to: ParamKind,
+) -> Option<String> {
+    let unwrapped = match from {
+        // Removing the `&` of a place would move out of it, where it was only borrowed before.
+        ParamKind::DynRef => match arg {
+            ast::Expr::RefExpr(it) => it.expr().filter(|it| !is_place(ctx, it)),
+            _ => None,
+        },
+        ParamKind::BoxedDyn => boxed_expr(arg),
+        ParamKind::ImplTrait | ParamKind::Generic => None,
+    };
+    match (to, from) {
+        (ParamKind::ImplTrait | ParamKind::Generic, ParamKind::ImplTrait | ParamKind::Generic) => {
+            Some(arg.to_string())
+        }
+        // `&dyn Trait` and `Box<dyn Trait>` don't implement the trait themselves.
+        (ParamKind::ImplTrait | ParamKind::Generic, _) => Some(unwrapped?.to_string()),
+        (ParamKind::DynRef, _) => match (from, unwrapped) {
+            (_, Some(inner)) => Some(format!("&{}", atom(&inner))),
+            (ParamKind::BoxedDyn, None) => Some(format!("&*{}", atom(arg))),
+            _ => Some(format!("&{}", atom(arg))),
+        },
+        (ParamKind::BoxedDyn, _) => match (from, unwrapped) {
+            (_, Some(inner)) => Some(format!("Box::new({})", inner)),
+            // We can't move out of a reference to an unsized value.
+            (ParamKind::DynRef, None) => None,
+            _ => Some(format!("Box::new({})", arg)),
+        },
+    }
+}
+
+/// Whether `expr` is a place expression, like a local or a field, rather than a temporary.
+fn is_place(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(it) => match it.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(_) | PathResolution::Def(ModuleDef::Static(_))) | None => {
+                true
+            }
+            Some(_) => false,
+        },
+        ast::Expr::FieldExpr(_) | ast::Expr::IndexExpr(_) => true,
+        ast::Expr::PrefixExpr(it) => it.op_kind() == Some(ast::UnaryOp::Deref),
+        ast::Expr::ParenExpr(it) => it.expr().map_or(true, |it| is_place(ctx, &it)),
+        _ => false,
+    }
+}
+
+/// Returns `e` for `Box::new(e)`.
+fn boxed_expr(expr: &ast::Expr) -> Option<ast::Expr> {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    match call.expr()? {
+        ast::Expr::PathExpr(path) if path.syntax().text() == "Box::new" => {}
+        _ => return None,
+    }
+    call.arg_list()?.args().exactly_one().ok()
+}
+
+/// Renders `expr` so that it can be the operand of a prefix operator.
+fn atom(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::BinExpr(_)
+        | ast::Expr::CastExpr(_)
+        | ast::Expr::ClosureExpr(_)
+        | ast::Expr::RangeExpr(_)
+        | ast::Expr::ReturnExpr(_)
+        | ast::Expr::BreakExpr(_) => format!("({})", expr),
+        _ => expr.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn impl_trait_to_dyn_ref_updates_calls() {
+        check_assist(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(scale: u32, shape: impl $0Draw) {}
+fn main() {
+    render(1, Circle);
+    render(2, if true { Circle } else { Circle });
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(scale: u32, shape: &dyn Draw) {}
+fn main() {
+    render(1, &Circle);
+    render(2, &if true { Circle } else { Circle });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn multiple_bounds_are_parenthesized() {
+        check_assist(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+auto trait Send {}
+fn render(shape: $0impl Draw + Send) {}
+"#,
+            r#"
+trait Draw {}
+auto trait Send {}
+fn render(shape: &(dyn Draw + Send)) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_boxed_dyn_removes_generic() {
+        check_assist(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw {}
+auto trait Send {}
+struct Circle;
+impl Draw for Circle {}
+fn render<'a, S: Draw, C>(canvas: &'a C, shape: $0S)
+where
+    C: Clone,
+    S: Send,
+{
+}
+fn main() {
+    render(&(), Circle);
+}
+"#,
+            r#"
+trait Draw {}
+auto trait Send {}
+struct Circle;
+impl Draw for Circle {}
+fn render<'a, C>(canvas: &'a C, shape: Box<dyn Draw + Send>)
+where
+    C: Clone,
+{
+}
+fn main() {
+    render(&(), Box::new(Circle));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn dyn_ref_to_boxed_dyn_boxes_temporaries() {
+        check_assist(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: &dyn $0Draw) {}
+fn circle() -> Circle { Circle }
+fn main() {
+    render(&Circle);
+    render(&circle());
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: Box<dyn Draw>) {}
+fn circle() -> Circle { Circle }
+fn main() {
+    render(Box::new(Circle));
+    render(Box::new(circle()));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_arguments_cant_be_unwrapped() {
+        check_assist_not_applicable(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: &dyn $0Draw) {}
+fn main(other: &dyn Draw) {
+    render(&Circle);
+    render(other);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: &dyn $0Draw) {}
+fn main() {
+    let circle = Circle;
+    render(&circle);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_param_to_generic,
+            r#"
+trait Draw {}
+fn render(shape: Box<dyn $0Draw>) {}
+fn main(other: Box<dyn Draw>) {
+    render(other);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn boxed_dyn_to_impl_trait_unboxes_arguments() {
+        check_assist(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: Box<dyn $0Draw>) {}
+fn main() {
+    render(Box::new(Circle));
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: impl Draw) {}
+fn main() {
+    render(Circle);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn boxed_dyn_to_dyn_ref() {
+        check_assist(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: $0Box<dyn Draw>) {}
+fn main(shape: Box<dyn Draw>) {
+    render(Box::new(Circle));
+    render(shape);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: &dyn Draw) {}
+fn main(shape: Box<dyn Draw>) {
+    render(&Circle);
+    render(&*shape);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_extends_lists() {
+        check_assist(
+            convert_param_to_generic,
+            r#"
+trait Draw {}
+fn render<D>(d: D, shape: $0impl Draw) -> u32 where D: Copy { 0 }
+"#,
+            r#"
+trait Draw {}
+fn render<D, T>(d: D, shape: T) -> u32 where D: Copy, T: Draw { 0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_impl_trait() {
+        check_assist(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+fn render<S: Draw>(shape: $0S) {}
+"#,
+            r#"
+trait Draw {}
+fn render(shape: impl Draw) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrites_turbofish() {
+        check_assist(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render<'a, C, S: Draw>(canvas: &'a C, shape: $0S) {}
+fn fill<S: Draw>(shape: S) {}
+fn main() {
+    render::<'static, u8, Circle>(&1, Circle);
+    render::<u8, _>(&1, Circle);
+    render(&1, Circle);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render<'a, C>(canvas: &'a C, shape: impl Draw) {}
+fn fill<S: Draw>(shape: S) {}
+fn main() {
+    render::<'static, u8>(&1, Circle);
+    render::<u8>(&1, Circle);
+    render(&1, Circle);
+}
+"#,
+        );
+        check_assist(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn fill<S: Draw>(shape: $0S) {}
+fn main() {
+    fill::<Circle>(Circle);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn fill(shape: impl Draw) {}
+fn main() {
+    fill(Circle);
+}
+"#,
+        );
+        check_assist(
+            convert_param_to_generic,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render<C>(canvas: C, shape: $0impl Draw) {}
+fn main() {
+    render::<u8>(1, Circle);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render<C, D>(canvas: C, shape: D) where D: Draw {}
+fn main() {
+    render::<u8, _>(1, Circle);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_function_references() {
+        check_assist_not_applicable(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: $0impl Draw) {}
+fn main() {
+    [Circle].into_iter().for_each(render);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+fn render(shape: $0&dyn Draw) {}
+fn main() {
+    let r: fn(&dyn Draw) = render;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_calls() {
+        check_assist(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+struct Canvas;
+impl Canvas {
+    fn render(&self, shape: $0impl Draw) {}
+}
+fn main() {
+    Canvas.render(Circle);
+    Canvas::render(&Canvas, Circle);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+struct Canvas;
+impl Canvas {
+    fn render(&self, shape: &dyn Draw) {}
+}
+fn main() {
+    Canvas.render(&Circle);
+    Canvas::render(&Canvas, &Circle);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_object_safe() {
+        check_assist_not_applicable(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw { fn duplicate(&self) -> Self; }
+fn render(shape: $0impl Draw) {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw { fn draw<T>(&self, t: T); }
+fn render(shape: $0impl Draw) {}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw: Sized {}
+fn render(shape: $0impl Draw) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn two_principal_traits() {
+        check_assist_not_applicable(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+trait Resize {}
+fn render(shape: $0impl Draw + Resize) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn sized_methods_dont_affect_object_safety() {
+        check_assist(
+            convert_param_to_boxed_dyn,
+            r#"
+trait Draw {
+    fn draw(&mut self);
+    fn duplicate(&self) -> Self where Self: Sized;
+}
+fn render(shape: $0impl Draw) {}
+"#,
+            r#"
+trait Draw {
+    fn draw(&mut self);
+    fn duplicate(&self) -> Self where Self: Sized;
+}
+fn render(shape: Box<dyn Draw>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_used_elsewhere() {
+        check_assist_not_applicable(
+            convert_param_to_impl_trait,
+            r#"
+trait Draw {}
+fn render<S: Draw>(shape: $0S) -> S { shape }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_trait_impls() {
+        check_assist_not_applicable(
+            convert_param_to_dyn_ref,
+            r#"
+trait Draw {}
+trait Render { fn render(&self, shape: impl Draw); }
+impl Render for () {
+    fn render(&self, shape: $0impl Draw) {}
+}
+"#,
+        );
+    }
+}
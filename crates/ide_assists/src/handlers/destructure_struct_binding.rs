@@ -0,0 +1,364 @@
+use hir::{HasAttrs, HasVisibility, ScopeDef, StructKind};
+use ide_db::{
+    assists::{AssistId, AssistKind},
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    search::FileReference,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxNode,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    handlers::destructure_tuple_binding::handle_ref_field_usage,
+};
+
+// Assist: destructure_struct_binding
+//
+// Destructures a struct binding in place, binding the fields it uses.
+//
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+// fn main() {
+//     let $0p = Point { x: 1, y: 2, z: 3 };
+//     let sum = p.x + p.y;
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32, z: i32 }
+// fn main() {
+//     let Point { x, y, .. } = Point { x: 1, y: 2, z: 3 };
+//     let sum = x + y;
+// }
+// ```
+pub(crate) fn destructure_struct_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let ident_pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+    if ident_pat.at_token().is_some() {
+        return None;
+    }
+    // `S { p }` binds `p` to a field, it can't be replaced by a pattern.
+    let is_shorthand_field = ident_pat
+        .syntax()
+        .parent()
+        .and_then(ast::RecordPatField::cast)
+        .map_or(false, |it| it.colon_token().is_none());
+    if is_shorthand_field {
+        return None;
+    }
+
+    let ty = ctx.sema.type_of_pat(&ident_pat.clone().into())?.adjusted();
+    let is_ref = ty.is_reference() || ident_pat.ref_token().is_some();
+    let strukt = match ty.strip_references().as_adt()? {
+        hir::Adt::Struct(it) => it,
+        _ => return None,
+    };
+    let kind = strukt.kind(ctx.db());
+    if kind == StructKind::Unit {
+        return None;
+    }
+    let module = ctx.sema.scope(ident_pat.syntax()).module()?;
+    let struct_path =
+        mod_path_to_ast(&module.find_use_path(ctx.db(), hir::ModuleDef::from(strukt))?);
+    let fields = strukt.fields(ctx.db());
+
+    let local = ctx.sema.to_def(&ident_pat)?;
+    let source_file = ctx.sema.parse(ctx.file_id());
+    let mut used_fields = Vec::new();
+    let mut names_in_scope = names_in_scope(ctx, ident_pat.syntax());
+    for (_, references) in Definition::Local(local).usages(&ctx.sema).all() {
+        for FileReference { name, .. } in references {
+            if name.syntax().ancestors().last().as_ref() != Some(source_file.syntax()) {
+                return None;
+            }
+            // Uses of the whole value can't be expressed with the fields.
+            let field_expr = field_access(&name)?;
+            let field = ctx.sema.resolve_field(&field_expr)?;
+            names_in_scope.extend(self::names_in_scope(ctx, field_expr.syntax()));
+            used_fields.push((field, field_expr));
+        }
+    }
+
+    // Private fields and those of `#[non_exhaustive]` structs of other crates can't be named.
+    let has_hidden_fields = fields.iter().any(|it| !it.is_visible_from(ctx.db(), module))
+        || (strukt.module(ctx.db()).krate() != module.krate()
+            && strukt.attrs(ctx.db()).by_key("non_exhaustive").exists());
+    let bindings: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            if !used_fields.iter().any(|(it, _)| it == field) {
+                return None;
+            }
+            let name = match kind {
+                StructKind::Tuple => format!("_{}", field.name(ctx.db())),
+                _ => field.name(ctx.db()).to_string(),
+            };
+            let name = if names_in_scope.contains(&name) {
+                (1..).map(|n| format!("{}_{}", name, n)).find(|it| !names_in_scope.contains(it))?
+            } else {
+                name
+            };
+            Some((*field, name))
+        })
+        .collect();
+
+    let binding_pat = |name: &str| {
+        let mut buf = String::new();
+        if ident_pat.ref_token().is_some() {
+            buf.push_str("ref ");
+        }
+        if ident_pat.mut_token().is_some() {
+            buf.push_str("mut ");
+        }
+        buf.push_str(name);
+        buf
+    };
+    let pat = match kind {
+        StructKind::Tuple => {
+            let mut pats: Vec<_> = bindings
+                .iter()
+                .map(|it| it.as_ref().map_or("_".to_owned(), |(_, name)| binding_pat(name)))
+                .collect();
+            // Trailing wildcards collapse into `..`.
+            let wildcards = pats.iter().rev().take_while(|it| *it == "_").count();
+            if wildcards > 0 || has_hidden_fields {
+                pats.truncate(pats.len() - wildcards);
+                pats.push("..".to_owned());
+            }
+            format!("{}({})", struct_path, pats.iter().format(", "))
+        }
+        _ => {
+            let mut pats: Vec<_> = bindings
+                .iter()
+                .flatten()
+                .map(|(field, name)| {
+                    let field_name = field.name(ctx.db()).to_string();
+                    if *name == field_name {
+                        binding_pat(name)
+                    } else {
+                        format!("{}: {}", field_name, binding_pat(name))
+                    }
+                })
+                .collect();
+            if pats.len() < fields.len() || has_hidden_fields {
+                pats.push("..".to_owned());
+            }
+            format!("{} {{ {} }}", struct_path, pats.iter().format(", "))
+        }
+    };
+
+    acc.add(
+        AssistId("destructure_struct_binding", AssistKind::RefactorRewrite),
+        "Destructure struct binding",
+        ident_pat.syntax().text_range(),
+        |builder| {
+            builder.replace(ident_pat.syntax().text_range(), pat);
+            for (field, field_expr) in &used_fields {
+                let name = match bindings.iter().flatten().find(|(it, _)| it == field) {
+                    Some((_, name)) => name,
+                    None => continue,
+                };
+                if is_ref {
+                    let ref_data = handle_ref_field_usage(ctx, field_expr);
+                    builder.replace(ref_data.range, ref_data.format(name));
+                } else {
+                    builder.replace(field_expr.syntax().text_range(), name);
+                }
+            }
+        },
+    )
+}
+
+/// The field access `name` is the receiver of, looking through parentheses.
+fn field_access(name: &ast::NameLike) -> Option<ast::FieldExpr> {
+    let node = name
+        .syntax()
+        .ancestors()
+        .skip_while(|it| !ast::PathExpr::can_cast(it.kind()))
+        .skip(1)
+        .find(|it| !ast::ParenExpr::can_cast(it.kind()))?;
+    ast::FieldExpr::cast(node)
+}
+
+/// The names a new binding would clash with: locals, which it would shadow, and constants, which
+/// would turn it into a constant pattern.
+fn names_in_scope(ctx: &AssistContext, node: &SyntaxNode) -> FxHashSet<String> {
+    let mut names = FxHashSet::default();
+    ctx.sema.scope(node).process_all_names(&mut |name, def| {
+        if matches!(
+            def,
+            ScopeDef::Local(_)
+                | ScopeDef::ModuleDef(hir::ModuleDef::Const(_))
+                | ScopeDef::ModuleDef(hir::ModuleDef::Static(_))
+        ) {
+            names.insert(name.to_string());
+        }
+    });
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructure_let_binding() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+fn main() {
+    let $0p = Point { x: 1, y: 2 };
+    let sum = p.x + (p).y;
+}
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+fn main() {
+    let Point { x, y } = Point { x: 1, y: 2 };
+    let sum = x + y;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_mut_binding_with_unused_fields() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+fn main() {
+    let mut $0p = Point { x: 1, y: 2 };
+    p.y = 3;
+}
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+fn main() {
+    let Point { mut y, .. } = Point { x: 1, y: 2 };
+    y = 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_ref_param() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+}
+fn f($0p: &geo::Point) -> i32 {
+    p.x * 2
+}
+"#,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+}
+fn f(geo::Point { x, .. }: &geo::Point) -> i32 {
+    *x * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_match_arm_tuple_struct() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+//- minicore: option
+struct Pair(i32, i32, i32);
+fn f(o: Option<Pair>) -> i32 {
+    match o {
+        Some($0pair) => pair.1,
+        None => 0,
+    }
+}
+"#,
+            r#"
+struct Pair(i32, i32, i32);
+fn f(o: Option<Pair>) -> i32 {
+    match o {
+        Some(Pair(_, _1, ..)) => _1,
+        None => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn private_fields_and_name_clashes() {
+        check_assist(
+            destructure_struct_binding,
+            r#"
+mod m {
+    pub struct S { pub a: i32, b: i32 }
+    pub fn s() -> S { S { a: 1, b: 2 } }
+}
+fn main() {
+    let a = 1;
+    let $0s = m::s();
+    let x = s.a + a;
+}
+"#,
+            r#"
+mod m {
+    pub struct S { pub a: i32, b: i32 }
+    pub fn s() -> S { S { a: 1, b: 2 } }
+}
+fn main() {
+    let a = 1;
+    let m::S { a: a_1, .. } = m::s();
+    let x = a_1 + a;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_whole_value_is_used() {
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+fn take(_: Point) {}
+fn main() {
+    let $0p = Point { x: 1, y: 2 };
+    let x = p.x;
+    take(p);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_structs() {
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            r#"
+fn main() {
+    let $0p = (1, 2);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            destructure_struct_binding,
+            r#"
+struct S { p: i32 }
+fn f(S { $0p }: S) {}
+"#,
+        );
+    }
+}
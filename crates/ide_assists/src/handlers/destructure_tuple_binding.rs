@@ -306,13 +306,13 @@ fn detect_tuple_index(usage: &FileReference, data: &TupleData) -> Option<TupleIn
     }
 }
 
-struct RefData {
-    range: TextRange,
+pub(crate) struct RefData {
+    pub(crate) range: TextRange,
     needs_deref: bool,
     needs_parentheses: bool,
 }
 impl RefData {
-    fn format(&self, field_name: &str) -> String {
+    pub(crate) fn format(&self, field_name: &str) -> String {
         match (self.needs_deref, self.needs_parentheses) {
             (true, true) => format!("(*{})", field_name),
             (true, false) => format!("*{}", field_name),
@@ -321,7 +321,7 @@ impl RefData {
         }
     }
 }
-fn handle_ref_field_usage(ctx: &AssistContext, field_expr: &FieldExpr) -> RefData {
+pub(crate) fn handle_ref_field_usage(ctx: &AssistContext, field_expr: &FieldExpr) -> RefData {
     let s = field_expr.syntax();
     let mut ref_data =
         RefData { range: s.text_range(), needs_deref: true, needs_parentheses: true };
@@ -0,0 +1,600 @@
+use hir::{HirDisplay, ModuleDef, PathResolution};
+use ide_db::{
+    assists::{AssistId, AssistKind, GroupLabel},
+    syntax_helpers::node_ext::walk_expr,
+};
+use rustc_hash::FxHashSet;
+use stdx::{format_to, to_upper_snake_case};
+use syntax::{
+    ast::{self, edit::IndentLevel, HasName},
+    match_ast, AstNode, SyntaxNode,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    handlers::promote_local_to_const::is_body_const,
+    utils::suggest_name,
+};
+
+// Assist: extract_constant
+//
+// Extracts a constant expression into a `const` or `static` item, replacing all its identical
+// occurrences in the chosen function, impl or module.
+//
+// ```
+// fn area(radius: f64) -> f64 {
+//     $03.14159$0 * radius * radius
+// }
+// ```
+// ->
+// ```
+// fn area(radius: f64) -> f64 {
+//     const $0VAR_NAME: f64 = 3.14159;
+//     VAR_NAME * radius * radius
+// }
+// ```
+pub(crate) fn extract_constant(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if ctx.has_empty_selection() {
+        return None;
+    }
+    let expr = ctx.find_node_at_range::<ast::Expr>()?;
+    if expr.syntax().text_range() != ctx.selection_trimmed() {
+        return None;
+    }
+    // Constants are already named, and the initializer of an item is where it would go anyway.
+    if matches!(expr, ast::Expr::PathExpr(_))
+        || expr
+            .syntax()
+            .parent()
+            .map_or(false, |it| ast::Const::can_cast(it.kind()) || ast::Static::can_cast(it.kind()))
+    {
+        return None;
+    }
+    if !is_body_const(&ctx.sema, &expr) {
+        return None;
+    }
+    let uses_self = refers_to_self(ctx, &expr)?;
+
+    let ty = ctx.sema.type_of_expr(&expr)?.original;
+    if ty.contains_unknown() || ty.is_closure() || ty.is_unit() {
+        return None;
+    }
+    let module = ctx.sema.scope(expr.syntax()).module()?;
+    let ty = ty.display_source_code(ctx.db(), module.into()).ok()?;
+
+    let mut names_in_scope = FxHashSet::default();
+    ctx.sema.scope(expr.syntax()).process_all_names(&mut |name, _| {
+        names_in_scope.insert(name.to_string());
+    });
+
+    let group = GroupLabel("Extract into constant".to_owned());
+    for target in targets(&expr) {
+        if uses_self && !matches!(target.scope, Scope::Impl(_)) {
+            continue;
+        }
+        let mut names_in_scope = names_in_scope.clone();
+        if let Scope::Impl(imp) = &target.scope {
+            names_in_scope.extend(
+                imp.assoc_item_list()
+                    .into_iter()
+                    .flat_map(|it| it.assoc_items())
+                    .filter_map(|it| match it {
+                        ast::AssocItem::Const(it) => it.name(),
+                        ast::AssocItem::Fn(it) => it.name(),
+                        ast::AssocItem::TypeAlias(it) => it.name(),
+                        ast::AssocItem::MacroCall(_) => None,
+                    })
+                    .map(|it| it.to_string()),
+            );
+        }
+        let name = to_upper_snake_case(&suggest_name(ctx, &expr));
+        let name = if names_in_scope.contains(&name) {
+            (1..).map(|n| format!("{}_{}", name, n)).find(|it| !names_in_scope.contains(it))?
+        } else {
+            name
+        };
+
+        let kinds: &[_] = match target.scope {
+            Scope::Module => &["const", "static"],
+            _ => &["const"],
+        };
+        for &kind in kinds {
+            let label = format!(
+                "Extract into {} in {}",
+                if kind == "const" { "constant" } else { "static" },
+                target.scope.description()
+            );
+            acc.add_group(
+                &group,
+                AssistId("extract_constant", AssistKind::RefactorExtract),
+                label,
+                expr.syntax().text_range(),
+                |builder| {
+                    let occurrences = occurrences(ctx, &expr, &target);
+                    let usage = match target.scope {
+                        Scope::Impl(_) => format!("Self::{}", name),
+                        _ => name.clone(),
+                    };
+                    let anchor = match (&target.scope, occurrences.first()) {
+                        (Scope::Function(_), Some(first)) => first
+                            .ancestors()
+                            .find(|it| it.parent().as_ref() == Some(&target.node))
+                            .unwrap_or_else(|| target.anchor.clone()),
+                        _ => target.anchor.clone(),
+                    };
+                    let separator = match target.scope {
+                        Scope::Function(_) => "\n",
+                        _ => "\n\n",
+                    };
+                    let indent = IndentLevel::from_node(&anchor);
+                    let mut item = format!("{} ", kind);
+                    if ctx.config.snippet_cap.is_some() {
+                        item.push_str("$0");
+                    }
+                    format_to!(item, "{}: {} = {};{}{}", name, ty, expr, separator, indent);
+                    let anchor_start = anchor.text_range().start();
+                    let mut inserted = false;
+                    for range in occurrences.iter().map(|it| it.text_range()) {
+                        if range.start() == anchor_start {
+                            inserted = true;
+                            match ctx.config.snippet_cap {
+                                Some(cap) => builder.replace_snippet(
+                                    cap,
+                                    range,
+                                    format!("{}{}", item, usage),
+                                ),
+                                None => builder.replace(range, format!("{}{}", item, usage)),
+                            }
+                        } else {
+                            builder.replace(range, usage.clone());
+                        }
+                    }
+                    if !inserted {
+                        match ctx.config.snippet_cap {
+                            Some(cap) => builder.insert_snippet(cap, anchor_start, item),
+                            None => builder.insert(anchor_start, item),
+                        }
+                    }
+                },
+            );
+        }
+    }
+    Some(())
+}
+
+enum Scope {
+    Function(ast::Fn),
+    Impl(ast::Impl),
+    Module,
+}
+
+impl Scope {
+    fn description(&self) -> String {
+        match self {
+            Scope::Function(func) => match func.name() {
+                Some(name) => format!("function `{}`", name),
+                None => "function".to_owned(),
+            },
+            Scope::Impl(_) => "impl".to_owned(),
+            Scope::Module => "module".to_owned(),
+        }
+    }
+}
+
+struct Target {
+    scope: Scope,
+    /// The node in which occurrences of the expression are replaced.
+    node: SyntaxNode,
+    /// The statement or item the new item is inserted before, unless an earlier statement of
+    /// the function uses the expression too.
+    anchor: SyntaxNode,
+}
+
+/// The scopes the expression can be extracted to, innermost first.
+fn targets(expr: &ast::Expr) -> Vec<Target> {
+    let mut targets = Vec::new();
+    let func = expr.syntax().ancestors().find_map(ast::Fn::cast);
+    if let Some(func) = &func {
+        let body = func.body().and_then(|it| it.stmt_list());
+        if let Some(body) = body {
+            let anchor =
+                expr.syntax().ancestors().find(|it| it.parent().as_ref() == Some(body.syntax()));
+            if let Some(anchor) = anchor {
+                targets.push(Target {
+                    scope: Scope::Function(func.clone()),
+                    node: body.syntax().clone(),
+                    anchor,
+                });
+            }
+        }
+        // Trait impls can't have items their trait doesn't declare.
+        let imp = func
+            .syntax()
+            .parent()
+            .and_then(ast::AssocItemList::cast)
+            .and_then(|it| it.syntax().parent())
+            .and_then(ast::Impl::cast)
+            .filter(|it| it.trait_().is_none());
+        if let Some(imp) = imp {
+            targets.push(Target {
+                scope: Scope::Impl(imp.clone()),
+                node: imp.syntax().clone(),
+                anchor: func.syntax().clone(),
+            });
+        }
+    }
+    let item = expr.syntax().ancestors().find(|it| {
+        it.parent().map_or(false, |parent| {
+            ast::SourceFile::can_cast(parent.kind()) || ast::ItemList::can_cast(parent.kind())
+        })
+    });
+    if let Some(item) = item {
+        if let Some(parent) = item.parent() {
+            targets.push(Target { scope: Scope::Module, node: parent, anchor: item });
+        }
+    }
+    targets
+}
+
+/// Whether the expression refers to `Self`, or `None` if it refers to something only available
+/// in the body of its function.
+fn refers_to_self(ctx: &AssistContext, expr: &ast::Expr) -> Option<bool> {
+    let mut uses_self = false;
+    let mut valid = true;
+    walk_expr(expr, &mut |expr| {
+        let path = match expr {
+            ast::Expr::PathExpr(it) => it.path(),
+            ast::Expr::RecordExpr(it) => it.path(),
+            _ => None,
+        };
+        let mut qualifier = path;
+        while let Some(path) = qualifier {
+            match ctx.sema.resolve_path(&path) {
+                Some(PathResolution::SelfType(_)) => uses_self = true,
+                Some(
+                    PathResolution::Local(_)
+                    | PathResolution::TypeParam(_)
+                    | PathResolution::ConstParam(_),
+                ) => valid = false,
+                _ => (),
+            }
+            qualifier = path.qualifier();
+        }
+    });
+    valid.then(|| uses_self)
+}
+
+/// The name of the field or variable the expression initializes, or else a name derived from
+/// the expression itself.
+fn suggest_name(ctx: &AssistContext, expr: &ast::Expr) -> String {
+    let parent = expr.syntax().parent();
+    let name = parent
+        .clone()
+        .and_then(ast::RecordExprField::cast)
+        .and_then(|it| it.name_ref())
+        .map(|it| it.to_string())
+        .or_else(|| match parent.and_then(ast::LetStmt::cast)?.pat()? {
+            ast::Pat::IdentPat(it) => Some(it.name()?.to_string()),
+            _ => None,
+        });
+    name.unwrap_or_else(|| suggest_name::for_variable(expr, &ctx.sema))
+}
+
+/// The expressions in the target identical to `expr`, ignoring trivia, which have the same type
+/// and refer to the same items.
+fn occurrences(ctx: &AssistContext, expr: &ast::Expr, target: &Target) -> Vec<SyntaxNode> {
+    let tokens = |node: &SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| !it.kind().is_trivia())
+            .map(|it| it.text().to_string())
+            .collect::<Vec<_>>()
+    };
+    let ty = |expr: &ast::Expr| ctx.sema.type_of_expr(expr).map(|it| it.original);
+    let resolutions = |node: &SyntaxNode| {
+        node.descendants()
+            .filter_map(|node| {
+                match_ast! {
+                    match node {
+                        ast::Path(it) => Some(ctx.sema.resolve_path(&it)),
+                        ast::MethodCallExpr(it) => Some(
+                            ctx.sema
+                                .resolve_method_call(&it)
+                                .map(|it| PathResolution::Def(ModuleDef::Function(it))),
+                        ),
+                        _ => None,
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let expected = tokens(expr.syntax());
+    let expected_ty = ty(expr);
+    let expected_resolutions = resolutions(expr.syntax());
+    let mut occurrences: Vec<SyntaxNode> = Vec::new();
+    for node in target.node.descendants() {
+        let candidate = match ast::Expr::cast(node.clone()) {
+            Some(it) => it,
+            None => continue,
+        };
+        if occurrences.iter().any(|it| it.text_range().contains_range(node.text_range())) {
+            continue;
+        }
+        // Nested modules would have to refer to the new item through `super`.
+        let in_nested_module = node
+            .ancestors()
+            .take_while(|it| *it != target.node)
+            .any(|it| ast::Module::can_cast(it.kind()));
+        if in_nested_module {
+            continue;
+        }
+        if tokens(&node) == expected
+            && ty(&candidate) == expected_ty
+            && resolutions(&node) == expected_resolutions
+        {
+            occurrences.push(node);
+        }
+    }
+    occurrences
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_into_module_replacing_occurrences() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+fn area(radius: f64) -> f64 {
+    $03.14159$0 * radius * radius
+}
+fn circumference(radius: f64) -> f64 {
+    2.0 * 3.14159 * radius
+}
+mod nested {
+    fn pi() -> f64 { 3.14159 }
+}
+"#,
+            r#"
+const $0VAR_NAME: f64 = 3.14159;
+
+fn area(radius: f64) -> f64 {
+    VAR_NAME * radius * radius
+}
+fn circumference(radius: f64) -> f64 {
+    2.0 * VAR_NAME * radius
+}
+mod nested {
+    fn pi() -> f64 { 3.14159 }
+}
+"#,
+            "Extract into constant in module",
+        );
+    }
+
+    #[test]
+    fn extract_into_static_in_module() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+mod m {
+    fn greet() -> &'static str {
+        $0"hello"$0
+    }
+}
+"#,
+            r#"
+mod m {
+    static $0VAR_NAME: &str = "hello";
+
+    fn greet() -> &'static str {
+        VAR_NAME
+    }
+}
+"#,
+            "Extract into static in module",
+        );
+    }
+
+    #[test]
+    fn extract_into_function() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+fn f(retries: u32) -> u32 {
+    let a = retries + double(4);
+    let b = retries * double( 4 );
+    $0double(4)$0 + a + b
+}
+fn g() -> u32 { double(4) }
+"#,
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+fn f(retries: u32) -> u32 {
+    const $0DOUBLE: u32 = double(4);
+    let a = retries + DOUBLE;
+    let b = retries * DOUBLE;
+    DOUBLE + a + b
+}
+fn g() -> u32 { double(4) }
+"#,
+            "Extract into constant in function `f`",
+        );
+    }
+
+    #[test]
+    fn skips_occurrences_of_other_types_or_items() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+const N: u32 = 1;
+fn f(a: u8, b: i64) -> u32 {
+    let offset = $0N + 1$0;
+    let inner = { const N: u32 = 2; N + 1 };
+    let small = a + 3;
+    let big = b + 3;
+    offset + inner
+}
+"#,
+            r#"
+const N: u32 = 1;
+fn f(a: u8, b: i64) -> u32 {
+    const $0OFFSET: u32 = N + 1;
+    let offset = OFFSET;
+    let inner = { const N: u32 = 2; N + 1 };
+    let small = a + 3;
+    let big = b + 3;
+    offset + inner
+}
+"#,
+            "Extract into constant in function `f`",
+        );
+        check_assist_by_label(
+            extract_constant,
+            r#"
+fn f(a: u8, b: i64) {
+    let small = a + $03$0;
+    let big = b + 3;
+    let other = a * 3;
+}
+"#,
+            r#"
+fn f(a: u8, b: i64) {
+    const $0VAR_NAME: u8 = 3;
+    let small = a + VAR_NAME;
+    let big = b + 3;
+    let other = a * VAR_NAME;
+}
+"#,
+            "Extract into constant in function `f`",
+        );
+    }
+
+    #[test]
+    fn names_after_field_or_variable() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+struct Config { capacity: usize }
+fn f() -> Config {
+    Config { capacity: $01024 * 4$0 }
+}
+"#,
+            r#"
+struct Config { capacity: usize }
+fn f() -> Config {
+    const $0CAPACITY: usize = 1024 * 4;
+    Config { capacity: CAPACITY }
+}
+"#,
+            "Extract into constant in function `f`",
+        );
+    }
+
+    #[test]
+    fn extract_tail_into_function() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+fn f() -> [u8; 4] {
+    $0[0; 4]$0
+}
+"#,
+            r#"
+fn f() -> [u8; 4] {
+    const $0VAR_NAME: [u8; 4] = [0; 4];
+    VAR_NAME
+}
+"#,
+            "Extract into constant in function `f`",
+        );
+    }
+
+    #[test]
+    fn extract_into_impl() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+struct Buffer;
+impl Buffer {
+    fn capacity(&self) -> usize {
+        $01024 * 4$0
+    }
+    fn is_full(&self, len: usize) -> bool {
+        len == 1024 * 4
+    }
+}
+"#,
+            r#"
+struct Buffer;
+impl Buffer {
+    const $0VAR_NAME: usize = 1024 * 4;
+
+    fn capacity(&self) -> usize {
+        Self::VAR_NAME
+    }
+    fn is_full(&self, len: usize) -> bool {
+        len == Self::VAR_NAME
+    }
+}
+"#,
+            "Extract into constant in impl",
+        );
+    }
+
+    #[test]
+    fn avoids_name_clashes() {
+        check_assist_by_label(
+            extract_constant,
+            r#"
+const VAR_NAME: i32 = 0;
+fn f() -> i32 {
+    $042$0 + VAR_NAME
+}
+"#,
+            r#"
+const VAR_NAME: i32 = 0;
+const $0VAR_NAME_1: i32 = 42;
+
+fn f() -> i32 {
+    VAR_NAME_1 + VAR_NAME
+}
+"#,
+            "Extract into constant in module",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_const_expressions() {
+        check_assist_not_applicable(
+            extract_constant,
+            r#"
+fn f(x: i32) -> i32 {
+    $0x + 1$0
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_constant,
+            r#"
+fn g() -> i32 { 1 }
+fn f() -> i32 {
+    $0g() + 1$0
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_constant,
+            r#"
+fn f() {
+    $0()$0
+}
+"#,
+        );
+    }
+}
@@ -922,7 +922,7 @@ fn reference_is_exclusive(
 }
 
 /// checks if this expr requires `&mut` access, recurses on field access
-fn expr_require_exclusive_access(ctx: &AssistContext, expr: &ast::Expr) -> Option<bool> {
+pub(crate) fn expr_require_exclusive_access(ctx: &AssistContext, expr: &ast::Expr) -> Option<bool> {
     if let ast::Expr::MacroCall(_) = expr {
         // FIXME: expand macro and check output for mutable usages of the variable?
         return None;
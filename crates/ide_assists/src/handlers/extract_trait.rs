@@ -0,0 +1,607 @@
+use hir::{ModuleDef, PathResolution};
+use ide_db::{
+    base_db::FileId,
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    search::FileReference,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{
+        self,
+        edit_in_place::{AttrsOwnerEdit, Indent},
+        make, HasGenericParams, HasVisibility,
+    },
+    ted::{self, Position},
+    AstNode, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_trait
+//
+// Moves the methods of an inherent impl into a new trait implemented by the type. Only the
+// selected methods are moved if there is a selection. Modules calling the methods import the
+// trait.
+//
+// ```
+// struct Counter(u32);
+//
+// impl $0Counter {
+//     pub fn get(&self) -> u32 {
+//         self.0
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+//
+// pub trait CounterExt {
+//     fn get(&self) -> u32;
+// }
+//
+// impl CounterExt for Counter {
+//     fn get(&self) -> u32 {
+//         self.0
+//     }
+// }
+// ```
+pub(crate) fn extract_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+    if impl_.trait_().is_some() || impl_.generic_param_list().is_some() {
+        return None;
+    }
+    let self_ty = match impl_.self_ty()? {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let self_name = self_ty.segment()?.name_ref()?;
+    let items = impl_.assoc_item_list()?;
+
+    // Trait methods can be neither `const` nor `async`, so those stay in the inherent impl.
+    let fns = items.assoc_items().filter_map(|item| match item {
+        ast::AssocItem::Fn(it) if it.const_token().is_none() && it.async_token().is_none() => {
+            Some(it)
+        }
+        _ => None,
+    });
+    let selected: Vec<_> = if ctx.has_empty_selection() {
+        // On the header, all methods are extracted.
+        if ctx.offset() >= items.syntax().text_range().start() {
+            return None;
+        }
+        fns.collect()
+    } else {
+        let selection = ctx.selection_trimmed();
+        fns.filter(|it| it.syntax().text_range().intersect(selection).is_some()).collect()
+    };
+    if selected.is_empty() {
+        return None;
+    }
+    let moves_all = selected.len() == items.assoc_items().count();
+
+    let impl_module = ctx.sema.to_def(&impl_)?.module(ctx.db());
+    let trait_name = format!("{}Ext", self_name);
+    let funcs = selected.iter().map(|it| ctx.sema.to_def(it)).collect::<Option<Vec<_>>>()?;
+
+    let target = impl_.syntax().text_range();
+    acc.add(
+        AssistId("extract_trait", AssistKind::RefactorExtract),
+        format!("Extract trait `{}`", trait_name),
+        target,
+        |builder| {
+            let imports = imports_needed(ctx, &funcs, impl_module, &trait_name);
+            let mut def_file_imports = None;
+            for (file_id, imports) in imports {
+                if file_id == ctx.file_id() {
+                    def_file_imports = Some(imports);
+                    continue;
+                }
+                builder.edit_file(file_id);
+                let imports: Vec<_> = imports
+                    .into_iter()
+                    .map(|(node, path)| (builder.make_syntax_mut(node), path))
+                    .collect();
+                insert_imports(ctx, imports);
+            }
+            builder.edit_file(ctx.file_id());
+            let def_file_imports: Vec<_> = def_file_imports
+                .into_iter()
+                .flatten()
+                .map(|(node, path)| (builder.make_syntax_mut(node), path))
+                .collect();
+
+            let impl_ = builder.make_mut(impl_.clone());
+            let selected: Vec<_> = selected.iter().map(|it| builder.make_mut(it.clone())).collect();
+            let indent = impl_.indent_level();
+
+            let vis = selected.iter().find_map(|it| it.visibility());
+            let trait_ = make::trait_(vis, make::name(&trait_name)).clone_for_update();
+            if let Some(trait_items) = trait_.assoc_item_list() {
+                for fn_ in &selected {
+                    let signature = method_signature(fn_);
+                    signature.dedent(indent);
+                    trait_items.add_item(ast::AssocItem::Fn(signature));
+                }
+            }
+            trait_.reindent_to(indent);
+            ted::insert_all(
+                Position::before(impl_.syntax()),
+                vec![
+                    trait_.syntax().clone().into(),
+                    make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                ],
+            );
+
+            if moves_all {
+                selected.iter().for_each(remove_visibility);
+                let trait_impl =
+                    make::impl_trait(make::ext::ident_path(&trait_name), self_ty.clone(), None)
+                        .clone_for_update();
+                // The attributes and comments of the impl are kept along with its items.
+                let leading: Vec<_> = impl_
+                    .syntax()
+                    .children_with_tokens()
+                    .take_while(|it| it.kind() != T![impl])
+                    .collect();
+                leading.iter().for_each(|it| it.detach());
+                ted::insert_all_raw(Position::first_child_of(trait_impl.syntax()), leading);
+                if let Some(items) = impl_.assoc_item_list() {
+                    items.syntax().detach();
+                    ted::replace(
+                        trait_impl.get_or_create_assoc_item_list().syntax(),
+                        items.syntax(),
+                    );
+                }
+                ted::replace(impl_.syntax(), trait_impl.syntax());
+            } else {
+                let trait_impl =
+                    make::impl_trait(make::ext::ident_path(&trait_name), self_ty.clone(), None)
+                        .clone_for_update();
+                let trait_impl_items = trait_impl.get_or_create_assoc_item_list();
+                for fn_ in selected {
+                    remove_item(fn_.syntax());
+                    remove_visibility(&fn_);
+                    fn_.dedent(indent);
+                    trait_impl_items.add_item(ast::AssocItem::Fn(fn_));
+                }
+                trait_impl.reindent_to(indent);
+                ted::insert_all(
+                    Position::after(impl_.syntax()),
+                    vec![
+                        make::tokens::whitespace(&format!("\n\n{}", indent)).into(),
+                        trait_impl.syntax().clone().into(),
+                    ],
+                );
+            }
+
+            insert_imports(ctx, def_file_imports);
+        },
+    )
+}
+
+/// For every module calling one of `funcs`, a node in that module along with the path of the
+/// trait to import there.
+fn imports_needed(
+    ctx: &AssistContext,
+    funcs: &[hir::Function],
+    impl_module: hir::Module,
+    trait_name: &str,
+) -> FxHashMap<FileId, Vec<(SyntaxNode, ast::Path)>> {
+    let mut visited = FxHashSet::default();
+    visited.insert(impl_module);
+    let mut res: FxHashMap<_, Vec<_>> = FxHashMap::default();
+    for &func in funcs {
+        for (file_id, references) in Definition::Function(func).usages(&ctx.sema).all() {
+            for FileReference { name, .. } in references {
+                let node = name.syntax().clone();
+                let module = match ctx.sema.scope(&node).module() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if !visited.insert(module) || glob_imports_module(ctx, &node, impl_module) {
+                    continue;
+                }
+                let module_path = module.find_use_path_prefixed(
+                    ctx.db(),
+                    ModuleDef::Module(impl_module),
+                    ctx.config.insert_use.prefix_kind,
+                );
+                let trait_path = make::ext::ident_path(trait_name);
+                let path = match module_path {
+                    Some(it) if it.segments().is_empty() && it.is_ident() => trait_path,
+                    Some(it) => make::path_concat(mod_path_to_ast(&it), trait_path),
+                    None => continue,
+                };
+                res.entry(file_id).or_default().push((node, path));
+            }
+        }
+    }
+    res
+}
+
+/// Whether the scope around `node` already has a `use module::*;`.
+fn glob_imports_module(ctx: &AssistContext, node: &SyntaxNode, module: hir::Module) -> bool {
+    let scope = match ImportScope::find_insert_use_container(node, &ctx.sema) {
+        Some(it) => it,
+        None => return false,
+    };
+    scope
+        .as_syntax_node()
+        .children()
+        .filter_map(ast::Use::cast)
+        .filter_map(|it| it.use_tree())
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast).collect::<Vec<_>>())
+        .filter(|it| it.star_token().is_some())
+        .filter_map(|it| it.path())
+        .any(|path| {
+            matches!(
+                ctx.sema.resolve_path(&path),
+                Some(PathResolution::Def(ModuleDef::Module(it))) if it == module
+            )
+        })
+}
+
+fn insert_imports(ctx: &AssistContext, imports: Vec<(SyntaxNode, ast::Path)>) {
+    for (node, path) in imports {
+        if let Some(scope) = ImportScope::find_insert_use_container(&node, &ctx.sema) {
+            insert_use(&scope, path, &ctx.config.insert_use);
+        }
+    }
+}
+
+/// The method's declaration as a trait item, without a body.
+fn method_signature(fn_: &ast::Fn) -> ast::Fn {
+    let signature = fn_.clone_subtree().clone_for_update();
+    remove_visibility(&signature);
+    signature.remove_attrs_and_docs();
+    if let Some(body) = signature.body() {
+        if let Some(ws) = body.syntax().prev_sibling_or_token() {
+            if ws.kind() == SyntaxKind::WHITESPACE {
+                ted::remove(ws);
+            }
+        }
+        ted::replace(body.syntax(), make::token(T![;]));
+    }
+    if let Some(comma) = signature
+        .where_clause()
+        .and_then(|it| it.syntax().last_token())
+        .filter(|it| it.kind() == T![,])
+    {
+        ted::remove(comma);
+    }
+    signature
+}
+
+fn remove_visibility(fn_: &ast::Fn) {
+    if let Some(vis) = fn_.visibility() {
+        if let Some(ws) = vis.syntax().next_sibling_or_token() {
+            if ws.kind() == SyntaxKind::WHITESPACE {
+                ted::remove(ws);
+            }
+        }
+        ted::remove(vis.syntax());
+    }
+}
+
+/// Removes an item from a list along with the whitespace separating it from its neighbours.
+fn remove_item(item: &SyntaxNode) {
+    let prev = item.prev_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+    let follows_open_brace = prev
+        .as_ref()
+        .and_then(|it| it.prev_sibling_or_token())
+        .map_or(true, |it| it.kind() == T!['{']);
+    let ws = if follows_open_brace {
+        item.next_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+    } else {
+        prev
+    };
+    if let Some(ws) = ws {
+        ted::remove(ws);
+    }
+    ted::remove(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_all_methods() {
+        check_assist(
+            extract_trait,
+            r#"
+mod shapes {
+    pub struct Circle { r: f64 }
+
+    impl Circle$0 {
+        /// The area.
+        pub fn area(&self) -> f64 {
+            self.r * self.r * 3.14
+        }
+
+        pub(crate) fn scale<T>(&mut self, by: T)
+        where
+            T: Into<f64>,
+        {
+            self.r *= by.into();
+        }
+    }
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Circle { r: f64 }
+
+    pub trait CircleExt {
+        fn area(&self) -> f64;
+
+        fn scale<T>(&mut self, by: T)
+        where
+            T: Into<f64>;
+    }
+
+    impl CircleExt for Circle {
+        /// The area.
+        fn area(&self) -> f64 {
+            self.r * self.r * 3.14
+        }
+
+        fn scale<T>(&mut self, by: T)
+        where
+            T: Into<f64>,
+        {
+            self.r *= by.into();
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_selected_methods() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Counter(u32);
+
+impl Counter {
+    fn new() -> Self {
+        Counter(0)
+    }
+
+    $0fn get(&self) -> u32 {
+        self.0
+    }$0
+
+    fn bump(&mut self) {
+        self.0 += 1;
+    }
+}
+"#,
+            r#"
+struct Counter(u32);
+
+trait CounterExt {
+    fn get(&self) -> u32;
+}
+
+impl Counter {
+    fn new() -> Self {
+        Counter(0)
+    }
+
+    fn bump(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl CounterExt for Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn imports_trait_at_call_sites() {
+        check_assist(
+            extract_trait,
+            r#"
+//- /main.rs
+mod counter;
+mod user;
+
+fn main() {
+    counter::Counter(1).get();
+}
+
+mod tests {
+    use super::counter::*;
+
+    fn check() {
+        super::counter::Counter(2).get();
+    }
+}
+//- /counter.rs
+pub struct Counter(pub u32);
+
+impl $0Counter {
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+fn local() {
+    Counter(0).get();
+}
+//- /user.rs
+use crate::counter::Counter;
+
+fn user() {
+    Counter::get(&Counter(3));
+}
+"#,
+            r#"
+//- /main.rs
+use counter::CounterExt;
+
+mod counter;
+mod user;
+
+fn main() {
+    counter::Counter(1).get();
+}
+
+mod tests {
+    use super::counter::*;
+
+    fn check() {
+        super::counter::Counter(2).get();
+    }
+}
+//- /counter.rs
+pub struct Counter(pub u32);
+
+pub trait CounterExt {
+    fn get(&self) -> u32;
+}
+
+impl CounterExt for Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+fn local() {
+    Counter(0).get();
+}
+//- /user.rs
+use crate::counter::{Counter, CounterExt};
+
+fn user() {
+    Counter::get(&Counter(3));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_const_and_async_methods() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Counter(u32);
+
+/// Counting.
+impl $0Counter {
+    pub const fn new() -> Self {
+        Counter(0)
+    }
+
+    pub async fn wait(&self) {}
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+            r#"
+struct Counter(u32);
+
+pub trait CounterExt {
+    fn get(&self) -> u32;
+}
+
+/// Counting.
+impl Counter {
+    pub const fn new() -> Self {
+        Counter(0)
+    }
+
+    pub async fn wait(&self) {}
+}
+
+impl CounterExt for Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct Counter(u32);
+impl $0Counter {
+    pub const fn new() -> Self {
+        Counter(0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_impl_attributes() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Counter(u32);
+
+/// Counting.
+#[allow(dead_code)]
+impl $0Counter {
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+            r#"
+struct Counter(u32);
+
+pub trait CounterExt {
+    fn get(&self) -> u32;
+}
+
+/// Counting.
+#[allow(dead_code)]
+impl CounterExt for Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_on_trait_impls_or_inside_methods() {
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct S;
+trait T { fn f(&self); }
+impl T for $0S {
+    fn f(&self) {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct S;
+impl S {
+    fn f(&self) { $0 }
+}
+"#,
+        );
+    }
+}
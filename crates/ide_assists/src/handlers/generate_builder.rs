@@ -0,0 +1,397 @@
+use ide_db::famous_defs::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, AstNode, HasGenericParams, HasName, HasVisibility, StructKind},
+    SourceFile,
+};
+
+use crate::{
+    utils::{find_struct_impl, generate_impl_text},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_builder
+//
+// Generates a builder for a struct, with a chainable setter for every field and a `build` method
+// failing when a required field is missing. Fields of `Option` or `Default` types are optional.
+//
+// ```
+// pub struct Person {
+//     name: String,
+//     age: u32,$0
+// }
+// ```
+// ->
+// ```
+// pub struct Person {
+//     name: String,
+//     age: u32,
+// }
+//
+// impl Person {
+//     pub fn builder() -> PersonBuilder {
+//         PersonBuilder { name: None, age: None }
+//     }
+// }
+//
+// pub struct $0PersonBuilder {
+//     name: Option<String>,
+//     age: Option<u32>,
+// }
+//
+// impl PersonBuilder {
+//     pub fn name(mut self, name: String) -> Self {
+//         self.name = Some(name);
+//         self
+//     }
+//
+//     pub fn age(mut self, age: u32) -> Self {
+//         self.age = Some(age);
+//         self
+//     }
+//
+//     pub fn build(self) -> Result<Person, &'static str> {
+//         Ok(Person {
+//             name: self.name.ok_or("missing field `name`")?,
+//             age: self.age.ok_or("missing field `age`")?,
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+    let adt = ast::Adt::Struct(strukt.clone());
+    find_struct_impl(ctx, &adt, "builder")?;
+
+    let name = strukt.name()?;
+    let builder_name = format!("{}Builder", name);
+    let module = ctx.sema.to_def(&strukt)?.module(ctx.db());
+    let already_defined =
+        module.scope(ctx.db(), None).into_iter().any(|(name, _)| name.to_string() == builder_name);
+    if already_defined {
+        return None;
+    }
+    let fields = field_list
+        .fields()
+        .map(|field| BuilderField::new(ctx, field))
+        .collect::<Option<Vec<_>>>()?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_builder", AssistKind::Generate),
+        "Generate a builder",
+        target,
+        |builder| {
+            let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+            let generic_params = strukt.generic_param_list();
+            let generic_args = generic_params.as_ref().map_or(String::new(), generic_args);
+
+            let mut buf = String::with_capacity(1024);
+            let nones = fields.iter().map(|it| format!("{}: None", it.name)).format(", ");
+            let builder_fn = format!(
+                "    {}fn builder() -> {}{} {{\n        {} {{ {} }}\n    }}",
+                vis, builder_name, generic_args, builder_name, nones
+            );
+            buf.push_str(&generate_impl_text(&adt, &builder_fn));
+
+            let mut builder_strukt = format!(
+                "{}struct {}{}",
+                vis,
+                builder_name,
+                generic_params.as_ref().map_or(String::new(), |it| it.to_string())
+            );
+            match strukt.where_clause() {
+                Some(where_clause) => format_to!(builder_strukt, "\n{}\n{{\n", where_clause),
+                None => builder_strukt.push_str(" {\n"),
+            }
+            for field in &fields {
+                format_to!(builder_strukt, "    {}: Option<{}>,\n", field.name, field.value_ty);
+            }
+            builder_strukt.push('}');
+            format_to!(buf, "\n\n{}", builder_strukt);
+
+            let mut methods: Vec<_> = fields.iter().map(|field| field.setter(&vis)).collect();
+            let build_fields: String = fields.iter().map(BuilderField::build_field).collect();
+            let mut build_fn = String::new();
+            format_to!(
+                build_fn,
+                "    {}fn build(self) -> Result<{}{}, &'static str> {{\n",
+                vis,
+                name,
+                generic_args
+            );
+            format_to!(build_fn, "        Ok({} {{\n{}        }})\n    }}", name, build_fields);
+            methods.push(build_fn);
+            let builder_adt = SourceFile::parse(&builder_strukt)
+                .tree()
+                .syntax()
+                .descendants()
+                .find_map(ast::Adt::cast);
+            if let Some(builder_adt) = builder_adt {
+                buf.push_str(&generate_impl_text(&builder_adt, &methods.join("\n\n")));
+            }
+
+            let offset = strukt.syntax().text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) => {
+                    let buf = buf.replacen(
+                        &format!("struct {}", builder_name),
+                        &format!("struct $0{}", builder_name),
+                        1,
+                    );
+                    builder.insert_snippet(cap, offset, buf);
+                }
+                None => builder.insert(offset, buf),
+            }
+        },
+    )
+}
+
+struct BuilderField {
+    name: String,
+    /// The type accepted by the setter, without the `Option` of optional fields.
+    value_ty: String,
+    kind: FieldKind,
+}
+
+enum FieldKind {
+    /// `build` fails when the field isn't set.
+    Required,
+    /// The field is an `Option`, left `None` when not set.
+    Option,
+    /// The field implements `Default`, used when not set.
+    Default,
+}
+
+impl BuilderField {
+    fn new(ctx: &AssistContext, field: ast::RecordField) -> Option<BuilderField> {
+        let name = field.name()?.to_string();
+        let ty = field.ty()?;
+        let hir_ty = ctx.sema.to_def(&field)?.ty(ctx.db());
+        let famous_defs = FamousDefs(&ctx.sema, ctx.sema.scope(field.syntax()).krate());
+
+        let is_option = famous_defs
+            .core_option_Option()
+            .map_or(false, |option| hir_ty.as_adt() == Some(hir::Adt::Enum(option)));
+        if is_option {
+            if let Some(inner) = option_arg(&ty) {
+                return Some(BuilderField {
+                    name,
+                    value_ty: inner.to_string(),
+                    kind: FieldKind::Option,
+                });
+            }
+        }
+        let is_default = famous_defs
+            .core_default_Default()
+            .map_or(false, |default| hir_ty.impls_trait(ctx.db(), default, &[]));
+        let kind = if is_default { FieldKind::Default } else { FieldKind::Required };
+        Some(BuilderField { name, value_ty: ty.to_string(), kind })
+    }
+
+    fn setter(&self, vis: &str) -> String {
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "    {}fn {}(mut self, {}: {}) -> Self {{\n",
+            vis,
+            self.name,
+            self.name,
+            self.value_ty
+        );
+        format_to!(buf, "        self.{} = Some({});\n", self.name, self.name);
+        buf.push_str("        self\n    }");
+        buf
+    }
+
+    /// The initializer of the field in `build`.
+    fn build_field(&self) -> String {
+        let value = match self.kind {
+            FieldKind::Required => format!("self.{0}.ok_or(\"missing field `{0}`\")?", self.name),
+            FieldKind::Option => format!("self.{}", self.name),
+            FieldKind::Default => format!("self.{}.unwrap_or_default()", self.name),
+        };
+        format!("            {}: {},\n", self.name, value)
+    }
+}
+
+/// The generic arguments referring to the parameters, `<'a, T>` for `<'a, T: Clone>`.
+fn generic_args(params: &ast::GenericParamList) -> String {
+    let lifetimes =
+        params.lifetime_params().filter_map(|it| it.lifetime()).map(|it| it.to_string());
+    let types = params.type_or_const_params().filter_map(|it| it.name()).map(|it| it.to_string());
+    format!("<{}>", lifetimes.chain(types).format(", "))
+}
+
+/// The `T` of an `Option<T>` type.
+fn option_arg(ty: &ast::Type) -> Option<ast::Type> {
+    let path = match ty {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let generic_args = path.segment()?.generic_arg_list()?;
+    match generic_args.generic_args().exactly_one().ok()? {
+        ast::GenericArg::TypeArg(it) => it.ty(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_builder_required_optional_and_default_fields() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: option, default
+struct Retries(u32);
+impl Default for Retries {
+    fn default() -> Self { Retries(3) }
+}
+struct Config {$0
+    name: &'static str,
+    port: Option<u16>,
+    retries: Retries,
+}
+"#,
+            r#"
+struct Retries(u32);
+impl Default for Retries {
+    fn default() -> Self { Retries(3) }
+}
+struct Config {
+    name: &'static str,
+    port: Option<u16>,
+    retries: Retries,
+}
+
+impl Config {
+    fn builder() -> ConfigBuilder {
+        ConfigBuilder { name: None, port: None, retries: None }
+    }
+}
+
+struct $0ConfigBuilder {
+    name: Option<&'static str>,
+    port: Option<u16>,
+    retries: Option<Retries>,
+}
+
+impl ConfigBuilder {
+    fn name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    fn retries(mut self, retries: Retries) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    fn build(self) -> Result<Config, &'static str> {
+        Ok(Config {
+            name: self.name.ok_or("missing field `name`")?,
+            port: self.port,
+            retries: self.retries.unwrap_or_default(),
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_builder_with_generics() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: option, default
+pub struct Wrapper<'a, T>$0
+where
+    T: Clone,
+{
+    pub inner: &'a T,
+}
+"#,
+            r#"
+pub struct Wrapper<'a, T>
+where
+    T: Clone,
+{
+    pub inner: &'a T,
+}
+
+impl<'a, T> Wrapper<'a, T>
+where
+    T: Clone,
+{
+    pub fn builder() -> WrapperBuilder<'a, T> {
+        WrapperBuilder { inner: None }
+    }
+}
+
+pub struct $0WrapperBuilder<'a, T>
+where
+    T: Clone,
+{
+    inner: Option<&'a T>,
+}
+
+impl<'a, T> WrapperBuilder<'a, T>
+where
+    T: Clone,
+{
+    pub fn inner(mut self, inner: &'a T) -> Self {
+        self.inner = Some(inner);
+        self
+    }
+
+    pub fn build(self) -> Result<Wrapper<'a, T>, &'static str> {
+        Ok(Wrapper {
+            inner: self.inner.ok_or("missing field `inner`")?,
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_tuple_struct() {
+        check_assist_not_applicable(generate_builder, r#"struct Foo$0(u32);"#);
+    }
+
+    #[test]
+    fn not_applicable_when_builder_exists() {
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Foo$0 { a: u32 }
+struct FooBuilder;
+"#,
+        );
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Foo$0 { a: u32 }
+impl Foo {
+    fn builder() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use ide_db::famous_defs::FamousDefs;
+use syntax::ast::{self, make, AstNode, HasName};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    handlers::generate_from_impl_for_enum::existing_from_impl,
+    utils::{gen_trait_fn_body, generate_trait_impl_text},
+    AssistId, AssistKind, GroupLabel,
+};
+
+type FamousTrait = fn(&FamousDefs) -> Option<hir::Trait>;
+
+/// The traits the assist can implement: their path, the items of the impl, whose function gets
+/// its body from `gen_trait_fn_body`, the trait itself and its supertraits.
+const TRAITS: &[(&str, &str, FamousTrait, &[FamousTrait])] = &[
+    ("Clone", "    fn clone(&self) -> Self {}", |it| it.core_clone_Clone(), &[]),
+    (
+        "core::fmt::Debug",
+        "    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {}",
+        |it| it.core_fmt_Debug(),
+        &[],
+    ),
+    ("Default", "    fn default() -> Self {}", |it| it.core_default_Default(), &[]),
+    (
+        "core::fmt::Display",
+        "    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {}",
+        |it| it.core_fmt_Display(),
+        &[],
+    ),
+    (
+        "core::hash::Hash",
+        "    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {}",
+        |it| it.core_hash_Hash(),
+        &[],
+    ),
+    ("PartialEq", "    fn eq(&self, other: &Self) -> bool {}", |it| it.core_cmp_PartialEq(), &[]),
+    (
+        "PartialOrd",
+        "    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {}",
+        |it| it.core_cmp_PartialOrd(),
+        &[|it| it.core_cmp_PartialEq()],
+    ),
+    (
+        "Ord",
+        "    fn cmp(&self, other: &Self) -> core::cmp::Ordering {}",
+        |it| it.core_cmp_Ord(),
+        &[|it| it.core_cmp_PartialOrd(), |it| it.core_cmp_Eq()],
+    ),
+    (
+        "core::str::FromStr",
+        "    type Err = ();\n\n    fn from_str(s: &str) -> Result<Self, Self::Err> {}",
+        |it| it.core_str_FromStr(),
+        &[],
+    ),
+];
+
+// Assist: generate_trait_impl
+//
+// Implements a commonly derived trait for the type, generating the body from its fields.
+//
+// ```
+// struct $0Point { x: i32, y: i32 }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// impl Clone for Point {
+//     fn clone(&self) -> Self {
+//         Self { x: self.x.clone(), y: self.y.clone() }
+//     }
+// }
+// ```
+pub(crate) fn generate_trait_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let adt = name.syntax().parent().and_then(ast::Adt::cast)?;
+    let ty = ctx.sema.to_def(&adt)?.ty(ctx.db());
+    let famous_defs = FamousDefs(&ctx.sema, ctx.sema.scope(adt.syntax()).krate());
+    // A trait that doesn't resolve, e.g. in a `no_core` crate, can't be implemented either.
+    let implemented = |trait_: FamousTrait| {
+        trait_(&famous_defs).map(|trait_| {
+            // The only parameter besides `Self`, if any, is `Rhs = Self`.
+            let params = hir::GenericDef::from(trait_).type_params(ctx.db());
+            let args = vec![ty.clone(); params.len().saturating_sub(1)];
+            ty.impls_trait(ctx.db(), trait_, &args)
+        })
+    };
+
+    let group = GroupLabel("Implement trait".to_owned());
+    let target = adt.syntax().text_range();
+    for &(path, items, trait_, supertraits) in TRAITS {
+        let trait_name = path.rsplit("::").next().unwrap_or(path);
+        if implemented(trait_) == Some(true)
+            || supertraits.iter().any(|&it| implemented(it) != Some(true))
+            || !can_gen_impl(&adt, trait_name)
+        {
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId("generate_trait_impl", AssistKind::Generate),
+            format!("Implement `{}` for `{}`", trait_name, name),
+            target,
+            |builder| {
+                if let Some(impl_text) = gen_impl(&adt, path, items) {
+                    builder.insert(target.end(), impl_text);
+                }
+            },
+        );
+    }
+
+    if let ast::Adt::Enum(enum_) = &adt {
+        generate_from_impls(acc, ctx, &group, enum_);
+    }
+    Some(())
+}
+
+/// Whether `gen_trait_fn_body` can generate the body of the trait's function for the type.
+fn can_gen_impl(adt: &ast::Adt, trait_name: &str) -> bool {
+    match (adt, trait_name) {
+        (ast::Adt::Union(_), _) => false,
+        (ast::Adt::Enum(_), "Default") => false,
+        (ast::Adt::Struct(strukt), "Hash") => strukt.field_list().is_some(),
+        (ast::Adt::Enum(enum_), "PartialOrd" | "Ord") => {
+            enum_.variant_list().map_or(false, |it| it.variants().next().is_some())
+        }
+        (ast::Adt::Enum(enum_), "FromStr") => enum_
+            .variant_list()
+            .map_or(false, |it| it.variants().all(|variant| variant.field_list().is_none())),
+        (_, "FromStr") => false,
+        _ => true,
+    }
+}
+
+/// Renders `impl <path> for Adt { <items> }`, with the function body generated for the type.
+fn gen_impl(adt: &ast::Adt, path: &str, items: &str) -> Option<String> {
+    let text = generate_trait_impl_text(adt, path, items);
+    let file = ast::SourceFile::parse(&text).tree().clone_for_update();
+    let impl_ = file.syntax().descendants().find_map(ast::Impl::cast)?;
+    let func = impl_.syntax().descendants().find_map(ast::Fn::cast)?;
+    gen_trait_fn_body(&func, &make::path_from_text(path), adt)?;
+    Some(format!("\n\n{}", impl_))
+}
+
+/// Offers `From` impls for all the single field variants whose field type isn't shared with
+/// another variant, as the conversion would be ambiguous for those.
+fn generate_from_impls(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    group: &GroupLabel,
+    enum_: &ast::Enum,
+) -> Option<()> {
+    let variants: Vec<_> = enum_
+        .variant_list()?
+        .variants()
+        .filter_map(|variant| {
+            let (field_name, field_ty) = match variant.field_list()? {
+                ast::FieldList::RecordFieldList(list) if list.fields().count() == 1 => {
+                    let field = list.fields().next()?;
+                    (Some(field.name()?), field.ty()?)
+                }
+                ast::FieldList::TupleFieldList(list) if list.fields().count() == 1 => {
+                    (None, list.fields().next()?.ty()?)
+                }
+                _ => return None,
+            };
+            Some((variant, field_name, field_ty))
+        })
+        .collect();
+    let variants: Vec<_> = variants
+        .iter()
+        .filter(|(variant, _, field_ty)| {
+            let field_ty = field_ty.syntax().text();
+            variants.iter().filter(|(_, _, it)| it.syntax().text() == field_ty).count() == 1
+                && existing_from_impl(&ctx.sema, variant).is_none()
+        })
+        .collect();
+    if variants.is_empty() {
+        return None;
+    }
+
+    let adt = ast::Adt::Enum(enum_.clone());
+    let target = enum_.syntax().text_range();
+    acc.add_group(
+        group,
+        AssistId("generate_trait_impl", AssistKind::Generate),
+        format!("Implement `From` for each variant of `{}`", enum_.name()?),
+        target,
+        |builder| {
+            let impls = variants.iter().filter_map(|(variant, field_name, field_ty)| {
+                let (param, expr) = match field_name {
+                    Some(name) => {
+                        (name.to_string(), format!("Self::{} {{ {} }}", variant.name()?, name))
+                    }
+                    None => ("v".to_owned(), format!("Self::{}(v)", variant.name()?)),
+                };
+                let items = format!(
+                    "    fn from({}: {}) -> Self {{\n        {}\n    }}",
+                    param, field_ty, expr
+                );
+                Some(generate_trait_impl_text(&adt, &format!("From<{}>", field_ty), &items))
+            });
+            builder.insert(target.end(), impls.collect::<String>())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist_by_label, check_assist_not_applicable, check_assist_not_applicable_by_label,
+    };
+
+    use super::*;
+
+    #[test]
+    fn display_for_structs() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+struct $0Point { x: i32, y: i32 }
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+
+impl core::fmt::Display for Point {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Point {{ x: {}, y: {} }}", self.x, self.y)
+    }
+}
+"#,
+            "Implement `Display` for `Point`",
+        );
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+struct $0Pair(i32, i32);
+"#,
+            r#"
+struct Pair(i32, i32);
+
+impl core::fmt::Display for Pair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Pair({}, {})", self.0, self.1)
+    }
+}
+"#,
+            "Implement `Display` for `Pair`",
+        );
+    }
+
+    #[test]
+    fn display_for_enum() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+enum $0Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }
+"#,
+            r#"
+enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }
+
+impl core::fmt::Display for Shape {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Circle(arg0) => write!(f, "Circle({})", arg0),
+            Self::Rect { w, h } => write!(f, "Rect {{ w: {}, h: {} }}", w, h),
+            Self::Empty => write!(f, "Empty"),
+        }
+    }
+}
+"#,
+            "Implement `Display` for `Shape`",
+        );
+    }
+
+    #[test]
+    fn ord_for_struct() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: ord, derive
+#[derive(PartialEq, Eq, PartialOrd)]
+struct $0Version { major: u32, minor: u32 }
+"#,
+            r#"
+#[derive(PartialEq, Eq, PartialOrd)]
+struct Version { major: u32, minor: u32 }
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        match self.major.cmp(&other.major) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        self.minor.cmp(&other.minor)
+    }
+}
+"#,
+            "Implement `Ord` for `Version`",
+        );
+    }
+
+    #[test]
+    fn partial_ord_for_enum() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: eq, derive
+#[derive(PartialEq)]
+enum $0Value { Int(i64), Pair { a: u8, b: u8 }, Nil }
+"#,
+            r#"
+#[derive(PartialEq)]
+enum Value { Int(i64), Pair { a: u8, b: u8 }, Nil }
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        match (self, other) {
+            (Self::Int(l0), Self::Int(r0)) => l0.partial_cmp(r0),
+            (Self::Pair { a: l_a, b: l_b }, Self::Pair { a: r_a, b: r_b }) => {
+                match l_a.partial_cmp(r_a) {
+                    Some(core::cmp::Ordering::Equal) => {}
+                    ord => return ord,
+                }
+                l_b.partial_cmp(r_b)
+            }
+            (Self::Nil, Self::Nil) => Some(core::cmp::Ordering::Equal),
+            (Self::Int(..), _) => Some(core::cmp::Ordering::Less),
+            (_, Self::Int(..)) => Some(core::cmp::Ordering::Greater),
+            (Self::Pair { .. }, _) => Some(core::cmp::Ordering::Less),
+            (_, Self::Pair { .. }) => Some(core::cmp::Ordering::Greater),
+        }
+    }
+}
+"#,
+            "Implement `PartialOrd` for `Value`",
+        );
+    }
+
+    #[test]
+    fn ord_needs_supertrait_impls() {
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: ord, derive
+#[derive(PartialEq, PartialOrd)]
+struct $0Version { major: u32, minor: u32 }
+"#,
+            "Implement `Ord` for `Version`",
+        );
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: ord
+struct $0Version { major: u32, minor: u32 }
+"#,
+            "Implement `PartialOrd` for `Version`",
+        );
+    }
+
+    #[test]
+    fn from_str_for_fieldless_enum() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+enum $0Color { Red, Green }
+"#,
+            r#"
+enum Color { Red, Green }
+
+impl core::str::FromStr for Color {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Red" => Ok(Self::Red),
+            "Green" => Ok(Self::Green),
+            _ => Err(()),
+        }
+    }
+}
+"#,
+            "Implement `FromStr` for `Color`",
+        );
+    }
+
+    #[test]
+    fn from_str_not_offered_for_enum_with_fields() {
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+enum $0Value { Int(i64), Nil }
+"#,
+            "Implement `FromStr` for `Value`",
+        );
+    }
+
+    #[test]
+    fn from_for_each_variant() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+enum $0Event { Key(char), Click { pos: (i32, i32) }, Scroll(i32), Resize(i32), Quit }
+"#,
+            r#"
+enum Event { Key(char), Click { pos: (i32, i32) }, Scroll(i32), Resize(i32), Quit }
+
+impl From<char> for Event {
+    fn from(v: char) -> Self {
+        Self::Key(v)
+    }
+}
+
+impl From<(i32, i32)> for Event {
+    fn from(pos: (i32, i32)) -> Self {
+        Self::Click { pos }
+    }
+}
+"#,
+            "Implement `From` for each variant of `Event`",
+        );
+    }
+
+    #[test]
+    fn skips_implemented_traits() {
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: derive, clone
+#[derive(Clone)]
+struct $0S;
+"#,
+            "Implement `Clone` for `S`",
+        );
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: fmt
+struct $0S;
+impl core::fmt::Display for S {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { loop {} }
+}
+"#,
+            "Implement `Display` for `S`",
+        );
+        check_assist_not_applicable_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: from
+enum $0E { A(u8) }
+impl From<u8> for E {
+    fn from(v: u8) -> Self { Self::A(v) }
+}
+"#,
+            "Implement `From` for each variant of `E`",
+        );
+    }
+
+    #[test]
+    fn user_defined_trait_does_not_count_as_implemented() {
+        check_assist_by_label(
+            generate_trait_impl,
+            r#"
+//- minicore: fmt
+trait Display {}
+struct $0S;
+impl Display for S {}
+"#,
+            r#"
+trait Display {}
+struct S;
+
+impl core::fmt::Display for S {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "S")
+    }
+}
+impl Display for S {}
+"#,
+            "Implement `Display` for `S`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_name() {
+        check_assist_not_applicable(
+            generate_trait_impl,
+            r#"
+struct Point { $0x: i32, y: i32 }
+"#,
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use ide_db::syntax_helpers::insert_whitespace_into_node::insert_ws_into;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, HasName},
+    ted, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_macro
+//
+// Replaces a macro call with its expansion. Bindings introduced by the macro are renamed if
+// their names clash with the names used around the call.
+//
+// ```
+// macro_rules! double {
+//     ($e:expr) => { $e * 2 };
+// }
+//
+// fn main() {
+//     let x = $0double!(1 + 2);
+// }
+// ```
+// ->
+// ```
+// macro_rules! double {
+//     ($e:expr) => { $e * 2 };
+// }
+//
+// fn main() {
+//     let x = (1 + 2) * 2;
+// }
+// ```
+pub(crate) fn inline_macro(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let name_range = TextRange::new(
+        macro_call.syntax().text_range().start(),
+        macro_call.excl_token()?.text_range().end(),
+    );
+    if !name_range.contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    let expansion = ctx.sema.expand(&macro_call)?;
+    if expansion.kind() == SyntaxKind::ERROR || expansion.first_token().is_none() {
+        return None;
+    }
+    let expansion = rename_hygienic_bindings(ctx, &macro_call, &expansion);
+    let expansion = insert_ws_into(expansion);
+    space_binary_operators(&expansion);
+
+    let indent = IndentLevel::from_node(macro_call.syntax());
+    let mut text = expansion.to_string();
+    let dollar_crates: Vec<_> = expansion
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::IDENT && it.text() == "$crate")
+        .collect();
+    if !dollar_crates.is_empty() {
+        let krate = dollar_crate_path(ctx, &macro_call)?;
+        let start = expansion.text_range().start();
+        for token in dollar_crates.iter().rev() {
+            let range = token.text_range() - start;
+            text.replace_range(std::ops::Range::<usize>::from(range), &krate);
+        }
+    }
+    let text = text.trim().replace('\n', &format!("\n{}", indent));
+    let in_expr = macro_call
+        .syntax()
+        .parent()
+        .map_or(false, |it| ast::Expr::can_cast(it.kind()) && !ast::ParenExpr::can_cast(it.kind()));
+    let text = if in_expr && needs_parens(&expansion) { format!("({})", text) } else { text };
+
+    // The expansion of a statement macro includes the semicolons.
+    let target = match macro_call.syntax().parent().and_then(ast::ExprStmt::cast) {
+        Some(stmt) if expansion.kind() == SyntaxKind::MACRO_STMTS => stmt.syntax().text_range(),
+        _ => macro_call.syntax().text_range(),
+    };
+    acc.add(
+        AssistId("inline_macro", AssistKind::RefactorInline),
+        "Inline macro call",
+        target,
+        |builder| builder.replace(target, text),
+    )
+}
+
+/// The path `$crate` stands for at the call site: `crate` or the name the calling crate uses for
+/// the macro's crate.
+fn dollar_crate_path(ctx: &AssistContext, macro_call: &ast::MacroCall) -> Option<String> {
+    let macro_crate = ctx.sema.resolve_macro_call(macro_call)?.module(ctx.db()).krate();
+    let calling_crate = ctx.sema.scope(macro_call.syntax()).krate()?;
+    if macro_crate == calling_crate {
+        return Some("crate".to_owned());
+    }
+    let dep =
+        calling_crate.dependencies(ctx.db()).into_iter().find(|it| it.krate == macro_crate)?;
+    Some(format!("::{}", dep.name))
+}
+
+fn space_binary_operators(node: &SyntaxNode) {
+    let ops: Vec<_> =
+        node.descendants().filter_map(ast::BinExpr::cast).filter_map(|it| it.op_token()).collect();
+    for op in ops {
+        if op.prev_token().map_or(false, |it| it.kind() != SyntaxKind::WHITESPACE) {
+            ted::insert(ted::Position::before(&op), make::tokens::single_space());
+        }
+        if op.next_token().map_or(false, |it| it.kind() != SyntaxKind::WHITESPACE) {
+            ted::insert(ted::Position::after(&op), make::tokens::single_space());
+        }
+    }
+}
+
+fn needs_parens(expansion: &SyntaxNode) -> bool {
+    matches!(
+        ast::Expr::cast(expansion.clone()),
+        Some(
+            ast::Expr::BinExpr(_)
+                | ast::Expr::RangeExpr(_)
+                | ast::Expr::CastExpr(_)
+                | ast::Expr::PrefixExpr(_)
+                | ast::Expr::ClosureExpr(_)
+                | ast::Expr::ReturnExpr(_)
+                | ast::Expr::BreakExpr(_)
+        )
+    )
+}
+
+/// Macro-introduced bindings are hygienic: they are distinct from the identically named ones
+/// at the call site. Once inlined, they would clash, so they are renamed to a fresh name.
+fn rename_hygienic_bindings(
+    ctx: &AssistContext,
+    macro_call: &ast::MacroCall,
+    expansion: &SyntaxNode,
+) -> SyntaxNode {
+    let expansion_file = ctx.sema.hir_file_for(expansion);
+    let mut from_input = FxHashSet::default();
+    let mut used_names = FxHashSet::default();
+    if let Some(tt) = macro_call.token_tree() {
+        for token in tt.syntax().descendants_with_tokens().filter_map(|it| it.into_token()) {
+            for descended in ctx.sema.descend_into_macros(token.clone()) {
+                let in_expansion = descended
+                    .parent()
+                    .map_or(false, |it| ctx.sema.hir_file_for(&it) == expansion_file);
+                if in_expansion {
+                    from_input.insert(descended.text_range());
+                }
+            }
+        }
+    }
+    // Everything the inlined names could be confused with: the names around the call...
+    let container = macro_call
+        .syntax()
+        .ancestors()
+        .find(|it| ast::Fn::can_cast(it.kind()))
+        .unwrap_or_else(|| macro_call.syntax().clone());
+    used_names.extend(idents(&container).map(|it| it.text().to_string()));
+    // ...and in the expansion.
+    let tokens: Vec<_> = idents(expansion).collect();
+    let expansion_names: FxHashSet<_> = tokens.iter().map(|it| it.text().to_string()).collect();
+
+    let is_hygienic = |token: &SyntaxToken| !from_input.contains(&token.text_range());
+    let mut renames = Vec::new();
+    for name in expansion.descendants().filter_map(ast::IdentPat::cast).filter_map(|it| it.name()) {
+        let token = match name.ident_token() {
+            Some(it) if is_hygienic(&it) => it,
+            _ => continue,
+        };
+        let clashes = tokens.iter().any(|it| !is_hygienic(it) && it.text() == token.text())
+            || idents(&container).any(|it| {
+                it.text() == token.text()
+                    && !macro_call.syntax().text_range().contains_range(it.text_range())
+            });
+        if !clashes || renames.iter().any(|(old, _): &(String, String)| old == token.text()) {
+            continue;
+        }
+        let new_name = (1..)
+            .map(|n| format!("{}_{}", token.text(), n))
+            .find(|it| !used_names.contains(it) && !expansion_names.contains(it))
+            .unwrap();
+        used_names.insert(new_name.clone());
+        renames.push((token.text().to_string(), new_name));
+    }
+    if renames.is_empty() {
+        return expansion.clone();
+    }
+
+    let expansion_mut = expansion.clone_subtree().clone_for_update();
+    let replacements: Vec<_> = tokens
+        .iter()
+        .zip(idents(&expansion_mut))
+        .filter(|(token, _)| is_hygienic(token))
+        .filter_map(|(token, token_mut)| {
+            let (_, new_name) = renames.iter().find(|(old, _)| old == token.text())?;
+            Some((token_mut, new_name))
+        })
+        .collect();
+    for (token, new_name) in replacements {
+        let new_token = make::name(new_name).clone_for_update().syntax().first_token().unwrap();
+        ted::replace(token, new_token);
+    }
+    expansion_mut
+}
+
+fn idents(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::IDENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_expression_macro() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+fn main() {
+    let x = double$0!(1 + 2) + 1;
+}
+"#,
+            r#"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+fn main() {
+    let x = ((1 + 2) * 2) + 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_statement_macro() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+fn main() {
+    let (mut x, mut y) = (1, 2);
+    $0swap!(x, y);
+}
+"#,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+fn main() {
+    let (mut x, mut y) = (1, 2);
+    let tmp = x;
+    x = y;
+    y = tmp;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn renames_clashing_hygienic_bindings() {
+        check_assist(
+            inline_macro,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+fn main() {
+    let (mut tmp, mut y) = (1, 2);
+    swap$0!(tmp, y);
+}
+"#,
+            r#"
+macro_rules! swap {
+    ($a:ident, $b:ident) => {
+        let tmp = $a;
+        $a = $b;
+        $b = tmp;
+    };
+}
+fn main() {
+    let (mut tmp, mut y) = (1, 2);
+    let tmp_1 = tmp;
+    tmp = y;
+    y = tmp_1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_builtin_macro() {
+        check_assist(
+            inline_macro,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! concat {}
+fn main() {
+    let s = concat$0!("a", 1, "b");
+}
+"#,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! concat {}
+fn main() {
+    let s = "a1b";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn resolves_dollar_crate() {
+        check_assist(
+            inline_macro,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    let v = dep::make$0!();
+}
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! make {
+    () => { $crate::Thing::named("$crate") };
+}
+pub struct Thing;
+"#,
+            r#"
+fn main() {
+    let v = ::dep::Thing::named("$crate");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_arguments() {
+        check_assist_not_applicable(
+            inline_macro,
+            r#"
+macro_rules! id {
+    ($e:expr) => { $e };
+}
+fn main() {
+    id!(1 +$0 2);
+}
+"#,
+        );
+    }
+}
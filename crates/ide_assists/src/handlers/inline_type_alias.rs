@@ -0,0 +1,394 @@
+use hir::{AsAssocItem, ModuleDef, PathResolution};
+use ide_db::{
+    base_db::FileId, defs::Definition, path_transform::PathTransform, search::FileReference,
+};
+use itertools::Itertools;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, make, HasGenericParams, HasName},
+    ted, AstNode, SyntaxNode, TextRange, WalkEvent,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: inline_type_alias
+//
+// Replaces a use of a type alias with the aliased type, substituting the generic arguments.
+//
+// ```
+// type Pair<T> = (T, T);
+//
+// fn swap(p: $0Pair<u32>) {}
+// ```
+// ->
+// ```
+// type Pair<T> = (T, T);
+//
+// fn swap(p: (u32, u32)) {}
+// ```
+pub(crate) fn inline_type_alias(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_type = ctx.find_node_at_offset::<ast::PathType>()?;
+    let alias = resolve_alias(ctx, &path_type)?;
+    let replacement = inline_alias(ctx, alias, &path_type, false)?;
+
+    let target = path_type.syntax().text_range();
+    acc.add(
+        AssistId("inline_type_alias", AssistKind::RefactorInline),
+        "Inline type alias",
+        target,
+        |builder| builder.replace(target, replacement),
+    )
+}
+
+// Assist: inline_type_alias_uses
+//
+// Replaces all uses of a type alias with the aliased type and removes the alias, unless it is
+// still used in other ways, e.g. in imports.
+//
+// ```
+// type $0Pair<T> = (T, T);
+//
+// fn swap(p: Pair<u32>) -> Pair<u32> {}
+// ```
+// ->
+// ```
+// fn swap(p: (u32, u32)) -> (u32, u32) {}
+// ```
+pub(crate) fn inline_type_alias_uses(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let alias_def = ctx.find_node_at_offset::<ast::TypeAlias>()?;
+    if !alias_def.name()?.syntax().text_range().contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    alias_def.ty()?;
+    if alias_def
+        .generic_param_list()
+        .iter()
+        .flat_map(|it| it.generic_params())
+        .any(|it| matches!(it, ast::GenericParam::ConstParam(_)))
+    {
+        return None;
+    }
+    let alias = ctx.sema.to_def(&alias_def)?;
+    if alias.as_assoc_item(ctx.db()).is_some() {
+        return None;
+    }
+
+    let target = alias_def.syntax().text_range();
+    acc.add(
+        AssistId("inline_type_alias_uses", AssistKind::RefactorInline),
+        "Inline type alias into all uses",
+        target,
+        |builder| {
+            // Aliases used in other ways than as types, e.g. in imports, can't be removed.
+            let mut remove_alias = true;
+            let mut uses: Vec<(FileId, ast::PathType)> = Vec::new();
+            for (file_id, references) in Definition::TypeAlias(alias).usages(&ctx.sema).all() {
+                let source_file = ctx.sema.parse(file_id);
+                for FileReference { range, .. } in references {
+                    match alias_use_at(source_file.syntax(), range) {
+                        Some(path_type) => uses.push((file_id, path_type)),
+                        None => remove_alias = false,
+                    }
+                }
+            }
+
+            // Uses nested in the arguments of other uses are inlined along with the outer use.
+            let is_nested = |(file_id, path_type): &(FileId, ast::PathType)| {
+                let range = path_type.syntax().text_range();
+                uses.iter().any(|(other_file, other)| {
+                    other_file == file_id
+                        && other.syntax().text_range() != range
+                        && other.syntax().text_range().contains_range(range)
+                })
+            };
+            let mut edits = Vec::new();
+            for it @ (file_id, path_type) in &uses {
+                if is_nested(it) {
+                    continue;
+                }
+                match inline_alias(ctx, alias, path_type, true) {
+                    Some(replacement) => {
+                        edits.push((*file_id, path_type.syntax().text_range(), replacement))
+                    }
+                    None => remove_alias = false,
+                }
+            }
+
+            let edits = edits.into_iter().into_group_map_by(|(file_id, ..)| *file_id);
+            let mut def_file_edits = Vec::new();
+            for (file_id, edits) in edits {
+                if file_id == ctx.file_id() {
+                    def_file_edits = edits;
+                    continue;
+                }
+                builder.edit_file(file_id);
+                for (_, range, replacement) in edits {
+                    builder.replace(range, replacement);
+                }
+            }
+            builder.edit_file(ctx.file_id());
+            for (_, range, replacement) in def_file_edits {
+                builder.replace(range, replacement);
+            }
+            if remove_alias {
+                let ws = alias_def
+                    .syntax()
+                    .next_sibling_or_token()
+                    .filter(|it| it.kind() == syntax::SyntaxKind::WHITESPACE);
+                let end = ws.map_or(target.end(), |it| it.text_range().end());
+                builder.delete(TextRange::new(target.start(), end));
+            }
+        },
+    )
+}
+
+fn resolve_alias(ctx: &AssistContext, path_type: &ast::PathType) -> Option<hir::TypeAlias> {
+    match ctx.sema.resolve_path(&path_type.path()?)? {
+        PathResolution::Def(ModuleDef::TypeAlias(it)) if it.as_assoc_item(ctx.db()).is_none() => {
+            Some(it)
+        }
+        _ => None,
+    }
+}
+
+/// The type whose path ends with the name at `range`.
+fn alias_use_at(root: &SyntaxNode, range: TextRange) -> Option<ast::PathType> {
+    let name_ref: ast::NameRef = find_node_at_range(root, range)?;
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    path.syntax().parent().and_then(ast::PathType::cast)
+}
+
+/// The text of the aliased type with the arguments of `path_type` substituted for the alias'
+/// parameters. With `inline_nested`, uses of the alias in the arguments are inlined as well.
+fn inline_alias(
+    ctx: &AssistContext,
+    alias: hir::TypeAlias,
+    path_type: &ast::PathType,
+    inline_nested: bool,
+) -> Option<String> {
+    let source = ctx.sema.source(alias)?.value;
+    let params = source.generic_param_list();
+    if params
+        .iter()
+        .flat_map(|it| it.generic_params())
+        .any(|it| matches!(it, ast::GenericParam::ConstParam(_)))
+    {
+        return None;
+    }
+
+    let args = path_type.path()?.segment()?.generic_arg_list();
+    let args = match args {
+        Some(args) if inline_nested => {
+            let args = inline_nested_uses(ctx, alias, args.syntax())?;
+            match make::ty(&format!("T{}", args)) {
+                ast::Type::PathType(it) => it.path()?.segment()?.generic_arg_list(),
+                _ => None,
+            }
+        }
+        args => args,
+    };
+
+    // Paths are resolved in the context of the alias' definition, so they are transformed on
+    // a copy of the whole item.
+    let item = source.clone_subtree().clone_for_update();
+    let ty = item.ty()?;
+    let lifetime_params: Vec<_> =
+        params.iter().flat_map(|it| it.lifetime_params()).filter_map(|it| it.lifetime()).collect();
+    let lifetime_args: Vec<_> = args
+        .iter()
+        .flat_map(|it| it.generic_args())
+        .filter_map(|it| match it {
+            ast::GenericArg::LifetimeArg(it) => it.lifetime(),
+            _ => None,
+        })
+        .collect();
+    let lifetimes: Vec<_> = ty.syntax().descendants().filter_map(ast::Lifetime::cast).collect();
+    for lifetime in lifetimes {
+        let index = lifetime_params.iter().position(|it| it.text() == lifetime.text());
+        if let Some(index) = index {
+            let arg = lifetime_args.get(index).map_or_else(|| "'_".to_owned(), |it| it.to_string());
+            ted::replace(lifetime.syntax(), make::lifetime(&arg).clone_for_update().syntax());
+        }
+    }
+
+    let target_scope = ctx.sema.scope(path_type.syntax());
+    let source_scope = ctx.sema.scope(source.ty()?.syntax());
+    PathTransform::type_alias(&target_scope, &source_scope, alias, args).apply(ty.syntax());
+
+    let ty = item.ty()?;
+    let bounds = match &ty {
+        ast::Type::DynTraitType(it) => it.type_bound_list(),
+        ast::Type::ImplTraitType(it) => it.type_bound_list(),
+        _ => None,
+    };
+    let needs_parens = bounds.map_or(false, |it| it.bounds().count() > 1)
+        && path_type.syntax().parent().map_or(false, |it| {
+            ast::RefType::can_cast(it.kind()) || ast::PtrType::can_cast(it.kind())
+        });
+    if needs_parens {
+        Some(format!("({})", ty))
+    } else {
+        Some(ty.to_string())
+    }
+}
+
+/// The text of `node` with all uses of `alias` in it inlined.
+fn inline_nested_uses(
+    ctx: &AssistContext,
+    alias: hir::TypeAlias,
+    node: &SyntaxNode,
+) -> Option<String> {
+    let range = node.text_range();
+    let mut edits = Vec::new();
+    let mut preorder = node.preorder();
+    while let Some(event) = preorder.next() {
+        let path_type = match event {
+            WalkEvent::Enter(it) => match ast::PathType::cast(it) {
+                Some(it) => it,
+                None => continue,
+            },
+            WalkEvent::Leave(_) => continue,
+        };
+        if resolve_alias(ctx, &path_type) == Some(alias) {
+            preorder.skip_subtree();
+            let replacement = inline_alias(ctx, alias, &path_type, true)?;
+            edits.push((path_type.syntax().text_range() - range.start(), replacement));
+        }
+    }
+
+    let mut text = node.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_generic_alias() {
+        check_assist(
+            inline_type_alias,
+            r#"
+type Map<'a, K, V = ()> = &'a [(K, V)];
+
+fn f<'x>(m: Map$0<'x, u8>) {}
+"#,
+            r#"
+type Map<'a, K, V = ()> = &'a [(K, V)];
+
+fn f<'x>(m: &'x [(u8, ())]) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_paths_for_target_module() {
+        check_assist(
+            inline_type_alias,
+            r#"
+mod a {
+    pub struct S<T>(T);
+    pub type Alias<T> = S<Option<T>>;
+}
+fn f(x: a::Alias$0<i32>) {}
+"#,
+            r#"
+mod a {
+    pub struct S<T>(T);
+    pub type Alias<T> = S<Option<T>>;
+}
+fn f(x: a::S<Option<i32>>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn parenthesizes_multiple_bounds() {
+        check_assist(
+            inline_type_alias,
+            r#"
+trait A {}
+trait B {}
+type Both = dyn A + B;
+fn f(x: &Both$0) {}
+"#,
+            r#"
+trait A {}
+trait B {}
+type Both = dyn A + B;
+fn f(x: &(dyn A + B)) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_assoc_types_and_structs() {
+        check_assist_not_applicable(
+            inline_type_alias,
+            r#"
+trait Tr { type Out; }
+fn f<T: Tr>(x: T::Out$0) {}
+"#,
+        );
+        check_assist_not_applicable(
+            inline_type_alias,
+            r#"
+struct S;
+fn f(x: S$0) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_all_uses() {
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+//- /main.rs
+mod other;
+type Pair$0<T> = (T, T);
+
+fn f(p: Pair<Pair<u8>>) -> Pair<bool> {
+    let x: Pair<i32> = (1, 2);
+}
+//- /other.rs
+fn g(p: crate::Pair<char>) {}
+"#,
+            r#"
+//- /main.rs
+mod other;
+fn f(p: ((u8, u8), (u8, u8))) -> (bool, bool) {
+    let x: (i32, i32) = (1, 2);
+}
+//- /other.rs
+fn g(p: (char, char)) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_alias_used_in_imports() {
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+mod m {
+    pub type Alias$0 = u8;
+    fn f(x: Alias) {}
+}
+use m::Alias;
+"#,
+            r#"
+mod m {
+    pub type Alias = u8;
+    fn f(x: u8) {}
+}
+use m::Alias;
+"#,
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use hir::term_search::TermSearchConfig;
+use ide_db::term_search;
+use itertools::Itertools;
+use syntax::{ast, AstNode};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: term_search
+//
+// Fills a `todo!()` or `_` placeholder with an expression of the expected type built from
+// the locals, functions and constructors in scope.
+//
+// ```
+// struct Meters(u32);
+// fn measure(raw: u32) {
+//     let distance: Meters = _$0;
+// }
+// ```
+// ->
+// ```
+// struct Meters(u32);
+// fn measure(raw: u32) {
+//     let distance: Meters = Meters(raw);
+// }
+// ```
+pub(crate) fn term_search(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let hole = ctx.find_node_at_offset::<ast::Expr>().and_then(|expr| match expr {
+        ast::Expr::UnderscoreExpr(_) => Some(expr),
+        ast::Expr::MacroCall(ref call) if is_todo(call) => Some(expr),
+        _ => None,
+    })?;
+    let goal = match &hole {
+        // `_` is lowered to a missing expression, so we have to look at its surroundings.
+        ast::Expr::UnderscoreExpr(_) => expected_type_of_hole(ctx, &hole)?,
+        _ => ctx.sema.type_of_expr(&hole)?.adjusted(),
+    };
+    if goal.is_unknown() || goal.is_never() {
+        return None;
+    }
+
+    let scope = ctx.sema.scope(hole.syntax());
+    let module = scope.module()?;
+    // Tests don't get a time limit, so that their results don't depend on the machine.
+    let config = TermSearchConfig {
+        budget: (!cfg!(test)).then(|| Duration::from_millis(100)),
+        ..TermSearchConfig::default()
+    };
+    let mut candidates: Vec<String> = term_search::term_search(&ctx.sema, &scope, &goal, &config)
+        .into_iter()
+        .filter_map(|expr| expr.gen_source_code(ctx.db(), module))
+        .unique()
+        .collect();
+    // Prefer the shortest candidates, they tend to be the most direct ones.
+    candidates.sort_by_key(|it| it.len());
+
+    let group = GroupLabel("Term search".to_owned());
+    let target = hole.syntax().text_range();
+    for candidate in candidates.into_iter().take(MAX_CANDIDATES) {
+        acc.add_group(
+            &group,
+            AssistId("term_search", AssistKind::Generate),
+            format!("Replace hole with `{}`", candidate),
+            target,
+            |builder| builder.replace(target, candidate),
+        );
+    }
+    Some(())
+}
+
+const MAX_CANDIDATES: usize = 8;
+
+fn expected_type_of_hole(ctx: &AssistContext, hole: &ast::Expr) -> Option<hir::Type> {
+    let parent = hole.syntax().parent()?;
+    if let Some(let_stmt) = ast::LetStmt::cast(parent.clone()) {
+        return ctx.sema.resolve_type(&let_stmt.ty()?);
+    }
+    let body = ast::StmtList::cast(parent)?;
+    if body.tail_expr().as_ref() != Some(hole) {
+        return None;
+    }
+    let func = ast::Fn::cast(body.syntax().parent()?.parent()?)?;
+    Some(ctx.sema.to_def(&func)?.ret_type(ctx.db()))
+}
+
+fn is_todo(call: &ast::MacroCall) -> bool {
+    call.path()
+        .and_then(|path| path.segment())
+        .and_then(|segment| segment.name_ref())
+        .map_or(false, |name| name.text() == "todo")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn fills_with_local() {
+        check_assist(
+            term_search,
+            r#"
+macro_rules! todo { () => { loop {} } }
+fn f(a: u128) -> u128 {
+    todo$0!()
+}
+"#,
+            r#"
+macro_rules! todo { () => { loop {} } }
+fn f(a: u128) -> u128 {
+    a
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn fills_underscore() {
+        check_assist(
+            term_search,
+            r#"
+struct Meters(u32);
+fn f(raw: u32) {
+    let m: Meters = _$0;
+}
+"#,
+            r#"
+struct Meters(u32);
+fn f(raw: u32) {
+    let m: Meters = Meters(raw);
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn uses_items_out_of_scope() {
+        check_assist(
+            term_search,
+            r#"
+mod settings {
+    pub struct Config(u32);
+    pub fn default_config() -> Config { Config(0) }
+}
+fn f() {
+    let c: settings::Config = _$0;
+}
+"#,
+            r#"
+mod settings {
+    pub struct Config(u32);
+    pub fn default_config() -> Config { Config(0) }
+}
+fn f() {
+    let c: settings::Config = settings::default_config();
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn fills_with_function_and_field() {
+        check_assist_by_label(
+            term_search,
+            r#"
+macro_rules! todo { () => { loop {} } }
+struct Config { port: u16 }
+struct Server(u16);
+fn listen(port: u16) -> Server { Server(port) }
+fn start(config: Config) -> Server {
+    todo!()$0
+}
+"#,
+            r#"
+macro_rules! todo { () => { loop {} } }
+struct Config { port: u16 }
+struct Server(u16);
+fn listen(port: u16) -> Server { Server(port) }
+fn start(config: Config) -> Server {
+    Server(config.port)
+}
+"#,
+            "Replace hole with `Server(config.port)`",
+        )
+    }
+
+    #[test]
+    fn fills_with_method() {
+        check_assist_by_label(
+            term_search,
+            r#"
+macro_rules! todo { () => { loop {} } }
+struct Celsius(i32);
+struct Reading { raw: i32 }
+impl Reading {
+    fn celsius(&self) -> Celsius { Celsius(self.raw) }
+}
+fn convert(reading: Reading) -> Celsius {
+    todo!()$0
+}
+"#,
+            r#"
+macro_rules! todo { () => { loop {} } }
+struct Celsius(i32);
+struct Reading { raw: i32 }
+impl Reading {
+    fn celsius(&self) -> Celsius { Celsius(self.raw) }
+}
+fn convert(reading: Reading) -> Celsius {
+    reading.celsius()
+}
+"#,
+            "Replace hole with `reading.celsius()`",
+        )
+    }
+
+    #[test]
+    fn not_applicable_without_candidates() {
+        check_assist_not_applicable(
+            term_search,
+            r#"
+macro_rules! todo { () => { loop {} } }
+struct Secret;
+fn f() -> u32 {
+    todo!()$0
+}
+"#,
+        )
+    }
+}
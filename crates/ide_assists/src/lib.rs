@@ -110,20 +110,30 @@ mod handlers {
     mod add_turbo_fish;
     mod apply_demorgan;
     mod auto_import;
+    mod change_signature;
     mod change_visibility;
     mod convert_bool_then;
+    mod convert_bool_to_enum;
+    mod convert_closure_to_fn;
     mod convert_comment_block;
     mod convert_integer_literal;
     mod convert_into_to_from;
     mod convert_iter_for_each_to_for;
+    mod convert_let_else;
+    mod convert_named_struct_to_tuple_struct;
+    mod convert_sync_to_async;
     mod convert_tuple_struct_to_named_struct;
     mod convert_to_guarded_return;
+    mod convert_trait_param;
     mod convert_while_to_loop;
+    mod destructure_struct_binding;
     mod destructure_tuple_binding;
     mod expand_glob_import;
+    mod extract_constant;
     mod extract_function;
     mod extract_module;
     mod extract_struct_from_enum_variant;
+    mod extract_trait;
     mod extract_type_alias;
     mod extract_variable;
     mod add_missing_match_arms;
@@ -131,6 +141,7 @@ mod handlers {
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_builder;
     mod generate_constant;
     mod generate_default_from_enum_variant;
     mod generate_default_from_new;
@@ -146,10 +157,13 @@ mod handlers {
     mod generate_is_empty_from_len;
     mod generate_new;
     mod generate_setter;
+    mod generate_trait_impl;
     mod generate_delegate_methods;
     mod add_return_type;
     mod inline_call;
     mod inline_local_variable;
+    mod inline_macro;
+    mod inline_type_alias;
     mod introduce_named_lifetime;
     mod invert_if;
     mod merge_imports;
@@ -180,6 +194,7 @@ mod handlers {
     mod replace_turbofish_with_explicit_type;
     mod split_import;
     mod sort_items;
+    mod term_search;
     mod toggle_ignore;
     mod unmerge_use;
     mod unwrap_block;
@@ -196,20 +211,35 @@ mod handlers {
             add_turbo_fish::add_turbo_fish,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
+            change_signature::change_signature,
             change_visibility::change_visibility,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
+            convert_bool_to_enum::convert_bool_to_enum,
+            convert_closure_to_fn::convert_closure_to_fn,
+            convert_closure_to_fn::convert_fn_to_closure,
             convert_comment_block::convert_comment_block,
             convert_integer_literal::convert_integer_literal,
             convert_into_to_from::convert_into_to_from,
             convert_iter_for_each_to_for::convert_iter_for_each_to_for,
             convert_iter_for_each_to_for::convert_for_loop_with_for_each,
+            convert_let_else::convert_to_let_else,
+            convert_let_else::convert_let_else_to_match,
+            convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
+            convert_sync_to_async::convert_sync_to_async,
+            convert_sync_to_async::convert_async_to_sync,
             convert_to_guarded_return::convert_to_guarded_return,
+            convert_trait_param::convert_param_to_impl_trait,
+            convert_trait_param::convert_param_to_generic,
+            convert_trait_param::convert_param_to_dyn_ref,
+            convert_trait_param::convert_param_to_boxed_dyn,
             convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
             convert_while_to_loop::convert_while_to_loop,
+            destructure_struct_binding::destructure_struct_binding,
             destructure_tuple_binding::destructure_tuple_binding,
             expand_glob_import::expand_glob_import,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
+            extract_trait::extract_trait,
             extract_type_alias::extract_type_alias,
             fix_visibility::fix_visibility,
             flip_binexpr::flip_binexpr,
@@ -228,9 +258,13 @@ mod handlers {
             generate_impl::generate_impl,
             generate_is_empty_from_len::generate_is_empty_from_len,
             generate_new::generate_new,
+            generate_builder::generate_builder,
             inline_call::inline_call,
             inline_call::inline_into_callers,
             inline_local_variable::inline_local_variable,
+            inline_macro::inline_macro,
+            inline_type_alias::inline_type_alias,
+            inline_type_alias::inline_type_alias_uses,
             introduce_named_generic::introduce_named_generic,
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
@@ -263,6 +297,7 @@ mod handlers {
             replace_turbofish_with_explicit_type::replace_turbofish_with_explicit_type,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             sort_items::sort_items,
+            term_search::term_search,
             split_import::split_import,
             toggle_ignore::toggle_ignore,
             unmerge_use::unmerge_use,
@@ -282,11 +317,13 @@ mod handlers {
             //
             extract_variable::extract_variable,
             extract_function::extract_function,
+            extract_constant::extract_constant,
             extract_module::extract_module,
             //
             generate_getter::generate_getter,
             generate_getter::generate_getter_mut,
             generate_setter::generate_setter,
+            generate_trait_impl::generate_trait_impl,
             generate_delegate_methods::generate_delegate_methods,
             generate_deref::generate_deref,
             // Are you sure you want to add new assist here, and not to the
@@ -217,6 +217,27 @@ pub mod std { pub mod collections { pub struct HashMap { } } }
     )
 }
 
+#[test]
+fn doctest_change_signature() {
+    check_doc_test(
+        "change_signature",
+        r#####"
+fn frobnicate($0x: i32, y: bool) {}
+
+fn main() {
+    frobnicate(92, true);
+}
+"#####,
+        r#####"
+fn frobnicate(y: bool, x: i32) {}
+
+fn main() {
+    frobnicate(true, 92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_change_visibility() {
     check_doc_test(
@@ -230,6 +251,27 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_async_to_sync() {
+    check_doc_test(
+        "convert_async_to_sync",
+        r#####"
+async fn $0fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch().await;
+}
+"#####,
+        r#####"
+fn fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_bool_then_to_if() {
     check_doc_test(
@@ -252,6 +294,77 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_bool_to_enum() {
+    check_doc_test(
+        "convert_bool_to_enum",
+        r#####"
+fn main() {
+    let $0verbose = true;
+    if verbose {
+        println!("hello");
+    }
+}
+"#####,
+        r#####"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verbose {
+    True,
+    False,
+}
+
+fn main() {
+    let verbose = Verbose::True;
+    if verbose == Verbose::True {
+        println!("hello");
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_closure_to_fn() {
+    check_doc_test(
+        "convert_closure_to_fn",
+        r#####"
+fn main() {
+    let square = $0|x: u64| x * x;
+    square(3);
+}
+"#####,
+        r#####"
+fn main() {
+    fn square(x: u64) -> u64 {
+        x * x
+    }
+    square(3);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_fn_to_closure() {
+    check_doc_test(
+        "convert_fn_to_closure",
+        r#####"
+fn double(x: i32) -> i32 { x * 2 }
+fn main() {
+    let v: Vec<i32> = vec![1, 2];
+    v.into_iter().map(double$0);
+}
+"#####,
+        r#####"
+fn double(x: i32) -> i32 { x * 2 }
+fn main() {
+    let v: Vec<i32> = vec![1, 2];
+    v.into_iter().map(|x: i32| x * 2);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_with_for_each() {
     check_doc_test(
@@ -364,6 +477,160 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_let_else_to_match() {
+    check_doc_test(
+        "convert_let_else_to_match",
+        r#####"
+fn main(opt: Option<u32>) {
+    $0let Some(x) = opt else { return };
+}
+"#####,
+        r#####"
+fn main(opt: Option<u32>) {
+    let x = match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point$0 { x: f32, y: f32 }
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point(x, y)
+    }
+
+    pub fn x(&self) -> f32 {
+        self.0
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_param_to_boxed_dyn() {
+    check_doc_test(
+        "convert_param_to_boxed_dyn",
+        r#####"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render<S>(shape: $0S) where S: Draw {}
+fn main() {
+    render(Circle);
+}
+"#####,
+        r#####"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: Box<dyn Draw>) {}
+fn main() {
+    render(Box::new(Circle));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_param_to_dyn_ref() {
+    check_doc_test(
+        "convert_param_to_dyn_ref",
+        r#####"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: $0impl Draw) {}
+fn main() {
+    render(Circle);
+}
+"#####,
+        r#####"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+fn render(shape: &dyn Draw) {}
+fn main() {
+    render(&Circle);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_param_to_generic() {
+    check_doc_test(
+        "convert_param_to_generic",
+        r#####"
+trait Draw {}
+fn render(shape: $0Box<dyn Draw>) {}
+"#####,
+        r#####"
+trait Draw {}
+fn render<D>(shape: D) where D: Draw {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_param_to_impl_trait() {
+    check_doc_test(
+        "convert_param_to_impl_trait",
+        r#####"
+trait Draw {}
+fn render(shape: $0&dyn Draw) {}
+"#####,
+        r#####"
+trait Draw {}
+fn render(shape: impl Draw) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_sync_to_async() {
+    check_doc_test(
+        "convert_sync_to_async",
+        r#####"
+fn $0fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch();
+}
+"#####,
+        r#####"
+async fn fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch().await;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -388,6 +655,26 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_to_let_else() {
+    check_doc_test(
+        "convert_to_let_else",
+        r#####"
+fn main(opt: Option<u32>) {
+    let x = $0match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#####,
+        r#####"
+fn main(opt: Option<u32>) {
+    let Some(x) = opt else { return };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_tuple_struct_to_named_struct() {
     check_doc_test(
@@ -453,6 +740,27 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_destructure_struct_binding() {
+    check_doc_test(
+        "destructure_struct_binding",
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+fn main() {
+    let $0p = Point { x: 1, y: 2, z: 3 };
+    let sum = p.x + p.y;
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32, z: i32 }
+fn main() {
+    let Point { x, y, .. } = Point { x: 1, y: 2, z: 3 };
+    let sum = x + y;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_destructure_tuple_binding() {
     check_doc_test(
@@ -499,6 +807,24 @@ fn qux(bar: Bar, baz: Baz) {}
     )
 }
 
+#[test]
+fn doctest_extract_constant() {
+    check_doc_test(
+        "extract_constant",
+        r#####"
+fn area(radius: f64) -> f64 {
+    $03.14159$0 * radius * radius
+}
+"#####,
+        r#####"
+fn area(radius: f64) -> f64 {
+    const $0VAR_NAME: f64 = 3.14159;
+    VAR_NAME * radius * radius
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_function() {
     check_doc_test(
@@ -570,6 +896,35 @@ enum A { One(One) }
     )
 }
 
+#[test]
+fn doctest_extract_trait() {
+    check_doc_test(
+        "extract_trait",
+        r#####"
+struct Counter(u32);
+
+impl $0Counter {
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#####,
+        r#####"
+struct Counter(u32);
+
+pub trait CounterExt {
+    fn get(&self) -> u32;
+}
+
+impl CounterExt for Counter {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_type_alias() {
     check_doc_test(
@@ -677,6 +1032,55 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+pub struct Person {
+    name: String,
+    age: u32,$0
+}
+"#####,
+        r#####"
+pub struct Person {
+    name: String,
+    age: u32,
+}
+
+impl Person {
+    pub fn builder() -> PersonBuilder {
+        PersonBuilder { name: None, age: None }
+    }
+}
+
+pub struct $0PersonBuilder {
+    name: Option<String>,
+    age: Option<u32>,
+}
+
+impl PersonBuilder {
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn age(mut self, age: u32) -> Self {
+        self.age = Some(age);
+        self
+    }
+
+    pub fn build(self) -> Result<Person, &'static str> {
+        Ok(Person {
+            name: self.name.ok_or("missing field `name`")?,
+            age: self.age.ok_or("missing field `age`")?,
+        })
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_constant() {
     check_doc_test(
@@ -1163,6 +1567,25 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_trait_impl() {
+    check_doc_test(
+        "generate_trait_impl",
+        r#####"
+struct $0Point { x: i32, y: i32 }
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+impl Clone for Point {
+    fn clone(&self) -> Self {
+        Self { x: self.x.clone(), y: self.y.clone() }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_call() {
     check_doc_test(
@@ -1239,6 +1662,63 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_macro() {
+    check_doc_test(
+        "inline_macro",
+        r#####"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = $0double!(1 + 2);
+}
+"#####,
+        r#####"
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+fn main() {
+    let x = (1 + 2) * 2;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_type_alias() {
+    check_doc_test(
+        "inline_type_alias",
+        r#####"
+type Pair<T> = (T, T);
+
+fn swap(p: $0Pair<u32>) {}
+"#####,
+        r#####"
+type Pair<T> = (T, T);
+
+fn swap(p: (u32, u32)) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_type_alias_uses() {
+    check_doc_test(
+        "inline_type_alias_uses",
+        r#####"
+type $0Pair<T> = (T, T);
+
+fn swap(p: Pair<u32>) -> Pair<u32> {}
+"#####,
+        r#####"
+fn swap(p: (u32, u32)) -> (u32, u32) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_generic() {
     check_doc_test(
@@ -2045,6 +2525,25 @@ use std::{collections::HashMap};
     )
 }
 
+#[test]
+fn doctest_term_search() {
+    check_doc_test(
+        "term_search",
+        r#####"
+struct Meters(u32);
+fn measure(raw: u32) {
+    let distance: Meters = _$0;
+}
+"#####,
+        r#####"
+struct Meters(u32);
+fn measure(raw: u32) {
+    let distance: Meters = Meters(raw);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_toggle_ignore() {
     check_doc_test(
@@ -21,7 +21,10 @@ pub(crate) fn gen_trait_fn_body(
         "Default" => gen_default_impl(adt, func),
         "Hash" => gen_hash_impl(adt, func),
         "PartialEq" => gen_partial_eq(adt, func),
-        "PartialOrd" => gen_partial_ord(adt, func),
+        "PartialOrd" => gen_ord(adt, func, true),
+        "Ord" => gen_ord(adt, func, false),
+        "Display" => gen_display_impl(adt, func),
+        "FromStr" => gen_from_str_impl(adt, func),
         _ => None,
     }
 }
@@ -573,85 +576,339 @@ fn gen_partial_eq(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
     Some(())
 }
 
-fn gen_partial_ord(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
-    fn gen_partial_eq_match(match_target: ast::Expr) -> Option<ast::Stmt> {
-        let mut arms = vec![];
+/// Generate a `PartialOrd` or `Ord` impl comparing the variants in declaration order, then the
+/// fields lexicographically, like the derives do.
+fn gen_ord(adt: &ast::Adt, func: &ast::Fn, partial: bool) -> Option<()> {
+    let method = if partial { "partial_cmp" } else { "cmp" };
+
+    // => core::cmp::Ordering::Less, or Some(core::cmp::Ordering::Less) for `PartialOrd`
+    let gen_ordering = |variant: &str| -> Option<ast::Expr> {
+        let path = make::ext::path_from_idents(["core", "cmp", "Ordering", variant])?;
+        let ordering = make::expr_path(path);
+        if !partial {
+            return Some(ordering);
+        }
+        let some = make::expr_path(make::ext::ident_path("Some"));
+        Some(make::expr_call(some, make::arg_list(Some(ordering))))
+    };
 
-        let variant_name =
+    // => match lhs.cmp(rhs) { core::cmp::Ordering::Equal => {} ord => return ord, }
+    let gen_ord_match = |cmp: ast::Expr| -> Option<ast::Stmt> {
+        let mut arms = vec![];
+        let equal =
             make::path_pat(make::ext::path_from_idents(["core", "cmp", "Ordering", "Equal"])?);
-        let lhs = make::tuple_struct_pat(make::ext::path_from_idents(["Some"])?, [variant_name]);
-        arms.push(make::match_arm(Some(lhs.into()), None, make::expr_empty_block()));
-
+        let equal = if partial {
+            make::tuple_struct_pat(make::ext::path_from_idents(["Some"])?, [equal]).into()
+        } else {
+            equal
+        };
+        arms.push(make::match_arm(Some(equal), None, make::expr_empty_block()));
         arms.push(make::match_arm(
             [make::ident_pat(false, false, make::name("ord")).into()],
             None,
             make::expr_return(Some(make::expr_path(make::ext::ident_path("ord")))),
         ));
         let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
-        Some(make::expr_stmt(make::expr_match(match_target, list)).into())
-    }
+        Some(make::expr_stmt(make::expr_match(cmp, list)).into())
+    };
 
-    fn gen_partial_cmp_call(lhs: ast::Expr, rhs: ast::Expr) -> ast::Expr {
-        let rhs = make::expr_ref(rhs, false);
-        let method = make::name_ref("partial_cmp");
-        make::expr_method_call(lhs, method, make::arg_list(Some(rhs)))
-    }
+    let gen_cmp_call = |lhs: ast::Expr, rhs: ast::Expr| -> ast::Expr {
+        make::expr_method_call(lhs, make::name_ref(method), make::arg_list(Some(rhs)))
+    };
 
-    // FIXME: return `None` if the trait carries a generic type; we can only
-    // generate this code `Self` for the time being.
+    // Compares all fields but the last one in a `match`, returning early when they differ.
+    let gen_cmp_chain = |mut cmps: Vec<ast::Expr>| -> Option<ast::BlockExpr> {
+        let tail = match cmps.pop() {
+            Some(it) => it,
+            None => gen_ordering("Equal")?,
+        };
+        let stmts = cmps.into_iter().map(&gen_ord_match).collect::<Option<Vec<_>>>()?;
+        Some(make::block_expr(stmts, Some(tail)))
+    };
+
+    let gen_field_cmp = |field: &str| -> ast::Expr {
+        let lhs = make::expr_path(make::ext::ident_path("self"));
+        let lhs = make::expr_field(lhs, field);
+        let rhs = make::expr_path(make::ext::ident_path("other"));
+        let rhs = make::expr_ref(make::expr_field(rhs, field), false);
+        gen_cmp_call(lhs, rhs)
+    };
 
     let body = match adt {
         // `PartialOrd` cannot be derived for unions, so no default impl can be provided.
         ast::Adt::Union(_) => return None,
-        // `core::mem::Discriminant` does not implement `PartialOrd` in stable Rust today.
-        ast::Adt::Enum(_) => return None,
-        ast::Adt::Struct(strukt) => match strukt.field_list() {
-            Some(ast::FieldList::RecordFieldList(field_list)) => {
-                let mut exprs = vec![];
-                for field in field_list.fields() {
-                    let lhs = make::expr_path(make::ext::ident_path("self"));
-                    let lhs = make::expr_field(lhs, &field.name()?.to_string());
-                    let rhs = make::expr_path(make::ext::ident_path("other"));
-                    let rhs = make::expr_field(rhs, &field.name()?.to_string());
-                    let ord = gen_partial_cmp_call(lhs, rhs);
-                    exprs.push(ord);
-                }
+        ast::Adt::Enum(enum_) => {
+            let variants: Vec<_> = enum_.variant_list()?.variants().collect();
+            let mut arms = vec![];
+            for variant in &variants {
+                let variant_path =
+                    make::ext::path_from_idents(["Self", &variant.name()?.to_string()])?;
+                let (left, right, mut cmps): (ast::Pat, ast::Pat, _) = match variant.field_list() {
+                    // => (Self::Bar { bin: l_bin }, Self::Bar { bin: r_bin }) => l_bin.cmp(r_bin),
+                    Some(ast::FieldList::RecordFieldList(list)) => {
+                        let mut l_fields = vec![];
+                        let mut r_fields = vec![];
+                        let mut cmps = vec![];
+                        for field in list.fields() {
+                            let field_name = field.name()?.to_string();
+                            let l_name = format!("l_{}", field_name);
+                            let r_name = format!("r_{}", field_name);
+                            for (fields, name) in
+                                [(&mut l_fields, &l_name), (&mut r_fields, &r_name)]
+                            {
+                                let pat = make::ext::simple_ident_pat(make::name(name));
+                                let name_ref = make::name_ref(&field_name);
+                                fields.push(make::record_pat_field(name_ref, pat.into()));
+                            }
+                            let lhs = make::expr_path(make::ext::ident_path(&l_name));
+                            let rhs = make::expr_path(make::ext::ident_path(&r_name));
+                            cmps.push(gen_cmp_call(lhs, rhs));
+                        }
+                        let left = make::record_pat_field_list(l_fields);
+                        let right = make::record_pat_field_list(r_fields);
+                        (
+                            make::record_pat_with_fields(variant_path.clone(), left).into(),
+                            make::record_pat_with_fields(variant_path, right).into(),
+                            cmps,
+                        )
+                    }
+                    // => (Self::Bar(l0), Self::Bar(r0)) => l0.cmp(r0),
+                    Some(ast::FieldList::TupleFieldList(list)) => {
+                        let mut l_fields = vec![];
+                        let mut r_fields = vec![];
+                        let mut cmps = vec![];
+                        for (i, _) in list.fields().enumerate() {
+                            let l_name = format!("l{}", i);
+                            let r_name = format!("r{}", i);
+                            l_fields
+                                .push(make::ident_pat(false, false, make::name(&l_name)).into());
+                            r_fields
+                                .push(make::ident_pat(false, false, make::name(&r_name)).into());
+                            let lhs = make::expr_path(make::ext::ident_path(&l_name));
+                            let rhs = make::expr_path(make::ext::ident_path(&r_name));
+                            cmps.push(gen_cmp_call(lhs, rhs));
+                        }
+                        (
+                            make::tuple_struct_pat(variant_path.clone(), l_fields).into(),
+                            make::tuple_struct_pat(variant_path, r_fields).into(),
+                            cmps,
+                        )
+                    }
+                    // => (Self::Bar, Self::Bar) => core::cmp::Ordering::Equal,
+                    None => {
+                        (make::path_pat(variant_path.clone()), make::path_pat(variant_path), vec![])
+                    }
+                };
+                let expr = match cmps.len() {
+                    0 => gen_ordering("Equal")?,
+                    1 => cmps.pop()?,
+                    _ => gen_cmp_chain(cmps)?.indent(ast::edit::IndentLevel(1)).into(),
+                };
+                arms.push(make::match_arm(Some(make::tuple_pat([left, right]).into()), None, expr));
+            }
 
-                let tail = exprs.pop();
-                let stmts = exprs
-                    .into_iter()
-                    .map(gen_partial_eq_match)
-                    .collect::<Option<Vec<ast::Stmt>>>()?;
-                make::block_expr(stmts.into_iter(), tail).indent(ast::edit::IndentLevel(1))
+            // Variants compare in the order they're declared.
+            // => (Self::Foo(..), _) => core::cmp::Ordering::Less,
+            // => (_, Self::Foo(..)) => core::cmp::Ordering::Greater,
+            for variant in variants.iter().take(variants.len().saturating_sub(1)) {
+                let variant_path =
+                    make::ext::path_from_idents(["Self", &variant.name()?.to_string()])?;
+                let pat: ast::Pat = match variant.field_list() {
+                    Some(ast::FieldList::RecordFieldList(_)) => {
+                        make::record_pat(variant_path, [make::rest_pat().into()]).into()
+                    }
+                    Some(ast::FieldList::TupleFieldList(_)) => {
+                        make::tuple_struct_pat(variant_path, [make::rest_pat().into()]).into()
+                    }
+                    None => make::path_pat(variant_path),
+                };
+                let wildcard: ast::Pat = make::wildcard_pat().into();
+                let less = make::tuple_pat([pat.clone(), wildcard.clone()]).into();
+                arms.push(make::match_arm(Some(less), None, gen_ordering("Less")?));
+                let greater = make::tuple_pat([wildcard, pat]).into();
+                arms.push(make::match_arm(Some(greater), None, gen_ordering("Greater")?));
+            }
+            if arms.is_empty() {
+                return None;
             }
 
-            Some(ast::FieldList::TupleFieldList(field_list)) => {
-                let mut exprs = vec![];
-                for (i, _) in field_list.fields().enumerate() {
-                    let idx = format!("{}", i);
-                    let lhs = make::expr_path(make::ext::ident_path("self"));
-                    let lhs = make::expr_field(lhs, &idx);
-                    let rhs = make::expr_path(make::ext::ident_path("other"));
-                    let rhs = make::expr_field(rhs, &idx);
-                    let ord = gen_partial_cmp_call(lhs, rhs);
-                    exprs.push(ord);
-                }
-                let tail = exprs.pop();
-                let stmts = exprs
-                    .into_iter()
-                    .map(gen_partial_eq_match)
-                    .collect::<Option<Vec<ast::Stmt>>>()?;
-                make::block_expr(stmts.into_iter(), tail).indent(ast::edit::IndentLevel(1))
+            let lhs = make::expr_path(make::ext::ident_path("self"));
+            let rhs = make::expr_path(make::ext::ident_path("other"));
+            let match_target = make::expr_tuple(vec![lhs, rhs]);
+            let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+            let expr = make::expr_match(match_target, list);
+            make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1))
+        }
+        ast::Adt::Struct(strukt) => {
+            let cmps = match strukt.field_list() {
+                Some(ast::FieldList::RecordFieldList(field_list)) => field_list
+                    .fields()
+                    .map(|field| Some(gen_field_cmp(&field.name()?.to_string())))
+                    .collect::<Option<Vec<_>>>()?,
+                Some(ast::FieldList::TupleFieldList(field_list)) => field_list
+                    .fields()
+                    .enumerate()
+                    .map(|(i, _)| gen_field_cmp(&format!("{}", i)))
+                    .collect(),
+                // No fields in the body means the values are always equal.
+                None => vec![],
+            };
+            gen_cmp_chain(cmps)?.indent(ast::edit::IndentLevel(1))
+        }
+    };
+
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
+
+/// Generate a `Display` impl writing the fields of the target type after its name.
+fn gen_display_impl(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
+    // => write!(f, "Name { a: {}, b: {} }", a, b)
+    fn gen_write_call(
+        name: &str,
+        field_list: Option<ast::FieldList>,
+        args: Vec<ast::Expr>,
+    ) -> Option<ast::Expr> {
+        let fmt = match field_list {
+            Some(ast::FieldList::RecordFieldList(list)) => {
+                let fields = list
+                    .fields()
+                    .map(|field| Some(format!("{}: {{}}", field.name()?)))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("{} {{{{ {} }}}}", name, fields.join(", "))
             }
+            Some(ast::FieldList::TupleFieldList(list)) => {
+                let fields = list.fields().map(|_| "{}").collect::<Vec<_>>();
+                format!("{}({})", name, fields.join(", "))
+            }
+            None => name.to_string(),
+        };
+        let target = make::expr_path(make::ext::ident_path("f"));
+        let fmt = make::expr_literal(&format!("\"{}\"", fmt)).into();
+        let args = make::arg_list([target, fmt].into_iter().chain(args));
+        let macro_name = make::expr_path(make::ext::ident_path("write"));
+        Some(make::expr_macro_call(macro_name, args))
+    }
 
-            // No fields in the body means there's nothing to hash.
-            None => {
-                let expr = make::expr_literal("true").into();
-                make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1))
+    let expr = match adt {
+        // `Display` cannot be derived for unions, so no default impl can be provided.
+        ast::Adt::Union(_) => return None,
+
+        // => match self { Self::Variant(arg0) => write!(f, "Variant({})", arg0) }
+        ast::Adt::Enum(enum_) => {
+            let mut arms = vec![];
+            for variant in enum_.variant_list()?.variants() {
+                let name = variant.name()?.to_string();
+                let variant_path = make::ext::path_from_idents(["Self", &name])?;
+                let (pat, args): (ast::Pat, Vec<_>) = match variant.field_list() {
+                    Some(ast::FieldList::RecordFieldList(list)) => {
+                        let names =
+                            list.fields().map(|it| it.name()).collect::<Option<Vec<_>>>()?;
+                        let pats =
+                            names.iter().map(|it| make::ident_pat(false, false, it.clone()).into());
+                        let args = names
+                            .iter()
+                            .map(|it| make::expr_path(make::ext::ident_path(&it.to_string())))
+                            .collect();
+                        (make::record_pat(variant_path, pats).into(), args)
+                    }
+                    Some(ast::FieldList::TupleFieldList(list)) => {
+                        let names: Vec<_> =
+                            list.fields().enumerate().map(|(i, _)| format!("arg{}", i)).collect();
+                        let pats = names
+                            .iter()
+                            .map(|it| make::ident_pat(false, false, make::name(it)).into());
+                        let args = names
+                            .iter()
+                            .map(|it| make::expr_path(make::ext::ident_path(it)))
+                            .collect();
+                        (make::tuple_struct_pat(variant_path, pats).into(), args)
+                    }
+                    None => (make::path_pat(variant_path), vec![]),
+                };
+                let expr = gen_write_call(&name, variant.field_list(), args)?;
+                arms.push(make::match_arm(Some(pat), None, expr));
             }
-        },
+            let match_target = make::expr_path(make::ext::ident_path("self"));
+            let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+            make::expr_match(match_target, list)
+        }
+
+        // => write!(f, "Name({}, {})", self.0, self.1)
+        ast::Adt::Struct(strukt) => {
+            let field_list = strukt.field_list();
+            let fields: Vec<String> = match &field_list {
+                Some(ast::FieldList::RecordFieldList(list)) => {
+                    list.fields().map(|it| Some(it.name()?.to_string())).collect::<Option<_>>()?
+                }
+                Some(ast::FieldList::TupleFieldList(list)) => {
+                    list.fields().enumerate().map(|(i, _)| format!("{}", i)).collect()
+                }
+                None => vec![],
+            };
+            let args = fields
+                .iter()
+                .map(|it| make::expr_field(make::expr_path(make::ext::ident_path("self")), it))
+                .collect();
+            gen_write_call(&adt.name()?.to_string(), field_list, args)?
+        }
     };
+    let body = make::block_expr(None, Some(expr)).indent(ast::edit::IndentLevel(1));
+    ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
+    Some(())
+}
 
+/// Generate a `FromStr` impl parsing the names of the variants of a fieldless enum.
+fn gen_from_str_impl(adt: &ast::Adt, func: &ast::Fn) -> Option<()> {
+    let enum_ = match adt {
+        ast::Adt::Enum(it) => it,
+        _ => return None,
+    };
+    // Only the `()` error can be created without knowing more about the error type.
+    let err_ty = func
+        .syntax()
+        .ancestors()
+        .find_map(ast::Impl::cast)?
+        .assoc_item_list()?
+        .assoc_items()
+        .find_map(|it| match it {
+            ast::AssocItem::TypeAlias(it) if it.name()?.text() == "Err" => it.ty(),
+            _ => None,
+        })?;
+    match err_ty {
+        ast::Type::TupleType(it) if it.fields().next().is_none() => (),
+        _ => return None,
+    }
+    let param = match func.param_list()?.params().next()?.pat()? {
+        ast::Pat::IdentPat(it) => it.name()?,
+        _ => return None,
+    };
+
+    // => match s { "Variant" => Ok(Self::Variant), _ => Err(()) }
+    let mut arms = vec![];
+    for variant in enum_.variant_list()?.variants() {
+        if variant.field_list().is_some() {
+            return None;
+        }
+        let name = variant.name()?;
+        let pat = make::literal_pat(&format!("\"{}\"", name));
+        let variant = make::expr_path(make::ext::path_from_idents(["Self", &name.to_string()])?);
+        let ok = make::expr_call(
+            make::expr_path(make::ext::ident_path("Ok")),
+            make::arg_list(Some(variant)),
+        );
+        arms.push(make::match_arm(Some(pat.into()), None, ok));
+    }
+    let err = make::expr_call(
+        make::expr_path(make::ext::ident_path("Err")),
+        make::arg_list(Some(make::expr_unit())),
+    );
+    arms.push(make::match_arm(Some(make::wildcard_pat().into()), None, err));
+
+    let match_target = make::expr_path(make::ext::ident_path(&param.to_string()));
+    let list = make::match_arm_list(arms).indent(ast::edit::IndentLevel(1));
+    let body = make::block_expr(None, Some(make::expr_match(match_target, list)))
+        .indent(ast::edit::IndentLevel(1));
     ted::replace(func.body()?.syntax(), body.clone_for_update().syntax());
     Some(())
 }
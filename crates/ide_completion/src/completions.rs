@@ -2,6 +2,7 @@
 
 pub(crate) mod attribute;
 pub(crate) mod dot;
+pub(crate) mod expected_type;
 pub(crate) mod extern_abi;
 pub(crate) mod flyimport;
 pub(crate) mod fn_param;
@@ -14,6 +15,7 @@ pub(crate) mod postfix;
 pub(crate) mod qualified_path;
 pub(crate) mod record;
 pub(crate) mod snippet;
+pub(crate) mod term_search;
 pub(crate) mod trait_impl;
 pub(crate) mod unqualified_path;
 pub(crate) mod use_;
@@ -33,7 +35,7 @@ use crate::{
         function::{render_fn, render_method},
         pattern::{render_struct_pat, render_variant_pat},
         render_field, render_resolution, render_tuple_field,
-        struct_literal::render_struct_literal,
+        struct_literal::{render_struct_literal, render_variant_literal},
         type_alias::{render_type_alias, render_type_alias_with_eq},
         RenderContext,
     },
@@ -77,6 +79,18 @@ impl Builder {
 }
 
 impl Completions {
+    /// Ranks the items the user recently accepted higher.
+    pub(crate) fn mark_recently_accepted(&mut self, ctx: &CompletionContext) {
+        if ctx.config.recently_accepted.is_empty() {
+            return;
+        }
+        for item in &mut self.buf {
+            let is_recently_accepted =
+                ctx.config.recently_accepted.iter().any(|it| it == item.label());
+            item.relevance_mut().is_recently_accepted = is_recently_accepted;
+        }
+    }
+
     fn add(&mut self, item: CompletionItem) {
         self.buf.push(item)
     }
@@ -234,6 +248,16 @@ impl Completions {
         self.add_opt(item);
     }
 
+    pub(crate) fn add_variant_literal(
+        &mut self,
+        ctx: &CompletionContext,
+        variant: hir::Variant,
+        path: hir::ModPath,
+    ) {
+        let item = render_variant_literal(RenderContext::new(ctx, false), variant, path);
+        self.add_opt(item);
+    }
+
     pub(crate) fn add_tuple_field(
         &mut self,
         ctx: &CompletionContext,
@@ -29,11 +29,17 @@ pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
         Some("target_endian") => ["little", "big"].into_iter().for_each(add_completion),
         Some(name) => {
             if let Some(krate) = ctx.krate {
+                let enabled = krate.cfg(ctx.db);
                 krate.potential_cfg(ctx.db).get_cfg_values(&name).cloned().for_each(|s| {
                     let insert_text = format!(r#""{}""#, s);
+                    let is_enabled = enabled.get_cfg_values(name).any(|it| *it == s);
                     let mut item =
                         CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
                     item.insert_text(insert_text);
+                    // Declared features are completed even if they are off in the current build.
+                    if !is_enabled {
+                        item.detail("disabled");
+                    }
 
                     acc.add(item.build());
                 })
@@ -0,0 +1,411 @@
+//! Completes expressions that produce the type expected at the cursor.
+//!
+//! ```
+//! struct Config { verbose: bool }
+//! impl Config {
+//!     fn new(verbose: bool) -> Config { Config { verbose } }
+//! }
+//! fn run(config: Config) {}
+//! fn main() {
+//!     run($0)
+//! }
+//! ```
+//!
+//! offers `Config::new(…)`, as well as `Config::default()` and `Default::default()` if the
+//! type implements `Default`. Constructors returning `Result<T, _>` or `Option<T>` are
+//! offered with a trailing `?` when the enclosing function returns the same kind of type, and
+//! locals are offered as `local.into()` or `local.clone()` when that gets them to `T`.
+
+use hir::{AssocItem, HasAttrs, HasVisibility, HirDisplay};
+use ide_db::SymbolKind;
+use itertools::Itertools;
+
+use crate::{
+    item::{Builder, CompletionRelevanceTypeMatch},
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionRelevance, Completions,
+};
+
+pub(crate) fn complete_expected_type_exprs(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+) -> Option<()> {
+    if !ctx.expects_expression() || ctx.path_qual().is_some() {
+        return None;
+    }
+    let expected = ctx.expected_type.as_ref()?;
+    if expected.is_unknown() {
+        return None;
+    }
+    let _p = profile::span("complete_expected_type_exprs");
+
+    if let Some(hir::Adt::Enum(enum_)) = expected.as_adt() {
+        super::enum_variants_with_paths(acc, ctx, enum_, |acc, ctx, variant, path| {
+            acc.add_variant_literal(ctx, variant, path)
+        });
+    }
+
+    if let Some(adt) = expected.as_adt() {
+        complete_constructors(acc, ctx, expected, adt);
+    }
+
+    complete_default(acc, ctx, expected);
+
+    complete_converted_locals(acc, ctx, expected);
+
+    Some(())
+}
+
+fn complete_constructors(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    expected: &hir::Type,
+    adt: hir::Adt,
+) -> Option<()> {
+    let module = ctx.module?;
+    let path = module.find_use_path(ctx.db, hir::ModuleDef::from(adt))?.to_string();
+
+    let try_wrapper = ctx
+        .function_def
+        .as_ref()
+        .and_then(|fn_| ctx.sema.to_def(fn_))
+        .and_then(|fn_| wrapper_kind(ctx, &fn_.ret_type(ctx.db)));
+
+    let mut has_default_fn = false;
+    let mut constructors = Vec::new();
+    expected.iterate_assoc_items(ctx.db, module.krate(), |item| {
+        if let AssocItem::Function(func) = item {
+            if func.self_param(ctx.db).is_none()
+                && !func.is_unsafe(ctx.db)
+                && func.is_visible_from(ctx.db, module)
+            {
+                constructors.push(func);
+            }
+        }
+        None::<()>
+    });
+
+    for func in constructors {
+        let name = func.name(ctx.db);
+        let ret_ty = func.ret_type(ctx.db);
+        let call = format!("{}::{}", path, name);
+        if ret_ty.could_unify_with(ctx.db, expected) {
+            has_default_fn |= name.to_smol_str() == "default";
+            add_call(acc, ctx, func, &call, "");
+        } else if let Some(wrapper) = wrapper_kind(ctx, &ret_ty) {
+            let unwraps_to_expected = ret_ty
+                .type_arguments()
+                .next()
+                .map_or(false, |inner| inner.could_unify_with(ctx.db, expected));
+            if unwraps_to_expected && try_wrapper == Some(wrapper) {
+                add_call(acc, ctx, func, &call, "?");
+            }
+        }
+    }
+
+    if let Some(default_trait) = ctx.famous_defs().core_default_Default() {
+        if !has_default_fn && expected.impls_trait(ctx.db, default_trait, &[]) {
+            // Most types implement `Default`, so this doesn't say much about the intent.
+            let call = format!("{}::default()", path);
+            let mut item =
+                CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), &call);
+            item.detail(expected.display(ctx.db).to_string());
+            add_with_relevance(acc, ctx, item, &call, &format!("{}$0", call), None);
+        }
+    }
+
+    Some(())
+}
+
+fn complete_default(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    expected: &hir::Type,
+) -> Option<()> {
+    let default_trait = ctx.famous_defs().core_default_Default()?;
+    if expected.impls_trait(ctx.db, default_trait, &[]) {
+        // Like `T::default()`, this says little about the intent, so it isn't ranked up.
+        let call = "Default::default()";
+        let mut item = CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), call);
+        item.detail(expected.display(ctx.db).to_string());
+        add_with_relevance(acc, ctx, item, call, "Default::default()$0", None);
+    }
+    Some(())
+}
+
+fn complete_converted_locals(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    expected: &hir::Type,
+) -> Option<()> {
+    let famous_defs = ctx.famous_defs();
+    let from_trait = famous_defs.core_convert_From();
+    let clone_trait = famous_defs.core_clone_Clone();
+
+    for (name, local) in ctx.locals.iter() {
+        let ty = local.ty(ctx.db);
+        if ty.is_unknown() || ty.could_unify_with(ctx.db, expected) {
+            continue;
+        }
+
+        if let Some(from_trait) = from_trait {
+            if expected.impls_trait(ctx.db, from_trait, std::slice::from_ref(&ty)) {
+                let expr = format!("{}.into()", name);
+                add_expr(acc, ctx, &expr, &format!("{}$0", expr), expected);
+                continue;
+            }
+        }
+
+        if let (Some(clone_trait), Some(pointee)) = (clone_trait, ty.remove_ref()) {
+            if pointee.could_unify_with(ctx.db, expected)
+                && pointee.impls_trait(ctx.db, clone_trait, &[])
+            {
+                let expr = format!("{}.clone()", name);
+                add_expr(acc, ctx, &expr, &format!("{}$0", expr), expected);
+            }
+        }
+    }
+
+    Some(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WrapperKind {
+    Option,
+    Result,
+}
+
+fn wrapper_kind(ctx: &CompletionContext, ty: &hir::Type) -> Option<WrapperKind> {
+    let famous_defs = ctx.famous_defs();
+    let adt = ty.as_adt()?;
+    if Some(adt) == famous_defs.core_option_Option().map(hir::Adt::Enum) {
+        Some(WrapperKind::Option)
+    } else if Some(adt) == famous_defs.core_result_Result().map(hir::Adt::Enum) {
+        Some(WrapperKind::Result)
+    } else {
+        None
+    }
+}
+
+fn add_call(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    func: hir::Function,
+    call: &str,
+    suffix: &str,
+) {
+    let params = func.assoc_fn_params(ctx.db);
+    let label = if params.is_empty() {
+        format!("{}(){}", call, suffix)
+    } else {
+        format!("{}(…){}", call, suffix)
+    };
+    let args =
+        params.iter().enumerate().format_with(", ", |(idx, param), f| match param.name(ctx.db) {
+            Some(name) => {
+                let name = name.to_smol_str();
+                f(&format_args!("${{{}:{}}}", idx + 1, name.trim_start_matches('_')))
+            }
+            None => f(&format_args!("${{{}:_}}", idx + 1)),
+        });
+    let snippet = format!("{}({}){}$0", call, args, suffix);
+    let text = format!("{}(){}", call, suffix);
+
+    let mut item = CompletionItem::new(SymbolKind::Function, ctx.source_range(), label);
+    item.detail(format!("-> {}", func.ret_type(ctx.db).display(ctx.db)))
+        .set_documentation(func.docs(ctx.db));
+    add_with_relevance(acc, ctx, item, &text, &snippet, Some(CompletionRelevanceTypeMatch::Exact));
+}
+
+fn add_expr(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    label: &str,
+    snippet: &str,
+    expected: &hir::Type,
+) {
+    let mut item = CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), label);
+    item.detail(expected.display(ctx.db).to_string());
+    add_with_relevance(acc, ctx, item, label, snippet, Some(CompletionRelevanceTypeMatch::Exact));
+}
+
+fn add_with_relevance(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    mut item: Builder,
+    text: &str,
+    snippet: &str,
+    type_match: Option<CompletionRelevanceTypeMatch>,
+) {
+    match ctx.config.snippet_cap {
+        Some(cap) => item.insert_snippet(cap, snippet),
+        None => item.insert_text(text),
+    };
+    item.set_relevance(CompletionRelevance { type_match, ..CompletionRelevance::default() });
+    item.add_to(acc);
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        let actual = actual
+            .lines()
+            .filter(|line| line.contains("::") || line.contains('.'))
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_constructors_and_default() {
+        check(
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+struct Config { verbose: bool }
+impl Config {
+    fn new(verbose: bool) -> Config { Config { verbose } }
+    fn quiet() -> Self { Config { verbose: false } }
+    fn verbose(&self) -> bool { self.verbose }
+    unsafe fn from_raw(raw: *const u8) -> Config { Config { verbose: false } }
+}
+fn run(config: Config) {}
+fn main() {
+    run($0)
+}
+"#,
+            expect![[r#"
+                fn Config::new(…)     -> Config
+                fn Config::quiet()    -> Config
+                sn Config::default()  Config
+                sn Default::default() Config
+            "#]],
+        );
+    }
+
+    #[test]
+    fn constructor_call_edit() {
+        check_edit(
+            "Config::new(…)",
+            r#"
+struct Config { verbose: bool }
+impl Config {
+    fn new(verbose: bool, _level: u8) -> Config { Config { verbose } }
+}
+fn main() {
+    let config: Config = $0;
+}
+"#,
+            r#"
+struct Config { verbose: bool }
+impl Config {
+    fn new(verbose: bool, _level: u8) -> Config { Config { verbose } }
+}
+fn main() {
+    let config: Config = Config::new(${1:verbose}, ${2:level})$0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fallible_constructors_need_matching_return_type() {
+        let fixture = r#"
+//- minicore: option, result
+struct Port(u16);
+impl Port {
+    fn parse(s: &str) -> Result<Port, ()> { Err(()) }
+    fn checked(n: u32) -> Option<Port> { None }
+}
+fn connect(port: Port) {}
+fn main() -> Result<(), ()> {
+    connect($0);
+    Ok(())
+}
+"#;
+        check(
+            fixture,
+            expect![[r#"
+                fn Port::parse(…)? -> Result<Port, ()>
+            "#]],
+        );
+        check_edit(
+            "Port::parse(…)?",
+            fixture,
+            r#"
+struct Port(u16);
+impl Port {
+    fn parse(s: &str) -> Result<Port, ()> { Err(()) }
+    fn checked(n: u32) -> Option<Port> { None }
+}
+fn connect(port: Port) {}
+fn main() -> Result<(), ()> {
+    connect(Port::parse(${1:s})?$0);
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_record_variant_literals() {
+        check_edit(
+            "Shape::Rect {…}",
+            r#"
+enum Shape { Circle(u32), Rect { w: u32, h: u32 } }
+fn draw(shape: Shape) {}
+fn main() {
+    draw($0);
+}
+"#,
+            r#"
+enum Shape { Circle(u32), Rect { w: u32, h: u32 } }
+fn draw(shape: Shape) {}
+fn main() {
+    draw(Shape::Rect { w: ${1:()}, h: ${2:()} }$0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn converts_locals() {
+        check(
+            r#"
+//- minicore: from, clone, derive
+#[derive(Clone)]
+struct Name(u32);
+struct Id(u32);
+impl From<u32> for Id {
+    fn from(raw: u32) -> Id { Id(raw) }
+}
+fn lookup(id: Id) {}
+fn greet(name: Name) {}
+fn main(raw: u32, name: &Name) {
+    lookup($0);
+}
+"#,
+            expect![[r#"
+                sn raw.into() Id
+            "#]],
+        );
+        check(
+            r#"
+//- minicore: from, clone, derive
+#[derive(Clone)]
+struct Name(u32);
+fn greet(name: Name) {}
+fn main(raw: u32, name: &Name) {
+    greet($0);
+}
+"#,
+            expect![[r#"
+                sn name.clone() Name
+            "#]],
+        );
+    }
+}
@@ -118,6 +118,10 @@ pub(crate) fn import_on_the_fly(acc: &mut Completions, ctx: &CompletionContext)
     {
         return None;
     }
+    // Import assets resolve the qualifier with `sema`, which doesn't know the fragment's syntax.
+    if ctx.in_macro_fragment && ctx.path_qual().is_some() {
+        return None;
+    }
     // FIXME: This should be encoded in a different way
     if ctx.pattern_ctx.is_none() && ctx.path_context.is_none() && !ctx.has_dot_receiver() {
         // completion inside `ast::Name` of a item declaration
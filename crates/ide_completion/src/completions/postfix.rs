@@ -73,7 +73,7 @@ pub(crate) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
     }
 
     if !ctx.config.snippets.is_empty() {
-        add_custom_postfix_completions(acc, ctx, &postfix_snippet, &receiver_text);
+        add_custom_postfix_completions(acc, ctx, &postfix_snippet, &receiver_text, &receiver_ty);
     }
 
     let try_enum = TryEnum::from_ty(&ctx.sema, &receiver_ty.strip_references());
@@ -257,10 +257,14 @@ fn add_custom_postfix_completions(
     ctx: &CompletionContext,
     postfix_snippet: impl Fn(&str, &str, &str) -> Builder,
     receiver_text: &str,
+    receiver_ty: &hir::Type,
 ) -> Option<()> {
     let import_scope = ImportScope::find_insert_use_container(&ctx.token.parent()?, &ctx.sema)?;
-    ctx.config.postfix_snippets().filter(|(_, snip)| snip.scope == SnippetScope::Expr).for_each(
-        |(trigger, snippet)| {
+    ctx.config
+        .postfix_snippets()
+        .filter(|(_, snip)| snip.scope == SnippetScope::Expr)
+        .filter(|(_, snip)| snip.applies_to_receiver(ctx, receiver_ty))
+        .for_each(|(trigger, snippet)| {
             let imports = match snippet.imports(ctx, &import_scope) {
                 Some(imports) => imports,
                 None => return,
@@ -273,8 +277,7 @@ fn add_custom_postfix_completions(
                 builder.add_import(import);
             }
             builder.add_to(acc);
-        },
-    );
+        });
     None
 }
 
@@ -500,6 +503,7 @@ fn main() {
                     &["ControlFlow::Break(${receiver})".into()],
                     "",
                     &["core::ops::ControlFlow".into()],
+                    None,
                     crate::SnippetScope::Expr,
                 )
                 .unwrap()],
@@ -518,6 +522,43 @@ fn main() { ControlFlow::Break(42) }
         );
     }
 
+    #[test]
+    fn custom_postfix_completion_receiver_type() {
+        let config = CompletionConfig {
+            snippets: vec![Snippet::new(
+                &[],
+                &["ok_or".into()],
+                &["${receiver}.ok_or(${error:()})".into()],
+                "",
+                &[],
+                Some("core::option::Option"),
+                crate::SnippetScope::Expr,
+            )
+            .unwrap()],
+            ..TEST_CONFIG
+        };
+        check_edit_with_config(
+            config.clone(),
+            "ok_or",
+            r#"
+//- minicore: option
+fn main() { let x = &Some(42); x.$0 }
+"#,
+            r#"
+fn main() { let x = &Some(42); x.ok_or(${1:()}) }
+"#,
+        );
+        let completions = crate::tests::completion_list_with_config(
+            config,
+            r#"
+//- minicore: option
+fn main() { 42.$0 }
+"#,
+            true,
+        );
+        assert!(!completions.contains("ok_or"));
+    }
+
     #[test]
     fn postfix_completion_for_format_like_strings() {
         check_edit(
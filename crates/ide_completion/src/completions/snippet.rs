@@ -141,6 +141,7 @@ mod tests {
                     &["ControlFlow::Break(())".into()],
                     "",
                     &["core::ops::ControlFlow".into()],
+                    None,
                     crate::SnippetScope::Expr,
                 )
                 .unwrap()],
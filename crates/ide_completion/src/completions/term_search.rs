@@ -0,0 +1,132 @@
+//! Completes whole expressions of the expected type found by term search.
+
+use std::time::Duration;
+
+use hir::term_search::{Expr, TermSearchConfig};
+use ide_db::term_search::term_search;
+
+use crate::{
+    item::CompletionRelevanceTypeMatch, CompletionContext, CompletionItem, CompletionItemKind,
+    CompletionRelevance, Completions,
+};
+
+pub(crate) fn complete_term_search(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    if !ctx.config.enable_term_search || !ctx.expects_expression() || ctx.path_qual().is_some() {
+        return None;
+    }
+    let expected = ctx.expected_type.as_ref()?;
+    if expected.is_unknown() {
+        return None;
+    }
+    let module = ctx.module?;
+
+    // Completion runs on every keystroke, so keep the search cheaper than the assist's.
+    // Tests don't get a time limit, so that their results don't depend on the machine.
+    let config = TermSearchConfig {
+        depth: 2,
+        max_exprs: 200,
+        exprs_per_type: 3,
+        budget: (!cfg!(test)).then(|| Duration::from_millis(50)),
+    };
+    for expr in term_search(&ctx.sema, &ctx.scope, expected, &config) {
+        // These are already completed by name.
+        if matches!(expr, Expr::Local(_) | Expr::Const(_) | Expr::Static(_)) {
+            continue;
+        }
+        let code = match expr.gen_source_code(ctx.db, module) {
+            Some(it) => it,
+            None => continue,
+        };
+        let mut item = CompletionItem::new(CompletionItemKind::Snippet, ctx.source_range(), &code);
+        item.insert_text(code).set_relevance(CompletionRelevance {
+            type_match: Some(CompletionRelevanceTypeMatch::Exact),
+            ..CompletionRelevance::default()
+        });
+        item.add_to(acc);
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{
+        tests::{check_edit_with_config, completion_list_with_config, TEST_CONFIG},
+        CompletionConfig,
+    };
+
+    fn config() -> CompletionConfig {
+        CompletionConfig { enable_term_search: true, ..TEST_CONFIG }
+    }
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list_with_config(config(), ra_fixture, true);
+        let actual = actual
+            .lines()
+            .filter(|line| line.starts_with("sn "))
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_composed_expressions() {
+        check(
+            r#"
+struct Config { port: u16 }
+struct Server(u16);
+fn serve(server: Server) {}
+fn start(config: Config) {
+    serve($0);
+}
+"#,
+            expect![[r#"
+                sn Server {…}          Server($1)$0
+                sn Server(config.port)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn term_search_edit() {
+        check_edit_with_config(
+            config(),
+            "listen(config.port)",
+            r#"
+struct Config { port: u16 }
+struct Server;
+fn listen(port: u16) -> Server { Server }
+fn start(config: Config) -> Server {
+    $0
+}
+"#,
+            r#"
+struct Config { port: u16 }
+struct Server;
+fn listen(port: u16) -> Server { Server }
+fn start(config: Config) -> Server {
+    listen(config.port)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = completion_list_with_config(
+            TEST_CONFIG,
+            r#"
+struct Config { port: u16 }
+struct Server(u16);
+fn serve(server: Server) {}
+fn start(config: Config) {
+    serve($0);
+}
+"#,
+            true,
+        );
+        assert!(!actual.contains("Server(config.port)"));
+    }
+}
@@ -34,13 +34,18 @@
 use hir::{self, HasAttrs};
 use ide_db::{path_transform::PathTransform, traits::get_missing_assoc_items, SymbolKind};
 use syntax::{
-    ast::{self, edit_in_place::AttrsOwnerEdit},
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        edit_in_place::AttrsOwnerEdit,
+        HasName,
+    },
     display::function_declaration,
     AstNode, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, T,
 };
 use text_edit::TextEdit;
 
-use crate::{CompletionContext, CompletionItem, CompletionItemKind, Completions};
+use crate::{CompletionContext, CompletionItem, CompletionItemKind, Completions, TraitImplBody};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum ImplCompletionKind {
@@ -151,6 +156,7 @@ fn add_function_impl(
     item.lookup_by(fn_name).set_documentation(func.docs(ctx.db));
 
     if let Some(source) = ctx.sema.source(func) {
+        let indent = IndentLevel::from_node(source.value.syntax());
         let assoc_item = ast::AssocItem::Fn(source.value);
         if let Some(transformed_item) = get_transformed_assoc_item(ctx, assoc_item, impl_def) {
             let transformed_fn = match transformed_item {
@@ -159,12 +165,27 @@ fn add_function_impl(
             };
 
             let function_decl = function_declaration(&transformed_fn);
-            match ctx.config.snippet_cap {
-                Some(cap) => {
+            let item_indent = IndentLevel::from_node(fn_def_node);
+            let body = match ctx.config.trait_impl_body {
+                TraitImplBody::Skeleton => None,
+                TraitImplBody::CopyDefault => transformed_fn.body().map(|body| {
+                    let body = body.dedent(indent).indent(item_indent);
+                    ("default body".to_owned(), body.to_string())
+                }),
+                TraitImplBody::Delegate => {
+                    delegating_body(ctx, &transformed_fn, func, impl_def, item_indent)
+                }
+            };
+            match (body, ctx.config.snippet_cap) {
+                (Some((detail, body)), _) => {
+                    item.detail(detail)
+                        .text_edit(TextEdit::replace(range, format!("{} {}", function_decl, body)));
+                }
+                (None, Some(cap)) => {
                     let snippet = format!("{} {{\n    $0\n}}", function_decl);
                     item.snippet_edit(cap, TextEdit::replace(range, snippet));
                 }
-                None => {
+                (None, None) => {
                     let header = format!("{} {{", function_decl);
                     item.text_edit(TextEdit::replace(range, header));
                 }
@@ -174,6 +195,41 @@ fn add_function_impl(
     }
 }
 
+/// Builds a body calling the method on the first field of `Self` implementing the same trait,
+/// along with the completion detail describing it.
+fn delegating_body(
+    ctx: &CompletionContext,
+    transformed_fn: &ast::Fn,
+    func: hir::Function,
+    impl_def: hir::Impl,
+    indent: IndentLevel,
+) -> Option<(String, String)> {
+    let trait_ = impl_def.trait_(ctx.db)?;
+    // Only `Self` is a generic parameter, we can't name the trait's other arguments here.
+    if hir::GenericDef::from(trait_).type_params(ctx.db).len() != 1 {
+        return None;
+    }
+    func.self_param(ctx.db)?;
+    let (field, _) = impl_def
+        .self_ty(ctx.db)
+        .fields(ctx.db)
+        .into_iter()
+        .find(|(_, ty)| ty.impls_trait(ctx.db, trait_, &[]))?;
+    let args = transformed_fn
+        .param_list()?
+        .params()
+        .map(|param| match param.pat()? {
+            ast::Pat::IdentPat(pat) if pat.pat().is_none() => Some(pat.name()?.to_string()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let receiver = format!("self.{}", field.name(ctx.db));
+    let await_ = if transformed_fn.async_token().is_some() { ".await" } else { "" };
+    let call = format!("{}.{}({}){}", receiver, func.name(ctx.db), args.join(", "), await_);
+    let body = format!("{{\n{}    {}\n{}}}", indent, call, indent);
+    Some((format!("delegate to `{}`", receiver), body))
+}
+
 /// Transform a relevant associated item to inline generics from the impl, remove attrs and docs, etc.
 fn get_transformed_assoc_item(
     ctx: &CompletionContext,
@@ -285,13 +341,26 @@ fn replacement_range(ctx: &CompletionContext, item: &SyntaxNode) -> TextRange {
 mod tests {
     use expect_test::{expect, Expect};
 
-    use crate::tests::{check_edit, completion_list_no_kw};
+    use crate::{
+        tests::{check_edit, check_edit_with_config, completion_list_with_config, TEST_CONFIG},
+        CompletionConfig, TraitImplBody,
+    };
 
     fn check(ra_fixture: &str, expect: Expect) {
-        let actual = completion_list_no_kw(ra_fixture);
+        check_with_body(TraitImplBody::Skeleton, ra_fixture, expect)
+    }
+
+    fn check_with_body(body: TraitImplBody, ra_fixture: &str, expect: Expect) {
+        let config = CompletionConfig { trait_impl_body: body, ..TEST_CONFIG };
+        let actual = completion_list_with_config(config, ra_fixture, false);
         expect.assert_eq(&actual)
     }
 
+    fn check_edit_with_body(body: TraitImplBody, what: &str, before: &str, after: &str) {
+        let config = CompletionConfig { trait_impl_body: body, ..TEST_CONFIG };
+        check_edit_with_config(config, what, before, after)
+    }
+
     #[test]
     fn no_completion_inside_fn() {
         check(
@@ -987,4 +1056,203 @@ where Self: SomeTrait<u32> {
 "#,
         )
     }
+
+    #[test]
+    fn copies_default_body() {
+        check_edit_with_body(
+            TraitImplBody::CopyDefault,
+            "function",
+            r#"
+mod m {
+    pub struct Wrapper<T>(pub T);
+
+    pub trait Foo<T> {
+        fn function(&self, t: T) -> Wrapper<T> {
+            let wrapped = Wrapper(t);
+            wrapped
+        }
+    }
+}
+struct Bar;
+
+impl m::Foo<u32> for Bar {
+    fn f$0
+}
+"#,
+            r#"
+mod m {
+    pub struct Wrapper<T>(pub T);
+
+    pub trait Foo<T> {
+        fn function(&self, t: T) -> Wrapper<T> {
+            let wrapped = Wrapper(t);
+            wrapped
+        }
+    }
+}
+struct Bar;
+
+impl m::Foo<u32> for Bar {
+    fn function(&self, t: u32) -> m::Wrapper<u32> {
+        let wrapped = m::Wrapper(t);
+        wrapped
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn copy_default_body_without_default() {
+        check_edit_with_body(
+            TraitImplBody::CopyDefault,
+            "function",
+            r#"
+trait Foo {
+    fn function();
+}
+struct Bar;
+
+impl Foo for Bar {
+    fn f$0
+}
+"#,
+            r#"
+trait Foo {
+    fn function();
+}
+struct Bar;
+
+impl Foo for Bar {
+    fn function() {
+    $0
+}
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn delegates_to_field() {
+        check_edit_with_body(
+            TraitImplBody::Delegate,
+            "read",
+            r#"
+trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> usize;
+}
+struct File;
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> usize { 0 }
+}
+struct Buffered {
+    len: usize,
+    inner: File,
+}
+
+impl Read for Buffered {
+    fn r$0
+}
+"#,
+            r#"
+trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> usize;
+}
+struct File;
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> usize { 0 }
+}
+struct Buffered {
+    len: usize,
+    inner: File,
+}
+
+impl Read for Buffered {
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.inner.read(buf)
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn delegates_in_nested_impl() {
+        check_edit_with_body(
+            TraitImplBody::Delegate,
+            "len",
+            r#"
+trait Len {
+    fn len(&self) -> usize;
+}
+mod m {
+    struct Inner;
+    struct Wrapper(Inner);
+    impl super::Len for Inner {
+        fn len(&self) -> usize { 0 }
+    }
+    impl super::Len for Wrapper {
+        fn l$0
+    }
+}
+"#,
+            r#"
+trait Len {
+    fn len(&self) -> usize;
+}
+mod m {
+    struct Inner;
+    struct Wrapper(Inner);
+    impl super::Len for Inner {
+        fn len(&self) -> usize { 0 }
+    }
+    impl super::Len for Wrapper {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn body_mode_is_shown_in_detail() {
+        let fixture = r#"
+trait Foo {
+    fn provided(&self) {}
+    fn required(&self);
+    fn assoc();
+}
+struct Inner;
+impl Foo for Inner {
+    fn provided(&self) {}
+    fn required(&self) {}
+    fn assoc() {}
+}
+struct Outer(Inner);
+
+impl Foo for Outer {
+    fn $0
+}
+"#;
+        check_with_body(
+            TraitImplBody::CopyDefault,
+            fixture,
+            expect![[r#"
+                me fn provided(..) default body
+                me fn required(..)
+                fn fn assoc()
+            "#]],
+        );
+        check_with_body(
+            TraitImplBody::Delegate,
+            fixture,
+            expect![[r#"
+                me fn provided(..) delegate to `self.0`
+                me fn required(..) delegate to `self.0`
+                fn fn assoc()
+            "#]],
+        );
+    }
 }
@@ -14,11 +14,26 @@ pub struct CompletionConfig {
     pub enable_imports_on_the_fly: bool,
     pub enable_self_on_the_fly: bool,
     pub enable_private_editable: bool,
+    pub enable_term_search: bool,
     pub add_call_parenthesis: bool,
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
     pub snippets: Vec<Snippet>,
+    pub trait_impl_body: TraitImplBody,
+    /// Labels of the completions the user accepted recently, most recent first.
+    pub recently_accepted: Vec<String>,
+}
+
+/// What to put into the body of a trait method completed inside of an `impl Trait for Type` block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraitImplBody {
+    /// An empty body.
+    Skeleton,
+    /// The trait's default body, if it has one.
+    CopyDefault,
+    /// A call of the same method on a field of `Self` that implements the trait, if there is one.
+    Delegate,
 }
 
 impl CompletionConfig {
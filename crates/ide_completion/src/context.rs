@@ -9,7 +9,9 @@ use hir::{
 use ide_db::{
     active_parameter::ActiveParameter,
     base_db::{FilePosition, SourceDatabase},
+    defs::{Definition, NameRefClass},
     famous_defs::FamousDefs,
+    usage_frequency::UsageFrequencyDatabase,
     RootDatabase,
 };
 use rustc_hash::FxHashSet;
@@ -133,6 +135,8 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) impl_def: Option<ast::Impl>,
     /// The NameLike under the cursor in the original file if it exists.
     pub(super) name_syntax: Option<ast::NameLike>,
+    /// The definition the `NameRef` under the cursor already resolves to, if any.
+    pub(super) completed_def: Option<Definition>,
     pub(super) incomplete_let: bool,
 
     pub(super) completion_location: Option<ImmediateLocation>,
@@ -145,6 +149,9 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) path_context: Option<PathCompletionCtx>,
 
     pub(super) existing_derives: FxHashSet<hir::Macro>,
+    /// Whether the syntax context comes from a macro input fragment parsed on its own, so its
+    /// nodes can't be passed to `sema`.
+    pub(super) in_macro_fragment: bool,
 
     pub(super) locals: Vec<(Name, Local)>,
 }
@@ -331,6 +338,22 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// How often `def` is referred to in the current crate, not counting the reference being
+    /// completed.
+    pub(crate) fn usage_frequency(&self, def: impl Into<Definition>) -> u32 {
+        let krate = match self.krate {
+            Some(it) => it,
+            None => return 0,
+        };
+        let def = def.into();
+        let count = self.db.crate_usage_counts(krate.into()).get(&def).copied().unwrap_or(0);
+        if self.completed_def == Some(def) {
+            count.saturating_sub(1)
+        } else {
+            count
+        }
+    }
+
     /// A version of [`SemanticsScope::process_all_names`] that filters out `#[doc(hidden)]` items.
     pub(crate) fn process_all_names(&self, f: &mut dyn FnMut(Name, ScopeDef)) {
         let _p = profile::span("CompletionContext::process_all_names");
@@ -435,6 +458,7 @@ impl<'a> CompletionContext<'a> {
             function_def: None,
             impl_def: None,
             name_syntax: None,
+            completed_def: None,
             lifetime_ctx: None,
             pattern_ctx: None,
             completion_location: None,
@@ -445,6 +469,7 @@ impl<'a> CompletionContext<'a> {
             locals,
             incomplete_let: false,
             existing_derives: Default::default(),
+            in_macro_fragment: false,
         };
         ctx.expand_and_fill(
             original_file.syntax().clone(),
@@ -452,6 +477,12 @@ impl<'a> CompletionContext<'a> {
             offset,
             fake_ident_token,
         );
+        if let Some(ast::NameLike::NameRef(name_ref)) = &ctx.name_syntax {
+            if let Some(NameRefClass::Definition(def)) = NameRefClass::classify(&ctx.sema, name_ref)
+            {
+                ctx.completed_def = Some(def);
+            }
+        }
         Some(ctx)
     }
 
@@ -466,6 +497,7 @@ impl<'a> CompletionContext<'a> {
     ) {
         let _p = profile::span("CompletionContext::expand_and_fill");
         let mut derive_ctx = None;
+        let mut fn_like_calls = Vec::new();
 
         'expansion: loop {
             let parent_item =
@@ -569,13 +601,44 @@ impl<'a> CompletionContext<'a> {
                         if new_offset > actual_expansion.text_range().end() {
                             break;
                         }
+                        fn_like_calls.push((
+                            actual_macro_call,
+                            speculative_args,
+                            fake_ident_token,
+                            speculative_file,
+                            offset,
+                        ));
                         original_file = actual_expansion;
                         speculative_file = fake_expansion;
                         fake_ident_token = fake_mapped_token;
                         offset = new_offset;
                         continue;
                     }
-                    _ => break,
+                    _ => {
+                        // The call doesn't expand with the fake ident, or it swallows the ident
+                        // in a nested call like `stringify!`. Complete the matcher fragment the
+                        // cursor is in on its own instead, starting with the innermost call.
+                        fn_like_calls.push((
+                            actual_macro_call,
+                            speculative_args,
+                            fake_ident_token.clone(),
+                            speculative_file.clone(),
+                            offset,
+                        ));
+                        for (call, args, fake_ident, file, offset) in fn_like_calls.iter().rev() {
+                            let fragment = self.sema.speculative_macro_fragment(
+                                call,
+                                args,
+                                fake_ident.clone(),
+                            );
+                            if let Some((kind, range)) = fragment {
+                                if self.fill_macro_fragment(file, &kind, range, *offset) {
+                                    return;
+                                }
+                            }
+                        }
+                        break;
+                    }
                 }
             }
 
@@ -585,6 +648,77 @@ impl<'a> CompletionContext<'a> {
         self.fill(&original_file, speculative_file, offset, derive_ctx);
     }
 
+    /// Fills the context for the `kind` fragment of a macro input at `range`, by parsing it on
+    /// its own in a position where that kind of syntax is expected.
+    ///
+    /// The resulting trees are unknown to `Semantics`, so only syntactic information is taken from
+    /// them and paths are resolved in the scope of the macro call.
+    fn fill_macro_fragment(
+        &mut self,
+        speculative_file: &SyntaxNode,
+        kind: &str,
+        range: TextRange,
+        offset: TextSize,
+    ) -> bool {
+        let (prefix, suffix) = match kind {
+            "expr" | "ident" | "path" => ("fn __ra_fragment() { let _ = ", "; }"),
+            "ty" => ("type __RaFragment = ", ";"),
+            "pat" | "pat_param" => ("fn __ra_fragment() { match () { ", " => (), } }"),
+            "stmt" => ("fn __ra_fragment() { ", " }"),
+            "block" => ("fn __ra_fragment() ", ""),
+            "item" => ("", ""),
+            _ => return false,
+        };
+        let text_with_fake_ident = speculative_file.text().slice(range).to_string();
+        let marker = usize::from(offset - range.start());
+        let mut text = text_with_fake_ident.clone();
+        text.replace_range(marker..marker + COMPLETION_MARKER.len(), "");
+
+        let parse = |text: &str| {
+            ast::SourceFile::parse(&format!("{}{}{}", prefix, text, suffix)).tree().syntax().clone()
+        };
+        let original_file = parse(&text);
+        let file_with_fake_ident = parse(&text_with_fake_ident);
+        let offset = TextSize::of(prefix) + (offset - range.start());
+        let fake_ident_token = match file_with_fake_ident.token_at_offset(offset).right_biased() {
+            Some(it) => it,
+            None => return false,
+        };
+
+        self.previous_token = previous_token(NodeOrToken::Token(fake_ident_token));
+        (self.expected_type, self.expected_name) = self.expected_type_and_name();
+        self.impl_def = self
+            .sema
+            .token_ancestors_with_macros(self.token.clone())
+            .take_while(|it| it.kind() != SOURCE_FILE && it.kind() != MODULE)
+            .find_map(ast::Impl::cast);
+        self.function_def = self
+            .sema
+            .token_ancestors_with_macros(self.token.clone())
+            .take_while(|it| it.kind() != SOURCE_FILE && it.kind() != MODULE)
+            .find_map(ast::Fn::cast);
+
+        match find_node_at_offset(&file_with_fake_ident, offset) {
+            Some(ast::NameLike::Lifetime(lifetime)) => {
+                self.lifetime_ctx = Self::classify_lifetime(&self.sema, &original_file, lifetime);
+            }
+            Some(ast::NameLike::NameRef(name_ref)) => {
+                if let Some((path_ctx, pat_ctx)) =
+                    Self::classify_name_ref(&self.sema, &original_file, name_ref, Some(&self.scope))
+                {
+                    self.path_context = Some(path_ctx);
+                    self.pattern_ctx = pat_ctx;
+                }
+            }
+            Some(ast::NameLike::Name(name)) => {
+                self.pattern_ctx = Self::classify_name(&self.sema, &original_file, name);
+            }
+            None => return false,
+        }
+        self.in_macro_fragment = true;
+        true
+    }
+
     fn expected_type_and_name(&self) -> (Option<Type>, Option<NameOrNameRef>) {
         let mut node = match self.token.parent() {
             Some(it) => it,
@@ -767,7 +901,7 @@ impl<'a> CompletionContext<'a> {
                 self.name_syntax =
                     find_node_at_offset(&original_file, name_ref.syntax().text_range().start());
                 if let Some((path_ctx, _)) =
-                    Self::classify_name_ref(&self.sema, &original_file, name_ref)
+                    Self::classify_name_ref(&self.sema, &original_file, name_ref, None)
                 {
                     self.path_context =
                         Some(PathCompletionCtx { kind: Some(PathKind::Derive), ..path_ctx });
@@ -802,7 +936,7 @@ impl<'a> CompletionContext<'a> {
             }
             ast::NameLike::NameRef(name_ref) => {
                 if let Some((path_ctx, pat_ctx)) =
-                    Self::classify_name_ref(&self.sema, original_file, name_ref)
+                    Self::classify_name_ref(&self.sema, original_file, name_ref, None)
                 {
                     self.path_context = Some(path_ctx);
                     self.pattern_ctx = pat_ctx;
@@ -855,10 +989,13 @@ impl<'a> CompletionContext<'a> {
         Some(pattern_context_for(original_file, bind_pat.into()))
     }
 
+    /// `speculative_scope` is used to resolve the qualifier when `original_file` isn't known to
+    /// `sema`.
     fn classify_name_ref(
         sema: &Semantics<RootDatabase>,
         original_file: &SyntaxNode,
         name_ref: ast::NameRef,
+        speculative_scope: Option<&SemanticsScope>,
     ) -> Option<(PathCompletionCtx, Option<PatternContext>)> {
         let parent = name_ref.syntax().parent()?;
         let segment = ast::PathSegment::cast(parent)?;
@@ -935,7 +1072,10 @@ impl<'a> CompletionContext<'a> {
                 .and_then(|it| find_node_in_file(original_file, &it))
                 .map(|it| it.parent_path());
             path_ctx.qualifier = path.map(|path| {
-                let res = sema.resolve_path(&path);
+                let res = match speculative_scope {
+                    Some(scope) => scope.speculative_resolve(&path),
+                    None => sema.resolve_path(&path),
+                };
                 let is_super_chain = iter::successors(Some(path.clone()), |p| p.qualifier())
                     .all(|p| p.segment().and_then(|s| s.super_token()).is_some());
                 PathQualifierCtx { path, resolution: res, is_super_chain, use_tree_parent }
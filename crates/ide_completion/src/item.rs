@@ -152,6 +152,13 @@ pub struct CompletionRelevance {
     /// Basically, we want to guarantee that postfix snippets always takes
     /// precedence over everything else.
     pub exact_postfix_snippet_match: bool,
+    /// How often the name of the item is referred to in the current crate.
+    ///
+    /// Used to rank the items a project uses all the time, like its own
+    /// domain types, above rarely used ones.
+    pub usage_frequency: u32,
+    /// Set for items which the user recently accepted.
+    pub is_recently_accepted: bool,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -212,6 +219,14 @@ impl CompletionRelevance {
         if self.is_local {
             score += 1;
         }
+        score += match self.usage_frequency {
+            0 => 0,
+            1..=4 => 1,
+            _ => 2,
+        };
+        if self.is_recently_accepted {
+            score += 1;
+        }
         if self.exact_postfix_snippet_match {
             score += 100;
         }
@@ -348,6 +363,10 @@ impl CompletionItem {
         self.relevance
     }
 
+    pub(crate) fn relevance_mut(&mut self) -> &mut CompletionRelevance {
+        &mut self.relevance
+    }
+
     pub fn trigger_call_info(&self) -> bool {
         self.trigger_call_info
     }
@@ -27,7 +27,7 @@ use text_edit::TextEdit;
 use crate::{completions::Completions, context::CompletionContext};
 
 pub use crate::{
-    config::CompletionConfig,
+    config::{CompletionConfig, TraitImplBody},
     item::{CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit},
     snippet::{Snippet, SnippetScope},
 };
@@ -149,6 +149,7 @@ pub fn completions(
     completions::attribute::complete_derive(&mut acc, &ctx);
     completions::attribute::complete_known_attribute_input(&mut acc, &ctx);
     completions::dot::complete_dot(&mut acc, &ctx);
+    completions::expected_type::complete_expected_type_exprs(&mut acc, &ctx);
     completions::extern_abi::complete_extern_abi(&mut acc, &ctx);
     completions::flyimport::import_on_the_fly(&mut acc, &ctx);
     completions::fn_param::complete_fn_param(&mut acc, &ctx);
@@ -164,11 +165,14 @@ pub fn completions(
     completions::record::complete_record(&mut acc, &ctx);
     completions::snippet::complete_expr_snippet(&mut acc, &ctx);
     completions::snippet::complete_item_snippet(&mut acc, &ctx);
+    completions::term_search::complete_term_search(&mut acc, &ctx);
     completions::trait_impl::complete_trait_impl(&mut acc, &ctx);
     completions::unqualified_path::complete_unqualified_path(&mut acc, &ctx);
     completions::use_::complete_use_tree(&mut acc, &ctx);
     completions::vis::complete_vis(&mut acc, &ctx);
 
+    acc.mark_recently_accepted(&ctx);
+
     Some(acc)
 }
 
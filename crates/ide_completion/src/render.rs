@@ -12,7 +12,7 @@ pub(crate) mod struct_literal;
 mod builder_ext;
 
 use hir::{AsAssocItem, HasAttrs, HirDisplay, ScopeDef};
-use ide_db::{helpers::item_name, RootDatabase, SnippetCap, SymbolKind};
+use ide_db::{defs::Definition, helpers::item_name, RootDatabase, SnippetCap, SymbolKind};
 use syntax::{SmolStr, SyntaxKind, TextRange};
 
 use crate::{
@@ -48,8 +48,12 @@ impl<'a> RenderContext<'a> {
         self.completion.source_range()
     }
 
-    fn completion_relevance(&self) -> CompletionRelevance {
-        CompletionRelevance { is_private_editable: self.is_private_editable, ..Default::default() }
+    fn completion_relevance(&self, def: impl Into<Definition>) -> CompletionRelevance {
+        CompletionRelevance {
+            is_private_editable: self.is_private_editable,
+            usage_frequency: self.completion.usage_frequency(def),
+            ..Default::default()
+        }
     }
 
     fn is_deprecated(&self, def: impl HasAttrs) -> bool {
@@ -98,6 +102,7 @@ pub(crate) fn render_field(
     item.set_relevance(CompletionRelevance {
         type_match: compute_type_match(ctx.completion, ty),
         exact_name_match: compute_exact_name_match(ctx.completion, name.as_str()),
+        usage_frequency: ctx.completion.usage_frequency(field),
         ..CompletionRelevance::default()
     });
     item.detail(ty.display(ctx.db()).to_string())
@@ -228,7 +233,9 @@ fn render_resolution_(
         if let Some(ref_match) = compute_ref_match(ctx.completion, &ty) {
             item.ref_match(ref_match);
         }
-    };
+    } else if let ScopeDef::ModuleDef(def) = resolution {
+        item.set_relevance(ctx.completion_relevance(def));
+    }
 
     // Add `<>` for generic types
     let type_path_no_ty_args = matches!(
@@ -338,7 +345,7 @@ mod tests {
     use crate::{
         item::CompletionRelevanceTypeMatch,
         tests::{check_edit, do_completion, get_all_items, TEST_CONFIG},
-        CompletionItem, CompletionItemKind, CompletionRelevance,
+        CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance,
     };
 
     #[track_caller]
@@ -407,6 +414,8 @@ mod tests {
                 (relevance.is_local, "local"),
                 (relevance.exact_postfix_snippet_match, "snippet"),
                 (relevance.is_op_method, "op_method"),
+                (relevance.usage_frequency > 0, "usage"),
+                (relevance.is_recently_accepted, "recent"),
             ]
             .into_iter()
             .filter_map(|(cond, desc)| if cond { Some(desc) } else { None })
@@ -570,6 +579,16 @@ fn main() { let _: m::Spam = S$0 }
                         kind: SymbolKind(
                             Module,
                         ),
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: None,
+                            is_local: false,
+                            is_op_method: false,
+                            is_private_editable: false,
+                            exact_postfix_snippet_match: false,
+                            usage_frequency: 1,
+                            is_recently_accepted: false,
+                        },
                     },
                     CompletionItem {
                         label: "Spam::Bar(…)",
@@ -590,6 +609,8 @@ fn main() { let _: m::Spam = S$0 }
                             is_op_method: false,
                             is_private_editable: false,
                             exact_postfix_snippet_match: false,
+                            usage_frequency: 0,
+                            is_recently_accepted: false,
                         },
                         trigger_call_info: true,
                     },
@@ -612,6 +633,8 @@ fn main() { let _: m::Spam = S$0 }
                             is_op_method: false,
                             is_private_editable: false,
                             exact_postfix_snippet_match: false,
+                            usage_frequency: 0,
+                            is_recently_accepted: false,
                         },
                     },
                 ]
@@ -699,6 +722,8 @@ fn foo() { A { the$0 } }
                             is_op_method: false,
                             is_private_editable: false,
                             exact_postfix_snippet_match: false,
+                            usage_frequency: 0,
+                            is_recently_accepted: false,
                         },
                     },
                 ]
@@ -807,6 +832,16 @@ use self::E::*;
                         documentation: Documentation(
                             "enum docs",
                         ),
+                        relevance: CompletionRelevance {
+                            exact_name_match: false,
+                            type_match: None,
+                            is_local: false,
+                            is_op_method: false,
+                            is_private_editable: false,
+                            exact_postfix_snippet_match: false,
+                            usage_frequency: 1,
+                            is_recently_accepted: false,
+                        },
                     },
                 ]
             "#]],
@@ -1046,8 +1081,8 @@ fn go(world: &WorldSnapshot) { go(w$0) }
 "#,
             expect![[r#"
                 lc world [type+name+local]
-                st WorldSnapshot []
-                fn go(…) []
+                st WorldSnapshot [usage]
+                fn go(…) [usage]
             "#]],
         );
     }
@@ -1062,8 +1097,8 @@ fn f(foo: &Foo) { f(foo, w$0) }
 "#,
             expect![[r#"
                 lc foo [local]
-                st Foo []
-                fn f(…) []
+                st Foo [usage]
+                fn f(…) [usage]
             "#]],
         );
     }
@@ -1080,7 +1115,7 @@ fn f() { A { bar: b$0 }; }
             expect![[r#"
                 fn bar() [type+name]
                 fn baz() [type]
-                st A []
+                st A [usage]
                 fn f() []
             "#]],
         );
@@ -1143,9 +1178,9 @@ fn main() {
             expect![[r#"
                 lc s [name+local]
                 lc &mut s [type+name+local]
-                st S []
+                st S [usage]
+                fn foo(…) [usage]
                 fn main() []
-                fn foo(…) []
             "#]],
         );
         check_relevance(
@@ -1159,9 +1194,9 @@ fn main() {
             "#,
             expect![[r#"
                 lc s [type+name+local]
-                st S []
+                st S [usage]
+                fn foo(…) [usage]
                 fn main() []
-                fn foo(…) []
             "#]],
         );
         check_relevance(
@@ -1175,9 +1210,9 @@ fn main() {
             "#,
             expect![[r#"
                 lc ssss [type+local]
-                st S []
+                st S [usage]
+                fn foo(…) [usage]
                 fn main() []
-                fn foo(…) []
             "#]],
         );
     }
@@ -1211,11 +1246,11 @@ fn main() {
                 lc m [local]
                 lc t [local]
                 lc &t [type+local]
-                st T []
-                st S []
+                st T [usage]
+                st S [usage]
+                fn foo(…) [usage]
+                md core [usage]
                 fn main() []
-                fn foo(…) []
-                md core []
                 tt Sized []
             "#]],
         )
@@ -1256,11 +1291,11 @@ fn main() {
                 lc m [local]
                 lc t [local]
                 lc &mut t [type+local]
-                st T []
-                st S []
+                st T [usage]
+                st S [usage]
+                fn foo(…) [usage]
+                md core [usage]
                 fn main() []
-                fn foo(…) []
-                md core []
                 tt Sized []
             "#]],
         )
@@ -1297,8 +1332,8 @@ fn bar(t: Foo) {}
             expect![[r#"
                 ev Foo::A [type]
                 ev Foo::B [type]
-                en Foo []
-                fn bar(…) []
+                en Foo [usage]
+                fn bar(…) [usage]
                 fn foo() []
             "#]],
         );
@@ -1315,12 +1350,12 @@ fn foo() {
 fn bar(t: &Foo) {}
 "#,
             expect![[r#"
+                en Foo [usage]
+                fn bar(…) [usage]
                 ev Foo::A []
                 ev &Foo::A [type]
                 ev Foo::B []
                 ev &Foo::B [type]
-                en Foo []
-                fn bar(…) []
                 fn foo() []
             "#]],
         );
@@ -1350,13 +1385,13 @@ fn main() {
 }
 "#,
             expect![[r#"
-                st T []
-                st S []
+                st T [usage]
+                st S [usage]
+                fn foo(…) [usage]
+                md core [usage]
                 fn main() []
                 fn bar() []
                 fn &bar() [type]
-                fn foo(…) []
-                md core []
                 tt Sized []
             "#]],
         )
@@ -1461,10 +1496,10 @@ fn foo() {
 "#,
             expect![[r#"
                 lc foo [type+local]
+                ev Foo::B [type_could_unify+usage]
                 ev Foo::A(…) [type_could_unify]
-                ev Foo::B [type_could_unify]
+                en Foo [usage]
                 fn foo() []
-                en Foo []
                 fn baz() []
                 fn bar() []
             "#]],
@@ -1507,4 +1542,53 @@ fn main() {
             "#]],
         );
     }
+
+    #[test]
+    fn usage_frequency_relevance() {
+        check_relevance(
+            r#"
+struct Rare;
+struct Domain;
+impl Domain {
+    fn helper(&self) {}
+}
+fn helper() {}
+fn make(_: Domain) -> Domain { Domain }
+fn main() {
+    let _: Domain = make(Domain);
+    Domain.helper();
+    let _ = $0;
+}
+"#,
+            expect![[r#"
+                fn make(…) [type_could_unify+usage]
+                fn helper() [type_could_unify]
+                fn main() [type_could_unify]
+                st Domain [usage]
+                st Rare []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn recently_accepted_relevance() {
+        let mut actual = get_all_items(
+            CompletionConfig { recently_accepted: vec!["second()".to_string()], ..TEST_CONFIG },
+            r#"
+fn first() {}
+fn second() {}
+fn main() { s$0 }
+"#,
+        );
+        actual.retain(|it| it.kind() == CompletionItemKind::SymbolKind(SymbolKind::Function));
+        actual.sort_by_key(|it| cmp::Reverse(it.relevance().score()));
+        check_relevance_(
+            actual,
+            expect![[r#"
+                fn second() [recent]
+                fn main() []
+                fn first() []
+            "#]],
+        );
+    }
 }
@@ -66,7 +66,7 @@ fn render(
     let ty = variant.parent_enum(completion.db).ty(completion.db);
     item.set_relevance(CompletionRelevance {
         type_match: compute_type_match(completion, &ty),
-        ..ctx.completion_relevance()
+        ..ctx.completion_relevance(variant)
     });
 
     if let Some(ref_match) = compute_ref_match(completion, &ty) {
@@ -75,7 +75,7 @@ fn render(
         type_match: compute_type_match(completion, &ret_type),
         exact_name_match: compute_exact_name_match(completion, &call),
         is_op_method,
-        ..ctx.completion_relevance()
+        ..ctx.completion_relevance(func)
     });
 
     if let Some(ref_match) = compute_ref_match(completion, &ret_type) {
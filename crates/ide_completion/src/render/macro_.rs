@@ -50,7 +50,7 @@ fn render(
     item.set_deprecated(ctx.is_deprecated(macro_))
         .detail(macro_.display(completion.db).to_string())
         .set_documentation(docs)
-        .set_relevance(ctx.completion_relevance());
+        .set_relevance(ctx.completion_relevance(macro_));
 
     if let Some(import_to_add) = import_to_add {
         item.add_import(import_to_add);
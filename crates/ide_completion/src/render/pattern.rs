@@ -56,13 +56,13 @@ fn build_completion(
     ctx: RenderContext<'_>,
     name: SmolStr,
     pat: String,
-    def: impl HasAttrs + Copy,
+    def: impl HasAttrs + Into<hir::ModuleDef> + Copy,
 ) -> CompletionItem {
     let mut item = CompletionItem::new(CompletionItemKind::Binding, ctx.source_range(), name);
     item.set_documentation(ctx.docs(def))
         .set_deprecated(ctx.is_deprecated(def))
         .detail(&pat)
-        .set_relevance(ctx.completion_relevance());
+        .set_relevance(ctx.completion_relevance(def.into()));
     match ctx.snippet_cap() {
         Some(snippet_cap) => item.insert_snippet(snippet_cap, pat),
         None => item.insert_text(pat),
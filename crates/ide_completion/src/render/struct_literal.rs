@@ -30,11 +30,29 @@ pub(crate) fn render_struct_literal(
     Some(build_completion(ctx, name, literal, strukt))
 }
 
+pub(crate) fn render_variant_literal(
+    ctx: RenderContext<'_>,
+    variant: hir::Variant,
+    path: hir::ModPath,
+) -> Option<CompletionItem> {
+    let _p = profile::span("render_variant_literal");
+
+    // Tuple and unit variants are already covered by the regular variant completion.
+    if variant.kind(ctx.db()) != StructKind::Record {
+        return None;
+    }
+    let fields = variant.fields(ctx.db());
+    let name = path.to_string();
+    let literal = render_literal(&ctx, Some(path), &name, StructKind::Record, &fields)?;
+
+    Some(build_completion(ctx, name.into(), literal, variant))
+}
+
 fn build_completion(
     ctx: RenderContext<'_>,
     name: SmolStr,
     literal: String,
-    def: impl HasAttrs + Copy,
+    def: impl HasAttrs + Into<hir::ModuleDef> + Copy,
 ) -> CompletionItem {
     let mut item = CompletionItem::new(
         CompletionItemKind::Snippet,
@@ -44,7 +62,7 @@ fn build_completion(
     item.set_documentation(ctx.docs(def))
         .set_deprecated(ctx.is_deprecated(def))
         .detail(&literal)
-        .set_relevance(ctx.completion_relevance());
+        .set_relevance(ctx.completion_relevance(def.into()));
     match ctx.snippet_cap() {
         Some(snippet_cap) => item.insert_snippet(snippet_cap, literal),
         None => item.insert_text(literal),
@@ -40,7 +40,7 @@ fn render(
     item.set_documentation(ctx.docs(type_alias))
         .set_deprecated(ctx.is_deprecated(type_alias) || ctx.is_deprecated_assoc_item(type_alias))
         .detail(detail)
-        .set_relevance(ctx.completion_relevance());
+        .set_relevance(ctx.completion_relevance(type_alias));
 
     if let Some(actm) = type_alias.as_assoc_item(db) {
         if let Some(trt) = actm.containing_trait_or_trait_impl(db) {
@@ -2,7 +2,7 @@
 //!
 //! Actual logic is implemented in [`crate::completions::postfix`] and [`crate::completions::snippet`] respectively.
 
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 
 // Feature: User Snippet Completions
 //
@@ -24,6 +24,12 @@ use std::ops::Deref;
 //       "description": "Insert a thread::spawn call",
 //       "requires": "std::thread",
 //       "scope": "expr",
+//     },
+//     "ok_or": {
+//       "postfix": "ok_or",
+//       "body": "${receiver}.ok_or(${error:Error::new()})",
+//       "receiverType": "core::option::Option",
+//       "description": "Turn the option into a result",
 //     }
 //   }
 // }
@@ -48,6 +54,9 @@ use std::ops::Deref;
 // ** for Snippet-Scopes: `expr`, `item` (default: `item`)
 // ** for Postfix-Snippet-Scopes: `expr`, `type` (default: `expr`)
 //
+// * `receiverType` is an optional path to a type or trait that the receiver of a postfix snippet must have or implement.
+// References are looked through, so `core::option::Option` also matches `&Option<T>`.
+//
 // The `body` field also has access to placeholders as visible in the example as `$0`.
 // These placeholders take the form of `$number` or `${number:placeholder_text}` which can be traversed as tabstop in ascending order starting from 1,
 // with `$0` being a special case that always comes last.
@@ -55,6 +64,13 @@ use std::ops::Deref;
 // There is also a special placeholder, `${receiver}`, which will be replaced by the receiver expression for postfix snippets, or a `$0` tabstop in case of normal snippets.
 // This replacement for normal snippets allows you to reuse a snippet for both post- and prefix in a single definition.
 //
+// Placeholders may also be named, as in `${error:Error::new()}` or `$error`. Named placeholders are numbered after the
+// numbered ones in order of their first appearance. A tabstop or placeholder name that appears several times
+// becomes a single stop with several cursors, so all occurrences are edited at once.
+//
+// Snippets can also be shared with a project by listing JSON files containing snippet definitions in the same format
+// in `rust-analyzer.completion.snippetFiles`. Relative paths are resolved against the workspace root.
+//
 // For the VSCode editor, rust-analyzer also ships with a small set of defaults which can be removed
 // by overwriting the settings object mentioned above, the defaults are:
 // [source,json]
@@ -104,6 +120,7 @@ use std::ops::Deref;
 
 use ide_db::imports::{import_assets::LocatedImport, insert_use::ImportScope};
 use itertools::Itertools;
+use stdx::format_to;
 use syntax::{ast, AstNode, GreenNode, SyntaxNode};
 
 use crate::{context::CompletionContext, ImportEdit};
@@ -129,6 +146,7 @@ pub struct Snippet {
     // and reconstruct them on demand instead. This is cheaper than reparsing them
     // from strings
     requires: Box<[GreenNode]>,
+    receiver_type: Option<GreenNode>,
 }
 
 impl Snippet {
@@ -138,12 +156,17 @@ impl Snippet {
         snippet: &[String],
         description: &str,
         requires: &[String],
+        receiver_type: Option<&str>,
         scope: SnippetScope,
     ) -> Option<Self> {
         if prefix_triggers.is_empty() && postfix_triggers.is_empty() {
             return None;
         }
         let (requires, snippet, description) = validate_snippet(snippet, description, requires)?;
+        let receiver_type = match receiver_type {
+            Some(path) => Some(parse_path(path)?),
+            None => None,
+        };
         Some(Snippet {
             // Box::into doesn't work as that has a Copy bound 😒
             postfix_triggers: postfix_triggers.iter().map(Deref::deref).map(Into::into).collect(),
@@ -152,6 +175,7 @@ impl Snippet {
             snippet,
             description,
             requires,
+            receiver_type,
         })
     }
 
@@ -164,6 +188,29 @@ impl Snippet {
         import_edits(ctx, import_scope, &self.requires)
     }
 
+    /// Whether a postfix snippet applies to a receiver of the given type.
+    pub(crate) fn applies_to_receiver(&self, ctx: &CompletionContext, ty: &hir::Type) -> bool {
+        let constraint = match &self.receiver_type {
+            Some(it) => it,
+            None => return true,
+        };
+        let path = match ast::Path::cast(SyntaxNode::new_root(constraint.clone())) {
+            Some(it) => it,
+            None => return false,
+        };
+        let ty = ty.strip_references();
+        match ctx.scope.speculative_resolve(&path) {
+            Some(hir::PathResolution::Def(hir::ModuleDef::Adt(adt))) => ty.as_adt() == Some(adt),
+            Some(hir::PathResolution::Def(hir::ModuleDef::Trait(trait_))) => {
+                ty.impls_trait(ctx.db, trait_, &[])
+            }
+            Some(hir::PathResolution::Def(hir::ModuleDef::BuiltinType(builtin))) => {
+                ctx.module.map_or(false, |module| builtin.ty(ctx.db, module) == ty)
+            }
+            _ => false,
+        }
+    }
+
     pub fn snippet(&self) -> String {
         self.snippet.replace("${receiver}", "$0")
     }
@@ -208,20 +255,157 @@ fn validate_snippet(
 ) -> Option<(Box<[GreenNode]>, String, Option<Box<str>>)> {
     let mut imports = Vec::with_capacity(requires.len());
     for path in requires.iter() {
-        let use_path = ast::SourceFile::parse(&format!("use {};", path))
-            .syntax_node()
-            .descendants()
-            .find_map(ast::Path::cast)?;
-        if use_path.syntax().text() != path.as_str() {
-            return None;
-        }
-        let green = use_path.syntax().green().into_owned();
-        imports.push(green);
+        imports.push(parse_path(path)?);
     }
-    let snippet = snippet.iter().join("\n");
+    let snippet = number_named_placeholders(&snippet.iter().join("\n"));
     let description = (!description.is_empty())
         .then(|| description.split_once('\n').map_or(description, |(it, _)| it))
         .map(ToOwned::to_owned)
         .map(Into::into);
     Some((imports.into_boxed_slice(), snippet, description))
 }
+
+fn parse_path(path: &str) -> Option<GreenNode> {
+    let use_path = ast::SourceFile::parse(&format!("use {};", path))
+        .syntax_node()
+        .descendants()
+        .find_map(ast::Path::cast)?;
+    if use_path.syntax().text() != path {
+        return None;
+    }
+    Some(use_path.syntax().green().into_owned())
+}
+
+/// Turns `$name` and `${name:default}` placeholders into numbered ones, as LSP clients would
+/// otherwise treat them as (unknown) variables. `${receiver}` is left alone.
+fn number_named_placeholders(snippet: &str) -> String {
+    // The highest tabstop index used anywhere in the snippet, including nested placeholders.
+    let max_index = snippet
+        .match_indices('$')
+        .filter_map(|(idx, _)| {
+            let rest = snippet[idx + 1..].trim_start_matches('{');
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            rest[..digits].parse::<usize>().ok()
+        })
+        .max()
+        .unwrap_or(0);
+    let mut names = Vec::new();
+    number_placeholders_rec(snippet, max_index, &mut names)
+}
+
+fn number_placeholders_rec<'a>(
+    snippet: &'a str,
+    max_index: usize,
+    names: &mut Vec<&'a str>,
+) -> String {
+    let mut res = String::with_capacity(snippet.len());
+    let mut last = 0;
+    for (range, name, default) in placeholders(snippet) {
+        let is_named = name != "receiver" && name.parse::<usize>().is_err();
+        res.push_str(&snippet[last..range.start]);
+        last = range.end;
+        let index = if is_named {
+            let idx = match names.iter().position(|it| *it == name) {
+                Some(idx) => idx,
+                None => {
+                    names.push(name);
+                    names.len() - 1
+                }
+            };
+            (max_index + idx + 1).to_string()
+        } else {
+            name.to_owned()
+        };
+        match default {
+            Some(default) => format_to!(
+                res,
+                "${{{}:{}}}",
+                index,
+                number_placeholders_rec(default, max_index, names)
+            ),
+            None if name == "receiver" => res.push_str("${receiver}"),
+            None if is_named => format_to!(res, "${}", index),
+            None => res.push_str(&snippet[range]),
+        }
+    }
+    res.push_str(&snippet[last..]);
+    res
+}
+
+/// Yields the top-level placeholders of a snippet as `(range, name, default)`.
+fn placeholders(snippet: &str) -> impl Iterator<Item = (Range<usize>, &str, Option<&str>)> {
+    let bytes = snippet.as_bytes();
+    let is_name_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let start = pos;
+            match bytes[pos] {
+                b'\\' => pos += 2,
+                b'$' if bytes.get(pos + 1) == Some(&b'{') => {
+                    let name_start = pos + 2;
+                    let mut name_end = name_start;
+                    while name_end < bytes.len() && is_name_char(bytes[name_end]) {
+                        name_end += 1;
+                    }
+                    let mut depth = 1;
+                    let mut end = name_end;
+                    while end < bytes.len() && depth > 0 {
+                        match bytes[end] {
+                            b'\\' => end += 1,
+                            b'{' => depth += 1,
+                            b'}' => depth -= 1,
+                            _ => (),
+                        }
+                        end += 1;
+                    }
+                    pos = end;
+                    if depth != 0 || name_end == name_start {
+                        continue;
+                    }
+                    let name = &snippet[name_start..name_end];
+                    let default = match bytes[name_end] {
+                        b':' => Some(&snippet[name_end + 1..end - 1]),
+                        b'}' => None,
+                        // Choices and transformations are left alone.
+                        _ => continue,
+                    };
+                    return Some((start..end, name, default));
+                }
+                b'$' => {
+                    let mut end = pos + 1;
+                    while end < bytes.len() && is_name_char(bytes[end]) {
+                        end += 1;
+                    }
+                    pos = end.max(pos + 1);
+                    if end > start + 1 {
+                        return Some((start..end, &snippet[start + 1..end], None));
+                    }
+                }
+                _ => pos += 1,
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_named_placeholders() {
+        assert_eq!(
+            number_named_placeholders("${receiver}.ok_or(${err:Error::new()})"),
+            "${receiver}.ok_or(${1:Error::new()})"
+        );
+        assert_eq!(
+            number_named_placeholders("let ${name:x} = $1;\n$name$0"),
+            "let ${2:x} = $1;\n$2$0"
+        );
+        assert_eq!(
+            number_named_placeholders("${outer:f(${inner})} \\$literal ${1|a,b|}"),
+            "${2:f($3)} \\$literal ${1|a,b|}"
+        );
+    }
+}
@@ -21,6 +21,7 @@ mod type_pos;
 mod use_tree;
 mod visibility;
 mod flyimport;
+mod macro_fragment;
 
 use std::mem;
 
@@ -35,7 +36,7 @@ use stdx::{format_to, trim_indent};
 use syntax::{AstNode, NodeOrToken, SyntaxElement};
 use test_utils::assert_eq_text;
 
-use crate::{CompletionConfig, CompletionItem, CompletionItemKind};
+use crate::{CompletionConfig, CompletionItem, CompletionItemKind, TraitImplBody};
 
 /// Lots of basic item definitions
 const BASE_ITEMS_FIXTURE: &str = r#"
@@ -62,6 +63,7 @@ pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
     enable_imports_on_the_fly: true,
     enable_self_on_the_fly: true,
     enable_private_editable: true,
+    enable_term_search: false,
     add_call_parenthesis: true,
     add_call_argument_snippets: true,
     snippet_cap: SnippetCap::new(true),
@@ -73,6 +75,8 @@ pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
         skip_glob_imports: true,
     },
     snippets: Vec::new(),
+    trait_impl_body: TraitImplBody::Skeleton,
+    recently_accepted: Vec::new(),
 };
 
 pub(crate) fn completion_list(ra_fixture: &str) -> String {
@@ -83,7 +87,7 @@ pub(crate) fn completion_list_no_kw(ra_fixture: &str) -> String {
     completion_list_with_config(TEST_CONFIG, ra_fixture, false)
 }
 
-fn completion_list_with_config(
+pub(crate) fn completion_list_with_config(
     config: CompletionConfig,
     ra_fixture: &str,
     include_keywords: bool,
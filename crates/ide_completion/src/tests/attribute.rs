@@ -659,6 +659,20 @@ mod cfg {
             "#]],
         );
     }
+
+    #[test]
+    fn cfg_declared_features() {
+        check(
+            r#"
+//- /lib.rs crate:foo cfg:feature=std features:std,serde,nightly
+#[cfg(feature = $0"#,
+            expect![[r#"
+                ba serde   disabled
+                ba nightly disabled
+                ba std
+            "#]],
+        );
+    }
 }
 
 mod derive {
@@ -0,0 +1,119 @@
+//! Completion tests for the inputs of `macro_rules!` calls, guided by the matcher fragments.
+use expect_test::{expect, Expect};
+
+use crate::tests::completion_list_no_kw;
+
+fn check(ra_fixture: &str, expect: Expect) {
+    let actual = completion_list_no_kw(ra_fixture);
+    expect.assert_eq(&actual)
+}
+
+#[test]
+fn expr_fragment_of_incomplete_call() {
+    check(
+        r#"
+macro_rules! select {
+    ($e:expr => $body:block) => { match $e { _ => $body } };
+}
+struct Unit;
+fn main() {
+    let local = 1;
+    select!(loc$0);
+}
+"#,
+        expect![[r#"
+            lc local      i32
+            bt u32
+            st Unit
+            fn main()     fn()
+            ma select!(…) macro_rules! select
+        "#]],
+    );
+}
+
+#[test]
+fn ty_fragment_of_incomplete_call() {
+    check(
+        r#"
+macro_rules! decl {
+    ($name:ident : $t:ty = $e:expr) => { let $name: $t = $e; };
+}
+struct Unit;
+fn main() {
+    decl!(x: Un$0);
+}
+"#,
+        expect![[r#"
+            bt u32
+            st Unit
+            ma decl!(…) macro_rules! decl
+        "#]],
+    );
+}
+
+#[test]
+fn fragment_swallowed_by_nested_call() {
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! stringify {}
+macro_rules! name_of {
+    ($t:ty) => { stringify!($t) };
+}
+struct Unit;
+fn main() {
+    let local = 1;
+    name_of!(Un$0);
+}
+"#,
+        expect![[r#"
+            bt u32
+            st Unit
+            ma stringify!(…) macro_rules! stringify
+            ma name_of!(…)   macro_rules! name_of
+        "#]],
+    );
+}
+
+#[test]
+fn pat_fragment() {
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! stringify {}
+macro_rules! pat_name {
+    ($p:pat) => { stringify!($p) };
+}
+enum Enum { Variant }
+fn main() {
+    let local = 1;
+    pat_name!(Enum::$0);
+}
+"#,
+        expect![[r#"
+            ev Variant ()
+        "#]],
+    );
+}
+
+#[test]
+fn fragment_dropped_by_expansion() {
+    check(
+        r#"
+macro_rules! ignore {
+    ($e:expr) => {};
+}
+struct Unit;
+fn main(param: u32) {
+    ignore!(par$0);
+}
+"#,
+        expect![[r#"
+            bt u32
+            lc param      u32
+            st Unit
+            fn main(…)    fn(u32)
+            ma ignore!(…) macro_rules! ignore
+        "#]],
+    );
+}
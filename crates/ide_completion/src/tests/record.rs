@@ -155,6 +155,8 @@ fn main() {
 }
 "#,
         expect![[r#"
+            sn Foo::default()       Foo
+            sn Default::default()   Foo
             kw unsafe
             kw match
             kw while
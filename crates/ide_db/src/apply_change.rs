@@ -145,6 +145,10 @@ impl RootDatabase {
             // LineIndexDatabase
             crate::LineIndexQuery
 
+            // UsageFrequencyDatabase
+            crate::usage_frequency::FileUsageCountsQuery
+            crate::usage_frequency::CrateUsageCountsQuery
+
             // InternDatabase
             hir::db::InternFunctionQuery
             hir::db::InternStructQuery
@@ -0,0 +1,378 @@
+//! Rewrites the parameter list of a function together with everything that has to agree with
+//! it: the other functions of a trait method family, and the argument lists of all call sites.
+//!
+//! Edits are computed textually. Call sites can nest (`f(f(a, b), c)`) and renamed parameters
+//! can be passed along to recursive calls, so edits contained in an argument which is moved
+//! elsewhere are applied to the argument's text before the argument itself is moved.
+
+use hir::{AsAssocItem, AssocItem, HasSource, Semantics};
+use rustc_hash::FxHashMap;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, HasArgList, HasAttrs},
+    AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, T,
+};
+use text_edit::TextEdit;
+
+use crate::{
+    base_db::{FileId, SourceDatabaseExt},
+    defs::Definition,
+    search::FileReference,
+    source_change::SourceChange,
+    LineIndexDatabase, RootDatabase,
+};
+
+/// The new parameter list of a function, not counting `self`, which always stays in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureChange {
+    pub params: Vec<ParamChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamChange {
+    /// Keeps the parameter at `index` in the original parameter list, not counting `self`,
+    /// optionally renaming it or changing its type.
+    Existing { index: usize, name: Option<String>, ty: Option<String> },
+    /// Adds a parameter, with callers passing `default` for it.
+    Added { name: String, ty: String, default: String },
+}
+
+/// Applies `change` to `func`, the other functions of its trait method family if it is one,
+/// and all calls of them.
+pub fn change_signature(
+    sema: &Semantics<RootDatabase>,
+    func: hir::Function,
+    change: &SignatureChange,
+) -> Result<SourceChange, String> {
+    let db = sema.db;
+    let family = method_family(db, func);
+
+    let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+    let mut call_sites = Vec::new();
+    let mut unsupported = Vec::new();
+    for &func in &family {
+        let source = func.source(db).ok_or("function has no source")?;
+        if source.file_id.is_macro() {
+            return Err(
+                "changing the signature of functions defined by macros is not supported".to_owned()
+            );
+        }
+        let file_id = source.file_id.original_file(db);
+        let params = source.value.param_list().ok_or("function has no parameter list")?;
+        validate(change, params.params().count())?;
+        check_removed_params(sema, func, &params, change)?;
+        let file_edits = edits.entry(file_id).or_default();
+        rename_uses(sema, &params, change, file_edits);
+        file_edits.push((params.syntax().text_range(), render_param_list(&params, change)));
+
+        let has_self = params.self_param().is_some();
+        for (file_id, references) in Definition::Function(func).usages(sema).all() {
+            let source_file = sema.parse(file_id);
+            for reference in references {
+                let range = reference.range;
+                match call_site(&source_file, reference, has_self) {
+                    Ok(Some(call)) => call_sites.push((file_id, call)),
+                    Ok(None) => (),
+                    Err(()) => unsupported.push((file_id, range)),
+                }
+            }
+        }
+    }
+    if !unsupported.is_empty() {
+        let usages: Vec<_> =
+            unsupported.into_iter().map(|(file_id, range)| location(db, file_id, range)).collect();
+        return Err(format!(
+            "can't update the usages which aren't calls or are in macro calls, at {}",
+            usages.join(", ")
+        ));
+    }
+
+    // Innermost calls first, so that outer calls can pick up their edits.
+    call_sites.sort_by_key(|(_, call)| call.arg_list.syntax().text_range().len());
+    for (file_id, call) in call_sites {
+        let file_edits = edits.entry(file_id).or_default();
+        if let Some(arg_list) = render_arg_list(&call, change, file_edits) {
+            file_edits.push((call.arg_list.syntax().text_range(), arg_list));
+        }
+    }
+
+    let source_file_edits = edits
+        .into_iter()
+        .map(|(file_id, mut file_edits)| {
+            file_edits.sort_by_key(|(range, _)| range.start());
+            let mut builder = TextEdit::builder();
+            for (range, text) in file_edits {
+                builder.replace(range, text);
+            }
+            (file_id, builder.finish())
+        })
+        .collect::<FxHashMap<_, _>>();
+    Ok(SourceChange::from(source_file_edits))
+}
+
+/// The functions whose signatures have to agree with `func`: for trait methods, the method in
+/// the trait and its implementations in all impls.
+fn method_family(db: &RootDatabase, func: hir::Function) -> Vec<hir::Function> {
+    let trait_ = match func.as_assoc_item(db).and_then(|it| it.containing_trait_or_trait_impl(db)) {
+        Some(it) => it,
+        None => return vec![func],
+    };
+    let name = func.name(db);
+    let same_name = |item: AssocItem| match item {
+        AssocItem::Function(it) if it.name(db) == name => Some(it),
+        _ => None,
+    };
+    trait_
+        .items(db)
+        .into_iter()
+        .chain(hir::Impl::all_for_trait(db, trait_).into_iter().flat_map(|it| it.items(db)))
+        .filter_map(same_name)
+        .collect()
+}
+
+fn validate(change: &SignatureChange, param_count: usize) -> Result<(), String> {
+    let mut seen = vec![false; param_count];
+    for param in &change.params {
+        if let ParamChange::Existing { index, .. } = *param {
+            match seen.get_mut(index) {
+                Some(true) => return Err(format!("parameter {} is used more than once", index)),
+                Some(it) => *it = true,
+                None => return Err(format!("there is no parameter {}", index)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fails if a removed parameter is still used in the body of `func`.
+fn check_removed_params(
+    sema: &Semantics<RootDatabase>,
+    func: hir::Function,
+    params: &ast::ParamList,
+    change: &SignatureChange,
+) -> Result<(), String> {
+    let kept: Vec<_> = change
+        .params
+        .iter()
+        .filter_map(|it| match it {
+            ParamChange::Existing { index, .. } => Some(*index),
+            ParamChange::Added { .. } => None,
+        })
+        .collect();
+    let removed = params.params().enumerate().filter(|(index, _)| !kept.contains(index));
+    for (_, param) in removed {
+        let bindings = param.pat().into_iter().flat_map(|it| it.syntax().descendants());
+        for pat in bindings.filter_map(ast::IdentPat::cast) {
+            let local = match sema.to_def(&pat) {
+                Some(it) => it,
+                None => continue,
+            };
+            if Definition::Local(local).usages(sema).at_least_one() {
+                return Err(format!(
+                    "parameter `{}` is still used in `{}`",
+                    local.name(sema.db),
+                    func.name(sema.db)
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn render_param_list(params: &ast::ParamList, change: &SignatureChange) -> String {
+    let old_params: Vec<_> = params.params().collect();
+    let self_param = params.self_param().map(|it| it.syntax().to_string());
+    let new_params = change.params.iter().map(|param| match param {
+        ParamChange::Existing { index, name: None, ty: None } => old_params[*index].to_string(),
+        ParamChange::Existing { index, name, ty } => {
+            let old = &old_params[*index];
+            let attrs = old.attrs().map(|it| format!("{} ", it)).collect::<String>();
+            let name = name.clone().or_else(|| old.pat().map(|it| it.to_string()));
+            let ty = ty.clone().or_else(|| old.ty().map(|it| it.to_string()));
+            format!(
+                "{}{}: {}",
+                attrs,
+                name.unwrap_or_else(|| "_".to_owned()),
+                ty.unwrap_or_default()
+            )
+        }
+        ParamChange::Added { name, ty, .. } => format!("{}: {}", name, ty),
+    });
+    render_list(params.syntax(), self_param.into_iter().chain(new_params).collect())
+}
+
+/// Renders a parenthesized list, laid out like `list`: on one line, or with one item per line
+/// and the indentation and trailing comma of the original.
+fn render_list(list: &SyntaxNode, items: Vec<String>) -> String {
+    let tokens: Vec<_> = list.children_with_tokens().filter_map(|it| it.into_token()).collect();
+    let indent_after_newline = |token: Option<&SyntaxToken>| {
+        let text = token.filter(|it| it.kind() == SyntaxKind::WHITESPACE)?.text();
+        text.rfind('\n').map(|pos| text[pos + 1..].to_owned())
+    };
+    let item_indent =
+        tokens.iter().position(|it| it.kind() == T!['(']).and_then(|i| tokens.get(i + 1));
+    let item_indent = match indent_after_newline(item_indent) {
+        Some(it) if !items.is_empty() => it,
+        _ => return format!("({})", items.join(", ")),
+    };
+    let close = tokens.iter().rposition(|it| it.kind() == T![')']);
+    let close_indent =
+        close.and_then(|i| indent_after_newline(i.checked_sub(1).and_then(|i| tokens.get(i))));
+    let trailing_comma = list
+        .children_with_tokens()
+        .filter(|it| !it.kind().is_trivia() && it.kind() != T![')'])
+        .last()
+        .map_or(false, |it| it.kind() == T![,]);
+    let mut res = String::from("(");
+    for (i, item) in items.iter().enumerate() {
+        let comma = if i + 1 < items.len() || trailing_comma { "," } else { "" };
+        res.push_str(&format!("\n{}{}{}", item_indent, item, comma));
+    }
+    res.push_str(&format!("\n{})", close_indent.unwrap_or_default()));
+    res
+}
+
+/// Renames the uses of the renamed parameters in the body of the function.
+fn rename_uses(
+    sema: &Semantics<RootDatabase>,
+    params: &ast::ParamList,
+    change: &SignatureChange,
+    edits: &mut Vec<(TextRange, String)>,
+) {
+    let old_params: Vec<_> = params.params().collect();
+    for param in &change.params {
+        let (index, new_name) = match param {
+            ParamChange::Existing { index, name: Some(name), .. } => (*index, name),
+            _ => continue,
+        };
+        let local = match old_params[index].pat() {
+            Some(ast::Pat::IdentPat(pat)) => sema.to_def(&pat),
+            _ => None,
+        };
+        let local = match local {
+            Some(it) => it,
+            None => continue,
+        };
+        for (_, references) in Definition::Local(local).usages(sema).all() {
+            for reference in references {
+                let name_ref = match reference.name {
+                    ast::NameLike::NameRef(it) => it,
+                    _ => continue,
+                };
+                let shorthand_field = ast::RecordExprField::for_field_name(&name_ref)
+                    .map_or(false, |field| field.name_ref().is_none());
+                let text = if shorthand_field {
+                    format!("{}: {}", name_ref, new_name)
+                } else {
+                    new_name.clone()
+                };
+                edits.push((reference.range, text));
+            }
+        }
+    }
+}
+
+struct CallSite {
+    arg_list: ast::ArgList,
+    /// Whether the first argument is the receiver of a method called with path syntax.
+    self_arg: bool,
+}
+
+/// The call `reference` is the callee of, `None` for imports, or `Err` for usages which can't be
+/// updated, like function references and calls in macros.
+fn call_site(
+    source_file: &ast::SourceFile,
+    FileReference { range, .. }: FileReference,
+    has_self: bool,
+) -> Result<Option<CallSite>, ()> {
+    let name_ref: ast::NameRef = match find_node_at_range(source_file.syntax(), range) {
+        Some(it) => it,
+        // Macro calls only have tokens, which we can't tell calls from.
+        None => {
+            let in_macro_call = source_file
+                .syntax()
+                .covering_element(range)
+                .ancestors()
+                .any(|it| ast::TokenTree::can_cast(it.kind()));
+            return if in_macro_call { Err(()) } else { Ok(None) };
+        }
+    };
+    if name_ref.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind())) {
+        return Ok(None);
+    }
+    if let Some(method_call) = name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        let arg_list = method_call.arg_list().ok_or(())?;
+        return Ok(Some(CallSite { arg_list, self_arg: false }));
+    }
+    let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast).ok_or(())?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast).ok_or(())?;
+    if call.expr().as_ref().map(|it| it.syntax()) != Some(path_expr.syntax()) {
+        return Err(());
+    }
+    Ok(Some(CallSite { arg_list: call.arg_list().ok_or(())?, self_arg: has_self }))
+}
+
+/// Renders the position of `range` as `path:line:column`.
+fn location(db: &RootDatabase, file_id: FileId, range: TextRange) -> String {
+    let source_root = db.source_root(db.file_source_root(file_id));
+    let path = source_root.path_for_file(&file_id).map(|it| it.to_string()).unwrap_or_default();
+    let line_col = db.line_index(file_id).line_col(range.start());
+    format!("{}:{}:{}", path, line_col.line + 1, line_col.col + 1)
+}
+
+fn render_arg_list(
+    call: &CallSite,
+    change: &SignatureChange,
+    edits: &mut Vec<(TextRange, String)>,
+) -> Option<String> {
+    let mut args = call.arg_list.args();
+    let self_arg = if call.self_arg { Some(args.next()?) } else { None };
+    let old_args: Vec<_> = args.collect();
+    let expected = change
+        .params
+        .iter()
+        .filter_map(|it| match it {
+            ParamChange::Existing { index, .. } => Some(index + 1),
+            ParamChange::Added { .. } => None,
+        })
+        .max()
+        .unwrap_or(0);
+    // Leave calls that don't type check alone rather than guessing.
+    if old_args.len() < expected {
+        return None;
+    }
+
+    let mut render = |arg: &ast::Expr| render_with_edits(arg.syntax(), edits);
+    let self_arg = self_arg.map(|it| render(&it));
+    let new_args = change.params.iter().map(|param| match param {
+        ParamChange::Existing { index, .. } => render(&old_args[*index]),
+        ParamChange::Added { default, .. } => default.clone(),
+    });
+    let new_args: Vec<_> = self_arg.into_iter().chain(new_args).collect();
+    // Arguments of removed parameters are dropped, so are the edits inside of them.
+    let range = call.arg_list.syntax().text_range();
+    edits.retain(|(it, _)| !range.contains_range(*it));
+    Some(render_list(call.arg_list.syntax(), new_args))
+}
+
+/// The text of `node` with the `edits` inside of it applied. The applied edits are removed.
+fn render_with_edits(node: &syntax::SyntaxNode, edits: &mut Vec<(TextRange, String)>) -> String {
+    let range = node.text_range();
+    let mut inner: Vec<_> =
+        edits.iter().filter(|(it, _)| range.contains_range(*it)).cloned().collect();
+    edits.retain(|(it, _)| !range.contains_range(*it));
+    inner.sort_by_key(|(it, _)| it.start());
+
+    let text = node.to_string();
+    let mut res = String::new();
+    let mut pos = range.start();
+    for (edit_range, replacement) in inner {
+        res.push_str(
+            &text[TextRange::new(pos - range.start(), edit_range.start() - range.start())],
+        );
+        res.push_str(&replacement);
+        pos = edit_range.end();
+    }
+    res.push_str(&text[TextRange::new(pos - range.start(), range.len())]);
+    res
+}
@@ -6,6 +6,7 @@ mod apply_change;
 
 pub mod active_parameter;
 pub mod assists;
+pub mod change_signature;
 pub mod defs;
 pub mod famous_defs;
 pub mod helpers;
@@ -18,8 +19,10 @@ pub mod rust_doc;
 pub mod search;
 pub mod source_change;
 pub mod symbol_index;
+pub mod term_search;
 pub mod traits;
 pub mod ty_filter;
+pub mod usage_frequency;
 
 pub mod imports {
     pub mod import_assets;
@@ -68,7 +71,8 @@ pub type FxIndexMap<K, V> =
     hir::db::HirDatabaseStorage,
     hir::db::InternDatabaseStorage,
     LineIndexDatabaseStorage,
-    symbol_index::SymbolsDatabaseStorage
+    symbol_index::SymbolsDatabaseStorage,
+    usage_frequency::UsageFrequencyDatabaseStorage
 )]
 pub struct RootDatabase {
     // We use `ManuallyDrop` here because every codegen unit that contains a
@@ -108,6 +112,12 @@ impl Upcast<dyn HirDatabase> for RootDatabase {
     }
 }
 
+impl Upcast<RootDatabase> for RootDatabase {
+    fn upcast(&self) -> &RootDatabase {
+        self
+    }
+}
+
 impl FileLoader for RootDatabase {
     fn file_text(&self, file_id: FileId) -> Arc<String> {
         FileLoaderDelegate(self).file_text(file_id)
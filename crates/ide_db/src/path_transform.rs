@@ -68,6 +68,20 @@ impl<'a> PathTransform<'a> {
         }
     }
 
+    pub fn type_alias(
+        target_scope: &'a SemanticsScope<'a>,
+        source_scope: &'a SemanticsScope<'a>,
+        alias: hir::TypeAlias,
+        generic_arg_list: Option<ast::GenericArgList>,
+    ) -> PathTransform<'a> {
+        PathTransform {
+            source_scope,
+            target_scope,
+            generic_def: alias.into(),
+            substs: generic_arg_list.and_then(get_type_args_from_arg_list).unwrap_or_default(),
+        }
+    }
+
     pub fn apply(&self, syntax: &SyntaxNode) {
         if let Some(ctx) = self.build_ctx() {
             ctx.apply(syntax)
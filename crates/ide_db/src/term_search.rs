@@ -0,0 +1,49 @@
+//! Runs `hir`'s term search over the names in scope and the items of the crate graph whose
+//! names resemble the goal type's, found with the [`items_locator`](crate::items_locator).
+
+use hir::{
+    term_search::{self, Expr, TermSearchConfig},
+    ModuleDef, Semantics, SemanticsScope, Type,
+};
+
+use crate::{
+    imports::import_assets::NameToImport,
+    items_locator::{self, AssocItemSearch, DEFAULT_QUERY_SEARCH_LIMIT},
+    RootDatabase,
+};
+
+/// Finds expressions of type `goal` that can be written at `scope`. Simpler expressions come
+/// first.
+pub fn term_search(
+    sema: &Semantics<RootDatabase>,
+    scope: &SemanticsScope,
+    goal: &Type,
+    config: &TermSearchConfig,
+) -> Vec<Expr> {
+    let items = match (scope.krate(), goal.as_adt()) {
+        // Items making a `Config` tend to be called `make_config`, `DEFAULT_CONFIG`, etc.
+        (Some(krate), Some(adt)) => {
+            let name = adt.name(sema.db).to_smol_str().to_lowercase();
+            items_locator::items_with_name(
+                sema,
+                krate,
+                NameToImport::Fuzzy(name),
+                AssocItemSearch::Exclude,
+                Some(DEFAULT_QUERY_SEARCH_LIMIT.inner()),
+            )
+            .filter_map(|item| item.as_module_def())
+            .filter(|def| {
+                matches!(
+                    def,
+                    ModuleDef::Function(_)
+                        | ModuleDef::Const(_)
+                        | ModuleDef::Static(_)
+                        | ModuleDef::Adt(_)
+                )
+            })
+            .collect()
+        }
+        _ => Vec::new(),
+    };
+    term_search::term_search(scope, goal, &items, config)
+}
@@ -0,0 +1,129 @@
+//! Usage statistics of the definitions referred to by a crate, used to rank completions.
+//!
+//! Every `NameRef` of the crate is resolved to its definition, so items which merely share a
+//! name aren't mixed up. Resolving a whole crate isn't cheap, so the counts are kept per file
+//! and summed up per crate in salsa queries: a change which doesn't alter what a file refers
+//! to leaves the crate totals alone.
+
+use std::sync::Arc;
+
+use base_db::{salsa, CrateId, FileId, SourceDatabase, Upcast};
+use hir::{Crate, Semantics};
+use rustc_hash::FxHashMap;
+use syntax::{ast, AstNode};
+
+use crate::{
+    defs::{Definition, NameRefClass},
+    RootDatabase,
+};
+
+/// Number of references to each definition.
+pub type UsageCounts = FxHashMap<Definition, u32>;
+
+#[salsa::query_group(UsageFrequencyDatabaseStorage)]
+pub trait UsageFrequencyDatabase: SourceDatabase + Upcast<RootDatabase> {
+    /// Number of references to each item and field in the given file.
+    fn file_usage_counts(&self, file_id: FileId) -> Arc<UsageCounts>;
+
+    /// Number of references to each item and field in the modules of the given crate.
+    fn crate_usage_counts(&self, krate: CrateId) -> Arc<UsageCounts>;
+}
+
+fn file_usage_counts(db: &dyn UsageFrequencyDatabase, file_id: FileId) -> Arc<UsageCounts> {
+    let sema = Semantics::new(db.upcast());
+    let mut counts = FxHashMap::default();
+    let file = sema.parse(file_id);
+    for name_ref in file.syntax().descendants().filter_map(ast::NameRef::cast) {
+        let def = match NameRefClass::classify(&sema, &name_ref) {
+            Some(NameRefClass::Definition(def)) => def,
+            Some(NameRefClass::FieldShorthand { field_ref, .. }) => Definition::Field(field_ref),
+            None => continue,
+        };
+        // Locals and generic params are only used close to their definition, counting them
+        // wouldn't tell anything.
+        if matches!(
+            def,
+            Definition::Module(_)
+                | Definition::Function(_)
+                | Definition::Adt(_)
+                | Definition::Variant(_)
+                | Definition::Const(_)
+                | Definition::Static(_)
+                | Definition::Trait(_)
+                | Definition::TypeAlias(_)
+                | Definition::Macro(_)
+                | Definition::Field(_)
+        ) {
+            *counts.entry(def).or_default() += 1;
+        }
+    }
+    Arc::new(counts)
+}
+
+fn crate_usage_counts(db: &dyn UsageFrequencyDatabase, krate: CrateId) -> Arc<UsageCounts> {
+    let _p = profile::span("crate_usage_counts");
+    let db: &RootDatabase = db.upcast();
+    let mut files: Vec<_> = Crate::from(krate)
+        .modules(db)
+        .into_iter()
+        .map(|module| module.definition_source(db).file_id.original_file(db))
+        .collect();
+    files.sort_unstable_by_key(|it| it.0);
+    files.dedup();
+    let mut counts = FxHashMap::default();
+    for file_id in files {
+        for (&def, count) in db.file_usage_counts(file_id).iter() {
+            *counts.entry(def).or_default() += count;
+        }
+    }
+    Arc::new(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::{fixture::WithFixture, FileLoader};
+    use expect_test::expect;
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn counts_resolved_references_across_crate_files() {
+        let (db, files) = RootDatabase::with_many_files(
+            r#"
+//- /main.rs
+mod other;
+struct Foo { field: u32 }
+fn make() -> Foo { Foo { field: 0 } }
+fn main() {
+    let make = make();
+    let _: Foo = other::make();
+    let _ = make.field;
+}
+//- /other.rs
+use crate::Foo;
+pub fn make() -> Foo { Foo { field: 1 } }
+"#,
+        );
+        let krate = db.relevant_crates(files[0]).iter().next().copied().unwrap();
+        let counts = db
+            .crate_usage_counts(krate)
+            .iter()
+            .map(|(def, count)| {
+                let name =
+                    |it: Option<hir::Name>| it.map_or("crate".to_string(), |it| it.to_string());
+                let module = name(def.module(&db).and_then(|it| it.name(&db)));
+                format!("{}::{} {}", module, name(def.name(&db)), count)
+            })
+            .sorted()
+            .join("\n");
+        expect![[r#"
+            crate::Foo 6
+            crate::crate 1
+            crate::field 3
+            crate::make 1
+            crate::other 1
+            other::make 1"#]]
+        .assert_eq(&counts);
+    }
+}
@@ -0,0 +1,123 @@
+use ide_db::base_db::CrateOrigin;
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, T};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: unknown-feature
+//
+// This diagnostic is shown for `#[cfg(feature = "...")]` predicates naming a feature that the
+// package doesn't declare in its `Cargo.toml`.
+pub(crate) fn unknown_feature(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    krate: hir::Crate,
+    root: &SyntaxNode,
+) -> Option<()> {
+    // Only cargo tells us which features are declared.
+    if !matches!(krate.origin(ctx.sema.db), CrateOrigin::CratesIo { .. }) {
+        return None;
+    }
+    let declared = krate.potential_cfg(ctx.sema.db);
+    let declared = declared.get_cfg_values("feature").collect_vec();
+
+    for attr in root.descendants().filter_map(ast::Attr::cast) {
+        let (name, tt) = match attr.as_simple_call() {
+            Some(it) => it,
+            None => continue,
+        };
+        let tokens = tt
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| !it.kind().is_trivia());
+        let predicate: Vec<SyntaxToken> = match &*name {
+            "cfg" => tokens.collect(),
+            // Only the first argument of `cfg_attr` is a predicate, the rest are attributes.
+            "cfg_attr" => tokens
+                .take_while(|it| it.kind() != T![,] || it.parent().as_ref() != Some(tt.syntax()))
+                .collect(),
+            _ => continue,
+        };
+        for feature in referenced_features(&predicate) {
+            let value = feature.text().trim_matches('"');
+            if declared.iter().any(|it| *it == value) {
+                continue;
+            }
+            let message = if declared.is_empty() {
+                format!("unknown feature `{}`, the package declares no features", value)
+            } else {
+                format!(
+                    "unknown feature `{}`, expected one of {}",
+                    value,
+                    declared.iter().sorted().map(|it| format!("`{}`", it)).join(", ")
+                )
+            };
+            acc.push(
+                Diagnostic::new("unknown-feature", message, feature.text_range())
+                    .severity(Severity::WeakWarning),
+            );
+        }
+    }
+
+    Some(())
+}
+
+/// Finds the string literals of `feature = "..."` pairs in a flattened cfg predicate.
+fn referenced_features(tokens: &[SyntaxToken]) -> impl Iterator<Item = &SyntaxToken> {
+    tokens.windows(3).filter_map(|window| match window {
+        [key, eq, value]
+            if key.kind() == SyntaxKind::IDENT
+                && key.text() == "feature"
+                && eq.kind() == T![=]
+                && value.kind() == SyntaxKind::STRING =>
+        {
+            Some(value)
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn unknown_feature() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo@CratesIo:0.1.0,https://example.com/foo cfg:feature=std features:std,serde
+#[cfg(feature = "serde")]
+fn a() {}
+#[cfg(any(feature = "std", feature = "sred"))]
+                                   //^^^^^^ weak: unknown feature `sred`, expected one of `serde`, `std`
+fn b() {}
+#[cfg_attr(feature = "nightly", doc = "feature = \"x\"")]
+                   //^^^^^^^^^ weak: unknown feature `nightly`, expected one of `serde`, `std`
+fn c() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_declared_features() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo@CratesIo:0.1.0,https://example.com/foo
+#[cfg(feature = "std")]
+              //^^^^^ weak: unknown feature `std`, the package declares no features
+fn a() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_a_cargo_package() {
+        check_diagnostics(
+            r#"
+#[cfg(feature = "std")]
+fn a() {}
+"#,
+        );
+    }
+}
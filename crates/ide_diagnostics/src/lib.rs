@@ -50,6 +50,7 @@ mod handlers {
     pub(crate) mod field_shorthand;
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
+    pub(crate) mod unknown_feature;
 }
 
 #[cfg(test)]
@@ -185,7 +186,13 @@ pub fn diagnostics(
 
     let mut diags = Vec::new();
     if let Some(m) = module {
-        m.diagnostics(db, &mut diags)
+        m.diagnostics(db, &mut diags);
+        handlers::unknown_feature::unknown_feature(
+            &ctx,
+            &mut res,
+            m.krate(),
+            parse.tree().syntax(),
+        );
     }
 
     for diag in diags {
@@ -8,7 +8,10 @@ mod transcriber;
 use rustc_hash::FxHashMap;
 use syntax::SmolStr;
 
-use crate::{ExpandError, ExpandResult};
+use crate::{
+    parser::{MetaTemplate, Op},
+    ExpandError, ExpandResult,
+};
 
 pub(crate) fn expand_rules(
     rules: &[crate::Rule],
@@ -49,6 +52,72 @@ pub(crate) fn expand_rules(
     }
 }
 
+/// Finds the metavariable that binds `token` in the rule matching `input` best, returning its
+/// fragment kind and the ids of all the tokens it binds.
+pub(crate) fn fragment_at(
+    rules: &[crate::Rule],
+    input: &tt::Subtree,
+    token: tt::TokenId,
+) -> Option<(SmolStr, Vec<tt::TokenId>)> {
+    let mut res = None;
+    let mut best_key = None;
+    for rule in rules {
+        let match_ = matcher::match_(&rule.lhs, input);
+        let bound = match_.bindings.inner.iter().find_map(|(name, binding)| {
+            let tokens = binding_tokens_containing(binding, token)?;
+            Some((name, tokens))
+        });
+        let (name, tokens) = match bound {
+            Some(it) => it,
+            None => continue,
+        };
+        let kind = match var_kind(&rule.lhs, name) {
+            Some(it) => it,
+            None => continue,
+        };
+        // Same preference as in `expand_rules`: error-free matches first, then the ones that
+        // matched more tokens or bound more variables.
+        let key = (match_.err.is_some(), match_.unmatched_tts, -(match_.bound_count as i32));
+        if best_key.map_or(true, |best| key < best) {
+            best_key = Some(key);
+            res = Some((kind, tokens));
+        }
+    }
+    res
+}
+
+fn binding_tokens_containing(binding: &Binding, token: tt::TokenId) -> Option<Vec<tt::TokenId>> {
+    match binding {
+        Binding::Fragment(Fragment::Tokens(tt) | Fragment::Expr(tt)) => {
+            let mut ids = Vec::new();
+            collect_token_ids(tt, &mut ids);
+            ids.contains(&token).then(|| ids)
+        }
+        Binding::Nested(nested) => {
+            nested.iter().find_map(|binding| binding_tokens_containing(binding, token))
+        }
+        Binding::Empty => None,
+    }
+}
+
+fn collect_token_ids(tt: &tt::TokenTree, acc: &mut Vec<tt::TokenId>) {
+    match tt {
+        tt::TokenTree::Leaf(leaf) => acc.push(leaf.id()),
+        tt::TokenTree::Subtree(subtree) => {
+            acc.extend(subtree.delimiter.map(|it| it.id));
+            subtree.token_trees.iter().for_each(|it| collect_token_ids(it, acc));
+        }
+    }
+}
+
+fn var_kind(pattern: &MetaTemplate, var: &str) -> Option<SmolStr> {
+    pattern.iter().find_map(|op| match op {
+        Op::Var { name, kind, .. } if name == var => kind.clone(),
+        Op::Subtree { tokens, .. } | Op::Repeat { tokens, .. } => var_kind(tokens, var),
+        _ => None,
+    })
+}
+
 /// The actual algorithm for expansion is not too hard, but is pretty tricky.
 /// `Bindings` structure is the key to understanding what we are doing here.
 ///
@@ -18,6 +18,8 @@ mod token_map;
 
 use std::fmt;
 
+use syntax::SmolStr;
+
 use crate::{
     parser::{MetaTemplate, Op},
     tt_iter::TtIter,
@@ -105,6 +107,15 @@ pub struct DeclarativeMacro {
     shift: Shift,
 }
 
+/// A part of a macro input bound by a `$name:kind` metavariable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchedFragment {
+    /// The fragment specifier, like `expr` or `ty`.
+    pub kind: SmolStr,
+    /// The ids of all the tokens and delimiters in the fragment.
+    pub tokens: Vec<tt::TokenId>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Rule {
     lhs: MetaTemplate,
@@ -249,6 +260,18 @@ impl DeclarativeMacro {
         expander::expand_rules(&self.rules, &tt)
     }
 
+    /// Finds the `$name:kind` metavariable binding `token` in the rule matching `tt` best.
+    ///
+    /// This works even if no rule matches completely, so completion can tell what syntax the
+    /// cursor is in when the macro call doesn't expand.
+    pub fn fragment_at(&self, tt: &tt::Subtree, token: tt::TokenId) -> Option<MatchedFragment> {
+        let mut tt = tt.clone();
+        self.shift.shift_all(&mut tt);
+        let (kind, tokens) = expander::fragment_at(&self.rules, &tt, self.shift.shift(token))?;
+        let tokens = tokens.into_iter().filter_map(|id| self.shift.unshift(id)).collect();
+        Some(MatchedFragment { kind, tokens })
+    }
+
     pub fn map_id_down(&self, id: tt::TokenId) -> tt::TokenId {
         self.shift.shift(id)
     }
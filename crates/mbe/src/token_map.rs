@@ -60,6 +60,17 @@ impl TokenMap {
             .filter_map(move |(_, range)| range.by_kind(kind))
     }
 
+    /// The range covering all the tokens and delimiters with one of the given ids.
+    pub fn covering_range(&self, token_ids: &[tt::TokenId]) -> Option<TextRange> {
+        self.entries
+            .iter()
+            .filter(|(id, _)| token_ids.contains(id))
+            .map(|(_, range)| match *range {
+                TokenTextRange::Token(it) | TokenTextRange::Delimiter(it) => it,
+            })
+            .reduce(TextRange::cover)
+    }
+
     pub fn synthetic_token_id(&self, token_id: tt::TokenId) -> Option<SyntheticTokenId> {
         self.synthetic_entries.iter().find(|(tid, _)| *tid == token_id).map(|(_, id)| *id)
     }
@@ -144,6 +144,8 @@ pub struct PackageData {
     pub dependencies: Vec<PackageDependency>,
     /// Rust edition for this package
     pub edition: Edition,
+    /// The `rust-version` given in the `Cargo.toml`
+    pub rust_version: Option<String>,
     /// Features provided by the crate, mapped to the features required by that feature.
     pub features: FxHashMap<String, Vec<String>>,
     /// List of features enabled on this package
@@ -334,6 +336,7 @@ impl CargoWorkspace {
                 is_local,
                 is_member,
                 edition,
+                rust_version: meta_pkg.rust_version.as_ref().and_then(rust_version),
                 repository: repository.clone(),
                 dependencies: Vec::new(),
                 features: meta_pkg.features.clone().into_iter().collect(),
@@ -495,3 +498,13 @@ fn cargo_config_build_target(cargo_toml: &ManifestPath) -> Option<String> {
         Err(_) => None,
     }
 }
+
+/// Cargo reports the `rust-version` of a package as a requirement, turn it back into the version.
+fn rust_version(req: &semver::VersionReq) -> Option<String> {
+    let version = req.comparators.first()?;
+    let res = match version.patch {
+        Some(patch) => format!("{}.{}.{}", version.major, version.minor?, patch),
+        None => format!("{}.{}", version.major, version.minor?),
+    };
+    Some(res)
+}
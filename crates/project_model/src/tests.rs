@@ -124,6 +124,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -188,6 +189,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -261,6 +263,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                         version: Some(
                             "0.2.98",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -326,6 +329,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -399,6 +403,7 @@ fn cargo_hello_world_project_model_with_wildcard_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -494,6 +499,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -560,6 +566,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -635,6 +642,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                         version: Some(
                             "0.2.98",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -700,6 +708,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -775,6 +784,7 @@ fn cargo_hello_world_project_model_with_selective_overrides() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -863,6 +873,7 @@ fn cargo_hello_world_project_model() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -929,6 +940,7 @@ fn cargo_hello_world_project_model() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1004,6 +1016,7 @@ fn cargo_hello_world_project_model() {
                         version: Some(
                             "0.2.98",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1071,6 +1084,7 @@ fn cargo_hello_world_project_model() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1146,6 +1160,7 @@ fn cargo_hello_world_project_model() {
                         version: Some(
                             "0.1.0",
                         ),
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1232,6 +1247,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1272,6 +1288,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1302,6 +1319,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1332,6 +1350,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1372,6 +1391,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1402,6 +1422,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1471,6 +1492,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1501,6 +1523,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1531,6 +1554,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1561,6 +1585,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1591,6 +1616,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
@@ -1703,6 +1729,7 @@ fn rust_project_hello_world_project_model() {
                         ),
                         edition: Edition2018,
                         version: None,
+                        rust_version: None,
                         display_name: Some(
                             CrateDisplayName {
                                 crate_name: CrateName(
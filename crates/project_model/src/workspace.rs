@@ -479,6 +479,7 @@ fn project_json_to_crate_graph(
                     krate.edition,
                     krate.display_name.clone(),
                     krate.version.clone(),
+                    None,
                     cfg_options.clone(),
                     cfg_options,
                     env,
@@ -705,6 +706,7 @@ fn detached_files_to_crate_graph(
             Edition::CURRENT,
             display_name,
             None,
+            None,
             cfg_options.clone(),
             cfg_options.clone(),
             Env::default(),
@@ -858,6 +860,7 @@ fn add_target_crate_root(
         edition,
         Some(display_name),
         Some(pkg.version.to_string()),
+        pkg.rust_version.clone(),
         cfg_options,
         potential_cfg_options,
         env,
@@ -903,6 +906,7 @@ fn sysroot_to_crate_graph(
                 Edition::CURRENT,
                 Some(display_name),
                 None,
+                None,
                 cfg_options.clone(),
                 cfg_options.clone(),
                 env,
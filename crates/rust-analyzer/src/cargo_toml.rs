@@ -0,0 +1,833 @@
+//! Editing support for `Cargo.toml`: completion and hover for manifest keys,
+//! crate names and features, and diagnostics for features that don't exist.
+//!
+//! We don't parse TOML properly. A small scanner keeps track of the current
+//! table, the key being defined and the bracket nesting, which is all we need
+//! to know what a token in a manifest stands for. Package and feature
+//! information comes from `cargo metadata`, crate names from the registry
+//! index cached in `$CARGO_HOME`.
+
+use std::{fs, path::Path};
+
+use itertools::Itertools;
+use project_model::ProjectWorkspace;
+use rustc_hash::FxHashMap;
+use syntax::{TextRange, TextSize};
+use vfs::AbsPath;
+
+/// What `cargo metadata` told us about the package of a manifest.
+#[derive(Debug, Default)]
+pub(crate) struct ManifestData {
+    /// Features declared by the package.
+    pub(crate) features: FxHashMap<String, Vec<String>>,
+    /// Dependencies of the package, keyed by their normalized name.
+    pub(crate) dependencies: FxHashMap<String, DependencyData>,
+    /// Names of all packages in the workspace.
+    pub(crate) workspace_packages: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct DependencyData {
+    pub(crate) package: String,
+    pub(crate) version: String,
+    pub(crate) features: FxHashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ManifestCompletion {
+    pub(crate) label: String,
+    pub(crate) detail: Option<String>,
+    pub(crate) range: TextRange,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ManifestHover {
+    pub(crate) markdown: String,
+    pub(crate) range: TextRange,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ManifestDiagnostic {
+    pub(crate) message: String,
+    pub(crate) range: TextRange,
+}
+
+pub(crate) fn is_manifest(path: &Path) -> bool {
+    path.file_name().map_or(false, |name| name == "Cargo.toml")
+}
+
+pub(crate) fn manifest_data(
+    workspaces: &[ProjectWorkspace],
+    path: &AbsPath,
+) -> Option<ManifestData> {
+    workspaces.iter().find_map(|ws| {
+        let cargo = match ws {
+            ProjectWorkspace::Cargo { cargo, .. } => cargo,
+            _ => return None,
+        };
+        let pkg = cargo.packages().find(|&pkg| &*cargo[pkg].manifest == path)?;
+        let pkg = &cargo[pkg];
+        let dependencies = pkg
+            .dependencies
+            .iter()
+            .map(|dep| {
+                let dep_pkg = &cargo[dep.pkg];
+                let data = DependencyData {
+                    package: dep_pkg.name.clone(),
+                    version: dep_pkg.version.to_string(),
+                    features: dep_pkg.features.clone(),
+                };
+                (normalize(&dep.name), data)
+            })
+            .collect();
+        let workspace_packages = cargo
+            .packages()
+            .filter(|&it| cargo[it].is_member)
+            .map(|it| cargo[it].name.clone())
+            .collect();
+        Some(ManifestData { features: pkg.features.clone(), dependencies, workspace_packages })
+    })
+}
+
+/// Names of the crates in the local registry index cache that start with `prefix`.
+pub(crate) fn registry_crate_names(cargo_home: &Path, prefix: &str) -> Vec<String> {
+    const LIMIT: usize = 100;
+
+    let prefix = prefix.to_ascii_lowercase();
+    // Crate names are ASCII, which also makes the byte slicing below safe.
+    if prefix.is_empty() || !prefix.is_ascii() {
+        return Vec::new();
+    }
+    let index_roots = fs::read_dir(cargo_home.join("registry/index"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join(".cache"));
+    let mut res = Vec::new();
+    for root in index_roots {
+        // The index shards crates by name: `1/a`, `2/ab`, `3/a/abc`, `ab/cd/abcd...`.
+        let mut dirs = vec!["1".to_owned(), "2".to_owned(), format!("3/{}", &prefix[..1])];
+        if prefix.len() >= 4 {
+            dirs.push(format!("{}/{}", &prefix[..2], &prefix[2..4]));
+        } else if prefix.len() >= 2 {
+            let shards = fs::read_dir(root.join(&prefix[..2])).into_iter().flatten().flatten();
+            for shard in shards {
+                let name = shard.file_name().to_string_lossy().into_owned();
+                if name.starts_with(&prefix[2..]) {
+                    dirs.push(format!("{}/{}", &prefix[..2], name));
+                }
+            }
+        }
+        for dir in &dirs {
+            let entries = fs::read_dir(root.join(dir)).into_iter().flatten().flatten();
+            res.extend(
+                entries
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .filter(|name| name.starts_with(&prefix)),
+            );
+        }
+    }
+    res.sort();
+    res.dedup();
+    res.truncate(LIMIT);
+    res
+}
+
+pub(crate) fn completions(
+    text: &str,
+    offset: TextSize,
+    data: &ManifestData,
+    registry: &dyn Fn(&str) -> Vec<String>,
+) -> Vec<ManifestCompletion> {
+    let ctx = match CompletionCtx::new(&text[..usize::from(offset)]) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let item = |label: &str, detail: Option<&str>| ManifestCompletion {
+        label: label.to_owned(),
+        detail: detail.map(ToOwned::to_owned),
+        range: ctx.range,
+    };
+    let keys = |keys: &[(&str, &str)]| -> Vec<ManifestCompletion> {
+        keys.iter().map(|(key, doc)| item(key, Some(doc))).collect()
+    };
+
+    match &ctx.kind {
+        CompletionKind::Header => keys(TABLES),
+        CompletionKind::Key { table, keys: parent } => match Place::of(table, parent) {
+            Place::Package => keys(PACKAGE_KEYS),
+            Place::Target => keys(TARGET_KEYS),
+            Place::Dependency { .. } => keys(DEPENDENCY_KEYS),
+            Place::DependencyTable => {
+                let mut names: Vec<String> = data.workspace_packages.clone();
+                names.extend(data.dependencies.values().map(|it| it.package.clone()));
+                names.extend(registry(&ctx.prefix));
+                names.sort();
+                names.dedup();
+                names.iter().map(|name| item(name, None)).collect()
+            }
+            _ => Vec::new(),
+        },
+        CompletionKind::Value { table, keys, in_array } => {
+            match (Place::of(table, &keys[..keys.len().saturating_sub(1)]), keys.last()) {
+                (Place::Dependency { name }, Some(key)) if key == "features" && *in_array => {
+                    let dep = match data.dependencies.get(&normalize(&name)) {
+                        Some(it) => it,
+                        None => return Vec::new(),
+                    };
+                    dep.features
+                        .iter()
+                        .sorted_by(|a, b| a.0.cmp(b.0))
+                        .map(|(name, enables)| item(name, Some(&enables.join(", "))))
+                        .collect()
+                }
+                (Place::Features, Some(_)) if *in_array => {
+                    if let Some((dep, _)) = ctx.prefix.split_once('/') {
+                        let dep_name = dep.trim_end_matches('?');
+                        let dep_data = match data.dependencies.get(&normalize(dep_name)) {
+                            Some(it) => it,
+                            None => return Vec::new(),
+                        };
+                        return dep_data
+                            .features
+                            .keys()
+                            .sorted()
+                            .map(|feature| item(&format!("{}/{}", dep, feature), None))
+                            .collect();
+                    }
+                    data.features
+                        .iter()
+                        .sorted_by(|a, b| a.0.cmp(b.0))
+                        .map(|(name, enables)| item(name, Some(&enables.join(", "))))
+                        .collect()
+                }
+                (Place::Package, Some(key)) if key == "edition" => {
+                    ["2015", "2018", "2021"].iter().map(|it| item(it, None)).collect()
+                }
+                _ => Vec::new(),
+            }
+        }
+    }
+}
+
+pub(crate) fn hover(text: &str, offset: TextSize, data: &ManifestData) -> Option<ManifestHover> {
+    let token = scan(text).tokens.into_iter().find(|it| it.range.contains_inclusive(offset))?;
+    let markdown = match token.kind {
+        TokenKind::Header => {
+            let name = token.table.first()?;
+            find_doc(TABLES, name)?.to_owned()
+        }
+        TokenKind::Key => {
+            let (key, parent) = token.keys.split_last()?;
+            match Place::of(&token.table, parent) {
+                Place::Package => find_doc(PACKAGE_KEYS, key)?.to_owned(),
+                Place::Target => find_doc(TARGET_KEYS, key)?.to_owned(),
+                Place::Dependency { .. } => find_doc(DEPENDENCY_KEYS, key)?.to_owned(),
+                Place::DependencyTable => {
+                    let dep = data.dependencies.get(&normalize(key))?;
+                    dependency_doc(dep)
+                }
+                Place::Features => feature_doc(key, data.features.get(key.as_str())?),
+                Place::Other => return None,
+            }
+        }
+        TokenKind::Str => {
+            let (key, parent) = token.keys.split_last()?;
+            match Place::of(&token.table, parent) {
+                Place::Dependency { name } if key == "features" => {
+                    let dep = data.dependencies.get(&normalize(&name))?;
+                    feature_doc(&token.text, dep.features.get(&token.text)?)
+                }
+                Place::Features => match token.text.split_once('/') {
+                    Some((dep, feature)) => {
+                        let dep = data.dependencies.get(&normalize(dep.trim_end_matches('?')))?;
+                        feature_doc(feature, dep.features.get(feature)?)
+                    }
+                    None => match token.text.strip_prefix("dep:") {
+                        Some(dep) => dependency_doc(data.dependencies.get(&normalize(dep))?),
+                        None => feature_doc(&token.text, data.features.get(&token.text)?),
+                    },
+                },
+                _ => return None,
+            }
+        }
+    };
+    Some(ManifestHover { markdown, range: token.range })
+}
+
+pub(crate) fn diagnostics(text: &str, data: &ManifestData) -> Vec<ManifestDiagnostic> {
+    let mut res = Vec::new();
+    for token in scan(text).tokens {
+        if token.kind != TokenKind::Str || !token.in_array {
+            continue;
+        }
+        let (key, parent) = match token.keys.split_last() {
+            Some(it) => it,
+            None => continue,
+        };
+        let message = match Place::of(&token.table, parent) {
+            Place::Dependency { name } if key == "features" => {
+                match data.dependencies.get(&normalize(&name)) {
+                    Some(dep) if !dep.features.contains_key(&token.text) => {
+                        format!("`{}` has no feature `{}`", dep.package, token.text)
+                    }
+                    _ => continue,
+                }
+            }
+            Place::Features => match unknown_feature_reference(&token.text, data) {
+                Some(it) => it,
+                None => continue,
+            },
+            _ => continue,
+        };
+        res.push(ManifestDiagnostic { message, range: token.range });
+    }
+    res
+}
+
+fn unknown_feature_reference(reference: &str, data: &ManifestData) -> Option<String> {
+    if let Some((dep, feature)) = reference.split_once('/') {
+        let dep_name = dep.trim_end_matches('?');
+        return match data.dependencies.get(&normalize(dep_name)) {
+            Some(dep) if !dep.features.contains_key(feature) => {
+                Some(format!("`{}` has no feature `{}`", dep.package, feature))
+            }
+            Some(_) => None,
+            None if data.features.contains_key(dep_name) => None,
+            None => Some(format!("unknown dependency `{}`", dep_name)),
+        };
+    }
+    if let Some(dep) = reference.strip_prefix("dep:") {
+        return match data.dependencies.contains_key(&normalize(dep)) {
+            true => None,
+            false => Some(format!("unknown dependency `{}`", dep)),
+        };
+    }
+    // Optional dependencies are implicitly features, too.
+    if data.features.contains_key(reference)
+        || data.dependencies.contains_key(&normalize(reference))
+    {
+        return None;
+    }
+    Some(format!("unknown feature `{}`", reference))
+}
+
+fn dependency_doc(dep: &DependencyData) -> String {
+    let mut res = format!("```\n{} {}\n```", dep.package, dep.version);
+    if !dep.features.is_empty() {
+        res.push_str("\n\nFeatures: ");
+        res.push_str(&dep.features.keys().sorted().map(|it| format!("`{}`", it)).join(", "));
+    }
+    res
+}
+
+fn feature_doc(name: &str, enables: &[String]) -> String {
+    if enables.is_empty() {
+        format!("Feature `{}` enables nothing else", name)
+    } else {
+        format!(
+            "Feature `{}` enables {}",
+            name,
+            enables.iter().map(|it| format!("`{}`", it)).join(", ")
+        )
+    }
+}
+
+fn find_doc(keys: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    keys.iter().find(|(it, _)| *it == key).map(|(_, doc)| *doc)
+}
+
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Where in a manifest a key lives.
+#[derive(Debug, PartialEq, Eq)]
+enum Place {
+    Package,
+    Target,
+    Features,
+    /// Directly in one of the dependency tables, so keys are dependency names.
+    DependencyTable,
+    /// Inside the definition of a dependency.
+    Dependency {
+        name: String,
+    },
+    Other,
+}
+
+impl Place {
+    /// `keys` are the keys enclosing the position within `table`.
+    fn of(table: &[String], keys: &[String]) -> Place {
+        let table: Vec<&str> = table.iter().map(String::as_str).collect();
+        let dep_table = match table.as_slice() {
+            [tbl, ..] if DEPENDENCY_TABLES.contains(tbl) => 1,
+            ["target", _, tbl, ..] if DEPENDENCY_TABLES.contains(tbl) => 3,
+            ["workspace", "dependencies", ..] => 2,
+            ["package"] if keys.is_empty() => return Place::Package,
+            ["lib" | "bin" | "example" | "test" | "bench"] if keys.is_empty() => {
+                return Place::Target
+            }
+            ["features"] => return Place::Features,
+            _ => return Place::Other,
+        };
+        match (&table[dep_table..], keys) {
+            ([], []) => Place::DependencyTable,
+            ([], [name]) => Place::Dependency { name: name.clone() },
+            ([name], []) => Place::Dependency { name: name.to_string() },
+            _ => Place::Other,
+        }
+    }
+}
+
+enum CompletionKind {
+    Header,
+    Key { table: Vec<String>, keys: Vec<String> },
+    Value { table: Vec<String>, keys: Vec<String>, in_array: bool },
+}
+
+struct CompletionCtx {
+    kind: CompletionKind,
+    prefix: String,
+    range: TextRange,
+}
+
+impl CompletionCtx {
+    /// `text` is the manifest up to the cursor.
+    fn new(text: &str) -> Option<CompletionCtx> {
+        let end = TextSize::of(text);
+        let scanner = scan(text);
+        if let Some(token) = scanner.partial {
+            let prefix = token.text.clone();
+            let range = token.range;
+            let kind = match token.kind {
+                TokenKind::Header => CompletionKind::Header,
+                TokenKind::Str => CompletionKind::Value {
+                    table: token.table,
+                    keys: token.keys,
+                    in_array: token.in_array,
+                },
+                TokenKind::Key => return None,
+            };
+            return Some(CompletionCtx { kind, prefix, range });
+        }
+        if !scanner.expect_key {
+            return None;
+        }
+        match scanner.tokens.last() {
+            Some(token) if token.kind == TokenKind::Key && token.range.end() == end => {
+                let mut keys = token.keys.clone();
+                keys.pop();
+                Some(CompletionCtx {
+                    kind: CompletionKind::Key { table: token.table.clone(), keys },
+                    prefix: token.text.clone(),
+                    range: token.range,
+                })
+            }
+            _ => Some(CompletionCtx {
+                kind: CompletionKind::Key { table: scanner.table, keys: scanner.keys },
+                prefix: String::new(),
+                range: TextRange::empty(end),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Header,
+    Key,
+    Str,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// The table containing the token; for headers the table they open.
+    table: Vec<String>,
+    /// The keys leading to the token within its table, including the key itself for keys.
+    keys: Vec<String>,
+    in_array: bool,
+    /// The unquoted text of the token.
+    text: String,
+    range: TextRange,
+}
+
+#[derive(Default)]
+struct Scanner {
+    tokens: Vec<Token>,
+    /// A header or string left open at the end of the input.
+    partial: Option<Token>,
+    table: Vec<String>,
+    keys: Vec<String>,
+    /// Open brackets, with the number of keys at the point they were opened.
+    nesting: Vec<(u8, usize)>,
+    expect_key: bool,
+}
+
+fn scan(text: &str) -> Scanner {
+    let mut s = Scanner { expect_key: true, ..Scanner::default() };
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'#' => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'\n' => {
+                pos += 1;
+                if s.nesting.is_empty() {
+                    s.expect_key = true;
+                    s.keys.clear();
+                }
+            }
+            b'[' if s.expect_key && s.nesting.is_empty() => {
+                while pos < bytes.len() && bytes[pos] == b'[' {
+                    pos += 1;
+                }
+                let start = pos;
+                while pos < bytes.len() && bytes[pos] != b']' && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+                let header = &text[start..pos];
+                let table: Vec<String> = header
+                    .split('.')
+                    .map(|it| it.trim().trim_matches(|c| c == '"' || c == '\'').to_owned())
+                    .collect();
+                let token = Token {
+                    kind: TokenKind::Header,
+                    table: table.clone(),
+                    keys: Vec::new(),
+                    in_array: false,
+                    text: header.trim().to_owned(),
+                    range: range(start, pos),
+                };
+                if pos == bytes.len() {
+                    s.partial = Some(token);
+                    break;
+                }
+                s.tokens.push(token);
+                while pos < bytes.len() && bytes[pos] == b']' {
+                    pos += 1;
+                }
+                s.table = table;
+                s.keys.clear();
+            }
+            quote @ (b'"' | b'\'') => {
+                let multiline = bytes[pos..].starts_with(&[quote; 3]);
+                let delim_len = if multiline { 3 } else { 1 };
+                pos += delim_len;
+                let start = pos;
+                let mut terminated = false;
+                while pos < bytes.len() {
+                    if quote == b'"' && bytes[pos] == b'\\' {
+                        pos += 2;
+                        continue;
+                    }
+                    if !multiline && bytes[pos] == b'\n' {
+                        break;
+                    }
+                    if bytes[pos..].starts_with(&[quote; 3][..delim_len]) {
+                        terminated = true;
+                        break;
+                    }
+                    pos += 1;
+                }
+                let end = pos.min(bytes.len());
+                let value = &text[start..end];
+                if s.expect_key {
+                    s.push_key(value, range(start, end));
+                } else {
+                    let token = Token {
+                        kind: TokenKind::Str,
+                        table: s.table.clone(),
+                        keys: s.keys.clone(),
+                        in_array: matches!(s.nesting.last(), Some((b'[', _))),
+                        text: value.to_owned(),
+                        range: range(start, end),
+                    };
+                    if !terminated && end == bytes.len() {
+                        s.partial = Some(token);
+                        break;
+                    }
+                    s.tokens.push(token);
+                }
+                if terminated {
+                    pos += delim_len;
+                }
+            }
+            b'=' => {
+                s.expect_key = false;
+                pos += 1;
+            }
+            open @ (b'[' | b'{') => {
+                s.nesting.push((open, s.keys.len()));
+                s.expect_key = open == b'{';
+                pos += 1;
+            }
+            b',' => {
+                if let Some(&(b'{', keys)) = s.nesting.last() {
+                    s.keys.truncate(keys);
+                    s.expect_key = true;
+                }
+                pos += 1;
+            }
+            b']' | b'}' => {
+                if let Some((_, keys)) = s.nesting.pop() {
+                    s.keys.truncate(keys);
+                }
+                s.expect_key = false;
+                pos += 1;
+            }
+            c if s.expect_key && is_key_char(c) => {
+                let start = pos;
+                while pos < bytes.len() && is_key_char(bytes[pos]) {
+                    pos += 1;
+                }
+                s.push_key(&text[start..pos], range(start, pos));
+            }
+            _ => pos += 1,
+        }
+    }
+    s
+}
+
+impl Scanner {
+    fn push_key(&mut self, key: &str, range: TextRange) {
+        self.keys.push(key.to_owned());
+        self.tokens.push(Token {
+            kind: TokenKind::Key,
+            table: self.table.clone(),
+            keys: self.keys.clone(),
+            in_array: false,
+            text: key.to_owned(),
+            range,
+        });
+    }
+}
+
+fn is_key_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'-'
+}
+
+fn range(start: usize, end: usize) -> TextRange {
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+const TABLES: &[(&str, &str)] = &[
+    ("package", "Defines a package."),
+    ("dependencies", "Package library dependencies."),
+    ("dev-dependencies", "Dependencies for examples, tests, and benchmarks."),
+    ("build-dependencies", "Dependencies for build scripts."),
+    ("features", "Conditional compilation features."),
+    ("lib", "Library target settings."),
+    ("bin", "Binary target settings."),
+    ("example", "Example target settings."),
+    ("test", "Test target settings."),
+    ("bench", "Benchmark target settings."),
+    ("target", "Platform-specific dependencies."),
+    ("badges", "Badges to display on a registry."),
+    ("profile", "Compiler settings and optimizations."),
+    ("patch", "Override dependencies."),
+    ("workspace", "The workspace definition."),
+];
+
+const PACKAGE_KEYS: &[(&str, &str)] = &[
+    ("name", "The name of the package."),
+    ("version", "The version of the package."),
+    ("authors", "The authors of the package."),
+    ("edition", "The Rust edition."),
+    ("rust-version", "The minimal supported Rust version."),
+    ("description", "A description of the package."),
+    ("documentation", "URL of the package documentation."),
+    ("readme", "Path to the package's README file."),
+    ("homepage", "URL of the package homepage."),
+    ("repository", "URL of the package source repository."),
+    ("license", "The package license."),
+    ("license-file", "Path to the text of the license."),
+    ("keywords", "Keywords for the package."),
+    ("categories", "Categories of the package."),
+    ("workspace", "Path to the workspace for the package."),
+    ("build", "Path to the package build script."),
+    ("links", "Name of the native library the package links with."),
+    ("exclude", "Files to exclude when publishing."),
+    ("include", "Files to include when publishing."),
+    ("publish", "Can be used to prevent publishing the package."),
+    ("metadata", "Extra settings for external tools."),
+    ("default-run", "The default binary to run by `cargo run`."),
+    ("autobins", "Disables binary auto discovery."),
+    ("autoexamples", "Disables example auto discovery."),
+    ("autotests", "Disables test auto discovery."),
+    ("autobenches", "Disables bench auto discovery."),
+    ("resolver", "Sets the dependency resolver to use."),
+];
+
+const DEPENDENCY_KEYS: &[(&str, &str)] = &[
+    ("version", "The version requirement of the dependency."),
+    ("path", "Path to a local dependency."),
+    ("git", "URL of a git repository to fetch the dependency from."),
+    ("branch", "The git branch to use."),
+    ("tag", "The git tag to use."),
+    ("rev", "The git revision to use."),
+    ("features", "Features of the dependency to enable."),
+    ("default-features", "Whether to enable the default features of the dependency."),
+    ("optional", "Makes the dependency optional, enabled by a feature of the same name."),
+    ("package", "The name of the package, if the dependency is renamed."),
+    ("registry", "The registry to fetch the dependency from."),
+    ("workspace", "Inherit the dependency from the workspace."),
+];
+
+const TARGET_KEYS: &[(&str, &str)] = &[
+    ("name", "The name of the target."),
+    ("path", "The source file of the target, relative to the manifest."),
+    ("test", "Is tested by default."),
+    ("doctest", "Documentation examples are tested by default."),
+    ("bench", "Is benchmarked by default."),
+    ("doc", "Is documented by default."),
+    ("proc-macro", "Set to `true` for a proc-macro library."),
+    ("harness", "Use libtest harness."),
+    ("edition", "The edition the target is compiled with."),
+    ("crate-type", "The crate types to generate."),
+    ("required-features", "Features required to build the target."),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> ManifestData {
+        let features = |it: &[(&str, &[&str])]| {
+            it.iter()
+                .map(|(name, enables)| {
+                    (name.to_string(), enables.iter().map(|it| it.to_string()).collect())
+                })
+                .collect()
+        };
+        let mut dependencies = FxHashMap::default();
+        dependencies.insert(
+            "serde_json".to_owned(),
+            DependencyData {
+                package: "serde-json".to_owned(),
+                version: "1.0.0".to_owned(),
+                features: features(&[("std", &[]), ("raw_value", &[])]),
+            },
+        );
+        ManifestData {
+            features: features(&[("default", &["fast"]), ("fast", &[])]),
+            dependencies,
+            workspace_packages: vec!["my-crate".to_owned()],
+        }
+    }
+
+    fn complete(text: &str) -> Vec<String> {
+        let offset = TextSize::from(text.find("$0").unwrap() as u32);
+        let text = text.replace("$0", "");
+        let registry = |prefix: &str| {
+            ["serde", "serde_derive", "syn"]
+                .iter()
+                .filter(|it| it.starts_with(prefix))
+                .map(|it| it.to_string())
+                .collect()
+        };
+        completions(&text, offset, &data(), &registry).into_iter().map(|it| it.label).collect()
+    }
+
+    #[test]
+    fn completes_tables_and_keys() {
+        assert!(complete("[pac$0").contains(&"package".to_owned()));
+        let keys = complete("[package]\nname = \"foo\"\ned$0");
+        assert!(keys.contains(&"edition".to_owned()));
+        assert_eq!(complete("[package]\nedition = \"$0"), vec!["2015", "2018", "2021"]);
+        assert!(complete("[dependencies]\nserde = { ver$0 }").contains(&"version".to_owned()));
+        assert!(complete("[dependencies.serde]\n$0").contains(&"features".to_owned()));
+    }
+
+    #[test]
+    fn completes_crate_names() {
+        assert_eq!(
+            complete("[dependencies]\nser$0"),
+            vec!["my-crate", "serde", "serde-json", "serde_derive"]
+        );
+    }
+
+    #[test]
+    fn searches_registry_index_cache() {
+        let cargo_home = std::env::temp_dir().join(format!("ra-cargo-home-{}", std::process::id()));
+        for registry in ["a", "b"] {
+            let cache = cargo_home.join("registry/index").join(registry).join(".cache");
+            for name in ["se/rd/serde", "se/rd/serde_derive", "3/s/syn"] {
+                let path = cache.join(name);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, "").unwrap();
+            }
+        }
+        assert_eq!(registry_crate_names(&cargo_home, "serd"), vec!["serde", "serde_derive"]);
+        assert_eq!(registry_crate_names(&cargo_home, "s"), vec!["syn"]);
+        assert!(registry_crate_names(&cargo_home, "é").is_empty());
+        assert!(registry_crate_names(&cargo_home, "séé").is_empty());
+        fs::remove_dir_all(cargo_home).unwrap();
+    }
+
+    #[test]
+    fn completes_features() {
+        assert_eq!(
+            complete("[dependencies]\nserde-json = { version = \"1\", features = [\"$0\"] }"),
+            vec!["raw_value", "std"]
+        );
+        assert_eq!(
+            complete("[dependencies.serde_json]\nfeatures = [\n    \"std\",\n    \"r$0"),
+            vec!["raw_value", "std"]
+        );
+        assert_eq!(complete("[features]\nextra = [\"$0"), vec!["default", "fast"]);
+        assert_eq!(
+            complete("[features]\nextra = [\"serde_json/$0"),
+            vec!["serde_json/raw_value", "serde_json/std"]
+        );
+    }
+
+    #[test]
+    fn hovers() {
+        let hover = |text: &str| {
+            let offset = TextSize::from(text.find("$0").unwrap() as u32);
+            hover(&text.replace("$0", ""), offset, &data()).map(|it| it.markdown)
+        };
+        assert_eq!(hover("[package]\ned$0ition = \"2021\"").unwrap(), "The Rust edition.");
+        assert_eq!(
+            hover("[dependencies]\nserde-j$0son = \"1\"").unwrap(),
+            "```\nserde-json 1.0.0\n```\n\nFeatures: `raw_value`, `std`"
+        );
+        assert_eq!(
+            hover("[features]\ndefault = [\"fa$0st\"]").unwrap(),
+            "Feature `fast` enables nothing else"
+        );
+    }
+
+    #[test]
+    fn reports_unknown_features() {
+        let text = r#"
+[dependencies]
+serde-json = { version = "1", features = ["std", "rc"] }
+unknown = { version = "1", features = ["whatever"] }
+
+[features]
+default = ["fast", "slow", "serde_json/raw_value", "serde_json/arbitrary", "dep:serde-json", "dep:nope"]
+"#;
+        let messages: Vec<_> = diagnostics(text, &data())
+            .into_iter()
+            .map(|it| format!("{}: {}", &text[it.range], it.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "rc: `serde-json` has no feature `rc`",
+                "slow: unknown feature `slow`",
+                "serde_json/arbitrary: `serde-json` has no feature `arbitrary`",
+                "dep:nope: unknown dependency `nope`",
+            ]
+        );
+    }
+}
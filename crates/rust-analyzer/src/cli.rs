@@ -9,6 +9,8 @@ mod analysis_stats;
 mod diagnostics;
 mod ssr;
 mod lsif;
+mod scip;
+mod index_manifest;
 
 mod progress_report;
 
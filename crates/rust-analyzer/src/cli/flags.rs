@@ -107,7 +107,31 @@ xflags::xflags! {
 
         cmd lsif
             required path: PathBuf
-        {}
+        {
+            /// The output path where the LSIF dump will be written to. Defaults to stdout.
+            optional --output output: PathBuf
+            /// Only index the workspace crates with the given name.
+            repeated --crate-name crate_name: String
+            /// Only index the given files, relative to `path`.
+            repeated --file file: PathBuf
+            /// A manifest with the input hashes of the indexed crates. Crates which didn't change
+            /// since the manifest was written are taken over from the existing dump at `--output`.
+            optional --manifest manifest: PathBuf
+        }
+
+        cmd scip
+            required path: PathBuf
+        {
+            /// The output path where the SCIP file will be written to. Defaults to `index.scip`.
+            optional --output output: PathBuf
+            /// Only index the workspace crates with the given name.
+            repeated --crate-name crate_name: String
+            /// Only index the given files, relative to `path`.
+            repeated --file file: PathBuf
+            /// A manifest with the input hashes of the indexed crates. Crates which didn't change
+            /// since the manifest was written are taken over from the existing index at `--output`.
+            optional --manifest manifest: PathBuf
+        }
     }
 }
 
@@ -136,6 +160,7 @@ pub enum RustAnalyzerCmd {
     Search(Search),
     ProcMacro(ProcMacro),
     Lsif(Lsif),
+    Scip(Scip),
 }
 
 #[derive(Debug)]
@@ -201,6 +226,21 @@ pub struct ProcMacro;
 #[derive(Debug)]
 pub struct Lsif {
     pub path: PathBuf,
+
+    pub output: Option<PathBuf>,
+    pub crate_name: Vec<String>,
+    pub file: Vec<PathBuf>,
+    pub manifest: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub struct Scip {
+    pub path: PathBuf,
+
+    pub output: Option<PathBuf>,
+    pub crate_name: Vec<String>,
+    pub file: Vec<PathBuf>,
+    pub manifest: Option<PathBuf>,
 }
 
 impl RustAnalyzer {
@@ -0,0 +1,220 @@
+//! Input hashes of the crates of an index, used by the LSIF and SCIP generators to only re-index
+//! the crates which changed since a previous run.
+
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use hir::Crate;
+use ide::{FileId, RootDatabase};
+use ide_db::base_db::SourceDatabaseExt;
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::Result;
+
+/// The inputs of a previous run, used to skip re-indexing unchanged crates.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct IndexManifest {
+    tool_version: String,
+    /// Input hashes of the indexed crates, keyed by the relative path of their root file.
+    crates: BTreeMap<String, String>,
+}
+
+impl IndexManifest {
+    /// Reads the manifest at `path`, if the index it describes still exists at `output` and was
+    /// written by this version of rust-analyzer.
+    pub(super) fn read(path: Option<&Path>, output: &Path) -> Result<Option<IndexManifest>> {
+        let path = match path {
+            Some(it) if it.exists() && output.exists() => it,
+            _ => return Ok(None),
+        };
+        let manifest: IndexManifest = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Some(manifest).filter(|it| it.tool_version == env!("REV")))
+    }
+
+    pub(super) fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// What an incremental run indexes.
+pub(super) struct IndexPlan {
+    /// The files to index in this run.
+    pub(super) reindexed_files: FxHashSet<FileId>,
+    /// The workspace files whose documents are taken over from the previous index. Empty when
+    /// there is no previous index.
+    pub(super) kept_files: FxHashSet<FileId>,
+    /// The manifest describing the index written by this run.
+    pub(super) manifest: IndexManifest,
+}
+
+impl IndexPlan {
+    /// Selects the files of the workspace crates named in `crate_names`, or of all of them if
+    /// empty, which changed since `previous` was written. `files` further restricts them to the
+    /// given paths, relative to the workspace root.
+    pub(super) fn new(
+        db: &RootDatabase,
+        relative_path: impl Fn(FileId) -> Option<String>,
+        previous: Option<&IndexManifest>,
+        crate_names: &[String],
+        files: &[PathBuf],
+    ) -> IndexPlan {
+        let files_filter: Option<FxHashSet<String>> = if files.is_empty() {
+            None
+        } else {
+            Some(files.iter().filter_map(|it| Some(it.to_str()?.to_string())).collect())
+        };
+
+        let mut hashes = FxHashMap::default();
+        let mut manifest =
+            IndexManifest { tool_version: env!("REV").to_string(), crates: BTreeMap::new() };
+        let mut local_files = FxHashSet::default();
+        let mut reindexed_files = FxHashSet::default();
+        for krate in Crate::all(db) {
+            let root_file = krate.root_file(db);
+            if db.source_root(db.file_source_root(root_file)).is_library {
+                continue;
+            }
+            let key = match relative_path(root_file) {
+                Some(it) => it,
+                None => continue,
+            };
+            let crate_files = crate_files(db, krate);
+            local_files.extend(crate_files.iter().copied());
+            let name = krate.display_name(db).map(|it| it.to_string()).unwrap_or_default();
+            if !crate_names.is_empty() && !crate_names.contains(&name) {
+                // The documents of this crate are kept as they are, and so is their hash.
+                if let Some(hash) = previous.and_then(|it| it.crates.get(&key)) {
+                    manifest.crates.insert(key, hash.clone());
+                }
+                continue;
+            }
+            let hash = format!("{:016x}", crate_hash(db, krate, &mut hashes));
+            if previous.map_or(false, |it| it.crates.get(&key) == Some(&hash)) {
+                manifest.crates.insert(key, hash);
+                continue;
+            }
+            reindexed_files.extend(crate_files.iter().copied().filter(|&file_id| {
+                match (&files_filter, relative_path(file_id)) {
+                    (None, _) => true,
+                    (Some(filter), Some(path)) => filter.contains(&path),
+                    (Some(_), None) => false,
+                }
+            }));
+            // A crate which was only partially indexed has to be indexed again next time.
+            if files_filter.is_none() {
+                manifest.crates.insert(key, hash);
+            }
+        }
+        // Every file which still belongs to the workspace keeps its previous document unless it
+        // is reindexed, documents of deleted files are dropped.
+        let kept_files = match previous {
+            Some(_) => local_files.difference(&reindexed_files).copied().collect(),
+            None => FxHashSet::default(),
+        };
+        IndexPlan { reindexed_files, kept_files, manifest }
+    }
+}
+
+/// Hashes the source of `krate` together with the hashes of its dependencies, so that
+/// a change to a dependency also invalidates all crates depending on it.
+///
+/// All files of the crate's source root are hashed, not only its modules, to also cover the
+/// files it `include!`s. The cfg options are part of the hash, as enabling a feature changes
+/// what gets indexed.
+fn crate_hash(db: &RootDatabase, krate: Crate, hashes: &mut FxHashMap<Crate, u64>) -> u64 {
+    if let Some(&hash) = hashes.get(&krate) {
+        return hash;
+    }
+    let mut hasher = FxHasher::default();
+    krate.edition(db).to_string().hash(&mut hasher);
+    // The `Debug` output of `CfgOptions` is sorted, unlike its iteration order.
+    format!("{:?}", krate.cfg(db)).hash(&mut hasher);
+    let source_root = db.source_root(db.file_source_root(krate.root_file(db)));
+    let mut files: Vec<_> = source_root
+        .iter()
+        .filter_map(|file_id| Some((source_root.path_for_file(&file_id)?.to_string(), file_id)))
+        .collect();
+    files.sort();
+    for (_, file_id) in files {
+        db.file_text(file_id).hash(&mut hasher);
+    }
+    for dep in krate.dependencies(db) {
+        dep.name.to_string().hash(&mut hasher);
+        crate_hash(db, dep.krate, hashes).hash(&mut hasher);
+    }
+    let hash = hasher.finish();
+    hashes.insert(krate, hash);
+    hash
+}
+
+fn crate_files(db: &RootDatabase, krate: Crate) -> Vec<FileId> {
+    let mut files: Vec<_> = krate
+        .modules(db)
+        .into_iter()
+        .map(|module| module.definition_source(db).file_id.original_file(db))
+        .collect();
+    files.sort_unstable_by_key(|it| it.0);
+    files.dedup();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ide::AnalysisHost;
+    use ide_db::base_db::fixture::ChangeFixture;
+
+    use super::*;
+
+    #[test]
+    fn crate_hash_changes_with_dependencies() {
+        let mut host = AnalysisHost::default();
+        let change_fixture = ChangeFixture::parse(
+            r#"
+//- /main.rs crate:main deps:dep new_source_root:local
+fn main() {}
+//- /dep/lib.rs crate:dep new_source_root:local
+pub fn dep() {}
+//- /dep/included.rs
+pub fn included() {}
+//- /other/lib.rs crate:other new_source_root:local cfg:feature=a
+pub fn other() {}
+//- /other2/lib.rs crate:other2 new_source_root:local cfg:feature=b
+pub fn other() {}
+"#,
+        );
+        let included_file = change_fixture.files[2];
+        host.raw_database_mut().apply_change(change_fixture.change);
+
+        let hashes = |host: &AnalysisHost| {
+            let db = host.raw_database();
+            let mut memo = FxHashMap::default();
+            Crate::all(db)
+                .into_iter()
+                .map(|krate| {
+                    let name = krate.display_name(db).unwrap().to_string();
+                    (name, crate_hash(db, krate, &mut memo))
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let before = hashes(&host);
+
+        // Only the cfg options tell these two crates apart.
+        assert_ne!(before["other"], before["other2"]);
+
+        let mut change = ide::Change::new();
+        change.change_file(included_file, Some(Arc::new("pub fn included2() {}".to_string())));
+        host.apply_change(change);
+        let after = hashes(&host);
+
+        assert_ne!(before["dep"], after["dep"]);
+        assert_ne!(before["main"], after["main"]);
+        assert_eq!(before["other"], after["other"]);
+    }
+}
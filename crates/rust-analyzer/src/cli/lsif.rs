@@ -1,22 +1,28 @@
 //! LSIF (language server index format) generator
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::time::Instant;
 
 use ide::{
-    Analysis, FileId, FileRange, MonikerKind, PackageInformation, RootDatabase, StaticIndex,
-    StaticIndexedFile, TokenId, TokenStaticData,
+    Analysis, FileId, FileRange, PackageInformation, RootDatabase, StaticIndex, StaticIndexedFile,
+    TokenId, TokenStaticData,
 };
 use ide_db::LineIndexDatabase;
 
 use ide_db::base_db::salsa::{self, ParallelDatabase};
+use ide_db::base_db::SourceDatabaseExt;
 use lsp_types::{self, lsif};
 use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace};
+use rustc_hash::FxHashSet;
 use vfs::{AbsPathBuf, Vfs};
 
 use crate::cli::{
     flags,
+    index_manifest::{IndexManifest, IndexPlan},
     load_cargo::{load_workspace, LoadCargoConfig},
     Result,
 };
@@ -40,6 +46,7 @@ struct LsifManager<'a> {
     analysis: &'a Analysis,
     db: &'a RootDatabase,
     vfs: &'a Vfs,
+    out: &'a mut dyn Write,
 }
 
 #[derive(Clone, Copy)]
@@ -52,7 +59,12 @@ impl From<Id> for lsp_types::NumberOrString {
 }
 
 impl LsifManager<'_> {
-    fn new<'a>(analysis: &'a Analysis, db: &'a RootDatabase, vfs: &'a Vfs) -> LsifManager<'a> {
+    fn new<'a>(
+        analysis: &'a Analysis,
+        db: &'a RootDatabase,
+        vfs: &'a Vfs,
+        out: &'a mut dyn Write,
+    ) -> LsifManager<'a> {
         LsifManager {
             count: 0,
             token_map: HashMap::default(),
@@ -62,6 +74,7 @@ impl LsifManager<'_> {
             analysis,
             db,
             vfs,
+            out,
         }
     }
 
@@ -80,9 +93,113 @@ impl LsifManager<'_> {
         self.add(lsif::Element::Edge(edge))
     }
 
-    // FIXME: support file in addition to stdout here
-    fn emit(&self, data: &str) {
-        println!("{}", data);
+    fn emit(&mut self, data: &str) {
+        writeln!(self.out, "{}", data).unwrap();
+    }
+
+    /// Takes over the documents of a previous dump for which `keep` returns true, along with
+    /// the ranges and results attached to them. The elements of this run are numbered after
+    /// the previous ones and attach their ranges to the documents taken over.
+    fn add_previous(&mut self, dump: &str, keep: impl Fn(FileId) -> bool) -> Result<()> {
+        let entries = dump
+            .lines()
+            .filter(|it| !it.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<serde_json::Value>>>()?;
+        let id = |it: &serde_json::Value| it.as_i64();
+        let ids = |it: &serde_json::Value| -> Vec<i64> {
+            match (it.get("inV").and_then(id), it.get("inVs")) {
+                (Some(in_v), _) => vec![in_v],
+                (None, Some(in_vs)) => {
+                    in_vs.as_array().into_iter().flatten().filter_map(id).collect()
+                }
+                (None, None) => vec![],
+            }
+        };
+        let is = |it: &serde_json::Value, ty: &str, label: &str| {
+            it["type"] == ty && it["label"] == label
+        };
+
+        let mut dropped = FxHashSet::default();
+        for entry in &entries {
+            if !is(entry, "vertex", "document") {
+                continue;
+            }
+            let entry_id = match id(&entry["id"]) {
+                Some(it) => it,
+                None => continue,
+            };
+            let file_id = entry["uri"]
+                .as_str()
+                .and_then(|it| lsp_types::Url::parse(it).ok())
+                .and_then(|it| it.to_file_path().ok())
+                .and_then(|it| AbsPathBuf::try_from(it).ok())
+                .and_then(|it| self.vfs.file_id(&it.into()))
+                .filter(|&it| keep(it));
+            match file_id {
+                Some(file_id) => {
+                    self.file_map.insert(file_id, Id(entry_id as i32));
+                }
+                None => {
+                    dropped.insert(entry_id);
+                }
+            }
+        }
+        // The ranges and folding ranges of the dropped documents go with them.
+        for entry in &entries {
+            let from_dropped = entry["outV"].as_i64().map_or(false, |it| dropped.contains(&it));
+            if from_dropped
+                && (is(entry, "edge", "contains") || is(entry, "edge", "textDocument/foldingRange"))
+            {
+                dropped.extend(ids(entry));
+            }
+        }
+        // So do the result sets only used by those ranges, and their results.
+        let used_result_sets: FxHashSet<i64> = entries
+            .iter()
+            .filter(|it| is(it, "edge", "next"))
+            .filter(|it| it["outV"].as_i64().map_or(false, |it| !dropped.contains(&it)))
+            .flat_map(ids)
+            .collect();
+        let unused_result_sets: FxHashSet<i64> = entries
+            .iter()
+            .filter(|it| is(it, "vertex", "resultSet"))
+            .filter_map(|it| id(&it["id"]))
+            .filter(|it| !used_result_sets.contains(it))
+            .collect();
+        for entry in &entries {
+            if entry["type"] == "edge"
+                && entry["outV"].as_i64().map_or(false, |it| unused_result_sets.contains(&it))
+            {
+                dropped.extend(ids(entry));
+            }
+        }
+        dropped.extend(unused_result_sets);
+
+        let mut max_id = -1;
+        for mut entry in entries {
+            max_id = max_id.max(id(&entry["id"]).unwrap_or(-1));
+            if id(&entry["id"]).map_or(false, |it| dropped.contains(&it)) {
+                continue;
+            }
+            if entry["type"] == "edge" {
+                let touches_dropped = ["outV", "inV", "document"]
+                    .iter()
+                    .any(|key| entry[*key].as_i64().map_or(false, |it| dropped.contains(&it)));
+                if touches_dropped {
+                    continue;
+                }
+                if let Some(in_vs) = entry.get_mut("inVs").and_then(|it| it.as_array_mut()) {
+                    in_vs.retain(|it| id(it).map_or(true, |it| !dropped.contains(&it)));
+                    if in_vs.is_empty() {
+                        continue;
+                    }
+                }
+            }
+            self.emit(&entry.to_string());
+        }
+        self.count = max_id as i32 + 1;
+        Ok(())
     }
 
     fn get_token_id(&mut self, id: TokenId) -> Id {
@@ -105,8 +222,8 @@ impl LsifManager<'_> {
                 manager: "cargo".to_string(),
                 uri: None,
                 content: None,
-                repository: Some(lsif::Repository {
-                    url: pi.repo,
+                repository: pi.repo.map(|url| lsif::Repository {
+                    url,
                     r#type: "git".to_string(),
                     commit_id: None,
                 }),
@@ -171,16 +288,8 @@ impl LsifManager<'_> {
             }));
         }
         if let Some(moniker) = token.moniker {
-            let package_id = self.get_package_id(moniker.package_information);
-            let moniker_id = self.add_vertex(lsif::Vertex::Moniker(lsp_types::Moniker {
-                scheme: "rust-analyzer".to_string(),
-                identifier: moniker.identifier.to_string(),
-                unique: lsp_types::UniquenessLevel::Scheme,
-                kind: Some(match moniker.kind {
-                    MonikerKind::Import => lsp_types::MonikerKind::Import,
-                    MonikerKind::Export => lsp_types::MonikerKind::Export,
-                }),
-            }));
+            let package_id = self.get_package_id(moniker.package_information.clone());
+            let moniker_id = self.add_vertex(lsif::Vertex::Moniker(to_proto::moniker(moniker)));
             self.add_edge(lsif::Edge::PackageInformation(lsif::EdgeData {
                 in_v: package_id.into(),
                 out_v: moniker_id.into(),
@@ -292,25 +401,74 @@ impl flags::Lsif {
         let db = host.raw_database();
         let analysis = host.analysis();
 
-        let si = StaticIndex::compute(&analysis);
+        let rootpath = path.normalize();
+        let relative_path = |file_id: FileId| {
+            let path = vfs.file_path(file_id);
+            let path = path.as_path()?;
+            Some(path.strip_prefix(&rootpath)?.as_ref().to_str()?.to_string())
+        };
+        // Incremental runs need a previous dump to merge with, so they don't apply to stdout.
+        let previous_manifest = match &self.output {
+            Some(output) => IndexManifest::read(self.manifest.as_deref(), output)?,
+            None => None,
+        };
+        let plan = IndexPlan::new(
+            db,
+            relative_path,
+            previous_manifest.as_ref(),
+            &self.crate_name,
+            &self.file,
+        );
+        let previous_dump = match (&previous_manifest, &self.output) {
+            (Some(_), Some(output)) => Some(std::fs::read_to_string(output)?),
+            _ => None,
+        };
+        eprintln!(
+            "Indexing {} files, reusing {} files from the previous dump",
+            plan.reindexed_files.len(),
+            plan.kept_files.len()
+        );
 
-        let mut lsif = LsifManager::new(&analysis, db, &vfs);
-        lsif.add_vertex(lsif::Vertex::MetaData(lsif::MetaData {
-            version: String::from("0.5.0"),
-            project_root: lsp_types::Url::from_file_path(path).unwrap(),
-            position_encoding: lsif::Encoding::Utf16,
-            tool_info: Some(lsp_types::lsif::ToolInfo {
-                name: "rust-analyzer".to_string(),
-                args: vec![],
-                version: Some(env!("REV").to_string()),
-            }),
-        }));
+        let si = StaticIndex::compute_filtered(&analysis, |file_id| {
+            plan.reindexed_files.contains(&file_id)
+        });
+
+        let stdout = io::stdout();
+        let mut out: Box<dyn Write> = match &self.output {
+            Some(output) => Box::new(BufWriter::new(File::create(output)?)),
+            None => Box::new(stdout.lock()),
+        };
+        let mut lsif = LsifManager::new(&analysis, db, &vfs, &mut *out);
+        match &previous_dump {
+            // Documents outside of the workspace, like those of dependencies, are kept too.
+            Some(dump) => lsif.add_previous(dump, |file_id| {
+                plan.kept_files.contains(&file_id)
+                    || (!plan.reindexed_files.contains(&file_id)
+                        && db.source_root(db.file_source_root(file_id)).is_library)
+            })?,
+            None => {
+                lsif.add_vertex(lsif::Vertex::MetaData(lsif::MetaData {
+                    version: String::from("0.5.0"),
+                    project_root: lsp_types::Url::from_file_path(path).unwrap(),
+                    position_encoding: lsif::Encoding::Utf16,
+                    tool_info: Some(lsp_types::lsif::ToolInfo {
+                        name: "rust-analyzer".to_string(),
+                        args: vec![],
+                        version: Some(env!("REV").to_string()),
+                    }),
+                }));
+            }
+        }
         for file in si.files {
             lsif.add_file(file);
         }
         for (id, token) in si.tokens.iter() {
             lsif.add_token(id, token);
         }
+        out.flush()?;
+        if let Some(manifest) = &self.manifest {
+            plan.manifest.write(manifest)?;
+        }
         eprintln!("Generating LSIF finished in {:?}", now.elapsed());
         Ok(())
     }
@@ -0,0 +1,403 @@
+//! SCIP (SCIP Code Intelligence Protocol) generator
+
+use std::{collections::HashMap, env, path::PathBuf, time::Instant};
+
+use ide::{
+    FileId, FileRange, MonikerDescriptorKind, MonikerKind, MonikerResult, RootDatabase,
+    StaticIndex, StaticIndexedFile, TokenId, TokenStaticData,
+};
+use ide_db::LineIndexDatabase;
+use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace};
+use protobuf::Message;
+use rustc_hash::FxHashSet;
+use scip::types as scip_types;
+use vfs::AbsPathBuf;
+
+use crate::{
+    cli::{
+        flags,
+        index_manifest::{IndexManifest, IndexPlan},
+        load_cargo::{load_workspace, LoadCargoConfig},
+        Result,
+    },
+    line_index::{LineEndings, LineIndex, OffsetEncoding},
+    to_proto,
+};
+
+impl flags::Scip {
+    pub fn run(self) -> Result<()> {
+        eprintln!("Generating SCIP start...");
+        let now = Instant::now();
+        let cargo_config = CargoConfig::default();
+        let no_progress = &|_| ();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: true,
+            with_proc_macro: true,
+            prefill_caches: true,
+        };
+        let path = AbsPathBuf::assert(env::current_dir()?.join(&self.path));
+        let rootpath = path.normalize();
+        let manifest = ProjectManifest::discover_single(&path)?;
+
+        let workspace = ProjectWorkspace::load(manifest, &cargo_config, no_progress)?;
+
+        let (host, vfs, _) = load_workspace(workspace, &load_cargo_config)?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let relative_path = |file_id: FileId| {
+            let path = vfs.file_path(file_id);
+            let path = path.as_path()?;
+            Some(path.strip_prefix(&rootpath)?.as_ref().to_str()?.to_string())
+        };
+
+        let output = self.output.unwrap_or_else(|| PathBuf::from("index.scip"));
+        let previous_manifest = IndexManifest::read(self.manifest.as_deref(), &output)?;
+        let plan = IndexPlan::new(
+            db,
+            relative_path,
+            previous_manifest.as_ref(),
+            &self.crate_name,
+            &self.file,
+        );
+        let kept_files: FxHashSet<String> =
+            plan.kept_files.iter().filter_map(|&file_id| relative_path(file_id)).collect();
+        eprintln!(
+            "Indexing {} files, reusing {} files from the previous index",
+            plan.reindexed_files.len(),
+            kept_files.len()
+        );
+
+        let si = StaticIndex::compute_filtered(&analysis, |file_id| {
+            plan.reindexed_files.contains(&file_id)
+        });
+        let mut documents = compute_documents(db, si, relative_path);
+        if previous_manifest.is_some() {
+            let previous = scip_types::Index::parse_from_bytes(&std::fs::read(&output)?)?;
+            documents =
+                merge_documents(previous.documents, documents, |path| kept_files.contains(path));
+        }
+
+        let metadata = scip_types::Metadata {
+            version: scip_types::ProtocolVersion::UnspecifiedProtocolVersion.into(),
+            tool_info: Some(scip_types::ToolInfo {
+                name: "rust-analyzer".to_owned(),
+                version: env!("REV").to_owned(),
+                arguments: vec![],
+                ..Default::default()
+            })
+            .into(),
+            project_root: format!("file://{}", rootpath.display()),
+            text_document_encoding: scip_types::TextEncoding::UTF8.into(),
+            ..Default::default()
+        };
+        let index = scip_types::Index {
+            metadata: Some(metadata).into(),
+            documents,
+            external_symbols: vec![],
+            ..Default::default()
+        };
+
+        scip::write_message_to_file(&output, index).map_err(|err| {
+            anyhow::anyhow!("Failed to write SCIP index to {:?}: {}", output, err)
+        })?;
+        if let Some(manifest) = &self.manifest {
+            plan.manifest.write(manifest)?;
+        }
+
+        eprintln!("Generating SCIP finished {:?}", now.elapsed());
+        Ok(())
+    }
+}
+
+/// Combines freshly computed documents with the documents of a previous index for which
+/// `keep` returns true. Fresh documents take precedence.
+fn merge_documents(
+    previous: Vec<scip_types::Document>,
+    documents: Vec<scip_types::Document>,
+    keep: impl Fn(&str) -> bool,
+) -> Vec<scip_types::Document> {
+    let fresh: FxHashSet<String> = documents.iter().map(|it| it.relative_path.clone()).collect();
+    let mut res: Vec<_> = previous
+        .into_iter()
+        .filter(|it| keep(&it.relative_path) && !fresh.contains(&it.relative_path))
+        .chain(documents)
+        .collect();
+    res.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    res
+}
+
+/// Converts the static index into SCIP documents, sorted by their relative path.
+///
+/// Files for which `relative_path` returns `None` are left out of the index.
+fn compute_documents(
+    db: &RootDatabase,
+    si: StaticIndex,
+    relative_path: impl Fn(FileId) -> Option<String>,
+) -> Vec<scip_types::Document> {
+    let mut documents = Vec::new();
+    for StaticIndexedFile { file_id, tokens, .. } in si.files.iter() {
+        let relative_path = match relative_path(*file_id) {
+            Some(it) => it,
+            None => continue,
+        };
+        let line_index = LineIndex {
+            index: db.line_index(*file_id),
+            encoding: OffsetEncoding::Utf8,
+            endings: LineEndings::Unix,
+        };
+
+        let mut local_symbols: HashMap<TokenId, String> = HashMap::new();
+        let mut occurrences = Vec::new();
+        let mut symbols = Vec::new();
+        for &(range, id) in tokens {
+            let token = match si.tokens.get(id) {
+                Some(it) => it,
+                None => continue,
+            };
+            let symbol = match &token.moniker {
+                Some(moniker) if moniker.kind != MonikerKind::Local => moniker_to_symbol(moniker),
+                Some(_) => {
+                    let next_local = local_symbols.len();
+                    local_symbols
+                        .entry(id)
+                        .or_insert_with(|| format!("local {}", next_local))
+                        .clone()
+                }
+                // Builtin types and crate roots have no moniker, nor a definition to point to.
+                None => continue,
+            };
+
+            let is_definition = token.definition == Some(FileRange { file_id: *file_id, range });
+            if is_definition {
+                symbols.push(symbol_information(&symbol, token));
+            }
+
+            occurrences.push(scip_types::Occurrence {
+                range: text_range_to_scip_range(&line_index, range),
+                symbol,
+                symbol_roles: if is_definition {
+                    scip_types::SymbolRole::Definition as i32
+                } else {
+                    0
+                },
+                ..Default::default()
+            });
+        }
+
+        documents.push(scip_types::Document {
+            relative_path,
+            language: "rust".to_string(),
+            occurrences,
+            symbols,
+            ..Default::default()
+        });
+    }
+    documents.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    documents
+}
+
+fn symbol_information(symbol: &str, token: &TokenStaticData) -> scip_types::SymbolInformation {
+    let documentation = match &token.hover {
+        Some(hover) => vec![hover.markup.as_str().to_string()],
+        None => vec![],
+    };
+    let relationships = token
+        .implements
+        .iter()
+        .map(|moniker| scip_types::Relationship {
+            symbol: moniker_to_symbol(moniker),
+            is_implementation: true,
+            ..Default::default()
+        })
+        .collect();
+    scip_types::SymbolInformation {
+        symbol: symbol.to_string(),
+        documentation,
+        relationships,
+        ..Default::default()
+    }
+}
+
+fn text_range_to_scip_range(line_index: &LineIndex, range: ide::TextRange) -> Vec<i32> {
+    let lsp_types::Range { start, end } = to_proto::range(line_index, range);
+    if start.line == end.line {
+        vec![start.line as i32, start.character as i32, end.character as i32]
+    } else {
+        vec![start.line as i32, start.character as i32, end.line as i32, end.character as i32]
+    }
+}
+
+fn moniker_to_symbol(moniker: &MonikerResult) -> String {
+    let package = &moniker.package_information;
+    let descriptors = moniker
+        .identifier
+        .description
+        .iter()
+        .map(|desc| scip_types::Descriptor {
+            name: desc.name.to_string(),
+            disambiguator: String::new(),
+            suffix: descriptor_suffix(desc.desc).into(),
+            ..Default::default()
+        })
+        .collect();
+    scip::symbol::format_symbol(scip_types::Symbol {
+        scheme: "rust-analyzer".to_string(),
+        package: Some(scip_types::Package {
+            manager: "cargo".to_string(),
+            name: package.name.clone(),
+            version: package.version.clone(),
+            ..Default::default()
+        })
+        .into(),
+        descriptors,
+        ..Default::default()
+    })
+}
+
+fn descriptor_suffix(kind: MonikerDescriptorKind) -> scip_types::descriptor::Suffix {
+    use scip_types::descriptor::Suffix;
+    match kind {
+        MonikerDescriptorKind::Namespace => Suffix::Namespace,
+        MonikerDescriptorKind::Type => Suffix::Type,
+        MonikerDescriptorKind::Term => Suffix::Term,
+        MonikerDescriptorKind::Method => Suffix::Method,
+        MonikerDescriptorKind::TypeParameter => Suffix::TypeParameter,
+        MonikerDescriptorKind::Parameter => Suffix::Parameter,
+        MonikerDescriptorKind::Macro => Suffix::Macro,
+        MonikerDescriptorKind::Meta => Suffix::Meta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use ide::AnalysisHost;
+    use ide_db::base_db::{fixture::ChangeFixture, SourceDatabaseExt};
+    use itertools::Itertools;
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let mut host = AnalysisHost::default();
+        let change_fixture = ChangeFixture::parse(ra_fixture);
+        host.raw_database_mut().apply_change(change_fixture.change);
+        let analysis = host.analysis();
+        let db = host.raw_database();
+        let si = StaticIndex::compute(&analysis);
+
+        let documents = compute_documents(db, si, |file_id| {
+            let source_root = db.source_root(db.file_source_root(file_id));
+            let path = source_root.path_for_file(&file_id)?.to_string();
+            Some(path.trim_start_matches('/').to_string())
+        });
+
+        let mut actual = String::new();
+        for document in documents {
+            actual += &format!("// {}\n", document.relative_path);
+            for occurrence in &document.occurrences {
+                let role = if occurrence.symbol_roles != 0 { " def" } else { "" };
+                actual += &format!("{:?} {}{}\n", occurrence.range, occurrence.symbol, role);
+            }
+            for symbol in &document.symbols {
+                let implements =
+                    symbol.relationships.iter().map(|it| it.symbol.as_str()).join(", ");
+                actual += &format!("symbol {} docs:{}", symbol.symbol, symbol.documentation.len());
+                if !implements.is_empty() {
+                    actual += &format!(" implements: {}", implements);
+                }
+                actual += "\n";
+            }
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn basic() {
+        check(
+            r#"
+//- /lib.rs crate:main@CratesIo:0.1.0,https://a.b/main.git deps:foo
+use foo::module::func;
+fn main() {
+    let x = 92;
+    func(x);
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub mod module {
+    pub fn func(_x: i32) {}
+}
+"#,
+            expect![[r#"
+                // foo/lib.rs
+                [0, 8, 14] rust-analyzer cargo foo 0.1.0 module/ def
+                [1, 11, 15] rust-analyzer cargo foo 0.1.0 module/func(). def
+                [1, 16, 18] local 0 def
+                symbol rust-analyzer cargo foo 0.1.0 module/ docs:1
+                symbol rust-analyzer cargo foo 0.1.0 module/func(). docs:1
+                symbol local 0 docs:1
+                // lib.rs
+                [0, 9, 15] rust-analyzer cargo foo 0.1.0 module/
+                [0, 17, 21] rust-analyzer cargo foo 0.1.0 module/func().
+                [1, 3, 7] rust-analyzer cargo main 0.1.0 main(). def
+                [2, 8, 9] local 0 def
+                [3, 4, 8] rust-analyzer cargo foo 0.1.0 module/func().
+                [3, 9, 10] local 0
+                symbol rust-analyzer cargo main 0.1.0 main(). docs:1
+                symbol local 0 docs:1
+            "#]],
+        );
+    }
+
+    #[test]
+    fn trait_implementations() {
+        check(
+            r#"
+//- /lib.rs crate:main@CratesIo:0.1.0,https://a.b/main.git
+pub trait Shape {
+    fn area(&self) -> u32;
+}
+/// A square.
+pub struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 4 }
+}
+"#,
+            expect![[r##"
+                // lib.rs
+                [0, 10, 15] rust-analyzer cargo main 0.1.0 Shape# def
+                [1, 7, 11] rust-analyzer cargo main 0.1.0 Shape#area(). def
+                [1, 13, 17] local 0 def
+                [4, 11, 17] rust-analyzer cargo main 0.1.0 Square# def
+                [5, 5, 10] rust-analyzer cargo main 0.1.0 Shape#
+                [5, 15, 21] rust-analyzer cargo main 0.1.0 Square#
+                [6, 7, 11] rust-analyzer cargo main 0.1.0 Square#Shape#area(). def
+                [6, 13, 17] local 1 def
+                symbol rust-analyzer cargo main 0.1.0 Shape# docs:1
+                symbol rust-analyzer cargo main 0.1.0 Shape#area(). docs:1
+                symbol local 0 docs:1
+                symbol rust-analyzer cargo main 0.1.0 Square# docs:1 implements: rust-analyzer cargo main 0.1.0 Shape#
+                symbol rust-analyzer cargo main 0.1.0 Square#Shape#area(). docs:1 implements: rust-analyzer cargo main 0.1.0 Shape#area().
+                symbol local 1 docs:1
+            "##]],
+        );
+    }
+
+    #[test]
+    fn merge_keeps_unchanged_documents() {
+        let document = |path: &str, language: &str| scip_types::Document {
+            relative_path: path.to_string(),
+            language: language.to_string(),
+            ..Default::default()
+        };
+        let previous =
+            vec![document("a.rs", "old"), document("b.rs", "old"), document("gone.rs", "old")];
+        let fresh = vec![document("b.rs", "new"), document("c.rs", "new")];
+        let merged = merge_documents(previous, fresh, |path| path != "gone.rs");
+        let merged = merged
+            .iter()
+            .map(|it| format!("{} {}", it.relative_path, it.language))
+            .collect::<Vec<_>>();
+        assert_eq!(merged, vec!["a.rs old", "b.rs new", "c.rs new"]);
+    }
+}
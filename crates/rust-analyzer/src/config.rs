@@ -7,12 +7,13 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
-use std::{ffi::OsString, iter, path::PathBuf};
+use std::{ffi::OsString, fs, iter, path::PathBuf};
 
 use flycheck::FlycheckConfig;
 use ide::{
     AssistConfig, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode, HighlightRelatedConfig,
     HoverConfig, HoverDocFormat, InlayHintsConfig, JoinLinesConfig, Snippet, SnippetScope,
+    TraitImplBody, TypingConfig,
 };
 use ide_db::{
     imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -160,6 +161,9 @@ config_data! {
                 "scope": "expr"
             }
         }"#,
+        /// JSON files with additional custom completion snippets, in the same format as
+        /// `#rust-analyzer.completion.snippets#`. Relative paths are resolved against the workspace root.
+        completion_snippetFiles: Vec<PathBuf>  = "[]",
         /// Whether to show postfix snippets like `dbg`, `if`, `not`, etc.
         completion_postfix_enable: bool         = "true",
         /// Toggles the additional completions that automatically add imports when completed.
@@ -170,6 +174,11 @@ config_data! {
         completion_autoself_enable: bool        = "true",
         /// Enables completions of private items and fields that are defined in the current workspace even if they are not visible at the current position.
         completion_privateEditable_enable: bool = "false",
+        /// Whether to synthesise expressions of the expected type from the locals, functions and
+        /// constructors in scope. This can be slow in large scopes.
+        completion_termSearch_enable: bool      = "false",
+        /// What to put into the body of trait methods completed inside of a trait implementation.
+        completion_traitImplBody: TraitImplBodyDef = "\"skeleton\"",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -320,6 +329,10 @@ config_data! {
         /// Additional arguments to be passed to cargo for runnables such as
         /// tests or binaries. For example, it may be `--release`.
         runnables_cargoExtraArgs: Vec<String>   = "[]",
+        /// Additional attribute paths marking a function as a test, for example
+        /// `rstest::rstest`. Attributes are matched both by how they are written and by the
+        /// path of the macro they resolve to.
+        runnables_extraTestAttributes: Vec<String> = "[]",
 
         /// Path to the Cargo.toml of the rust compiler workspace, for usage in rustc_private
         /// projects, or "discover" to try to automatically find it if the `rustc-dev` component
@@ -341,6 +354,16 @@ config_data! {
         /// available on a nightly build.
         rustfmt_enableRangeFormatting: bool = "false",
 
+        /// Whether to insert a closing `>` when typing `<` to start the generic arguments of a
+        /// path.
+        typing_autoClosingAngleBrackets_enable: bool = "false",
+        /// Whether to insert the closing `|` when typing `|` to start the parameters of a closure.
+        typing_autoClosingClosureParams_enable: bool = "true",
+        /// Whether to add a missing `;` to a `let` statement when starting a new line after it.
+        typing_letSemicolonOnNewline_enable: bool = "false",
+        /// Whether to turn `=` typed after the pattern of a match arm into `=> ,`.
+        typing_matchArmArrow_enable: bool = "true",
+
         /// Workspace symbol search scope.
         workspace_symbol_search_scope: WorkspaceSymbolSearchScopeDef = "\"workspace\"",
         /// Workspace symbol search kind.
@@ -488,6 +511,7 @@ pub struct ClientCommandsConfig {
     pub show_reference: bool,
     pub goto_location: bool,
     pub trigger_parameter_hints: bool,
+    pub completion_accepted: bool,
 }
 
 impl Config {
@@ -517,7 +541,23 @@ impl Config {
                 .collect();
         self.data = ConfigData::from_json(json, &mut errors);
         self.snippets.clear();
-        for (name, def) in self.data.completion_snippets.iter() {
+        let mut snippet_defs: Vec<(String, SnippetDef)> =
+            self.data.completion_snippets.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        for path in &self.data.completion_snippetFiles {
+            let path = self.root_path.join(path);
+            let text = match fs::read_to_string(&path) {
+                Ok(it) => it,
+                Err(e) => {
+                    tracing::warn!("failed to read snippet file {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            match serde_json::from_str::<FxHashMap<String, SnippetDef>>(&text) {
+                Ok(defs) => snippet_defs.extend(defs),
+                Err(e) => errors.push((format!("completion.snippetFiles: {}", path.display()), e)),
+            }
+        }
+        for (name, def) in snippet_defs.iter() {
             if def.prefix.is_empty() && def.postfix.is_empty() {
                 continue;
             }
@@ -532,6 +572,7 @@ impl Config {
                 &def.body,
                 def.description.as_ref().unwrap_or(name),
                 &def.requires,
+                def.receiver_type.as_deref(),
                 scope,
             ) {
                 Some(snippet) => self.snippets.push(snippet),
@@ -846,6 +887,11 @@ impl Config {
             cargo_extra_args: self.data.runnables_cargoExtraArgs.clone(),
         }
     }
+    pub fn ide_runnables(&self) -> ide::RunnablesConfig {
+        ide::RunnablesConfig {
+            extra_test_attributes: self.data.runnables_extraTestAttributes.clone(),
+        }
+    }
     pub fn inlay_hints(&self) -> InlayHintsConfig {
         InlayHintsConfig {
             render_colons: self.data.inlayHints_renderColons,
@@ -881,6 +927,7 @@ impl Config {
                 && completion_item_edit_resolve(&self.caps),
             enable_self_on_the_fly: self.data.completion_autoself_enable,
             enable_private_editable: self.data.completion_privateEditable_enable,
+            enable_term_search: self.data.completion_termSearch_enable,
             add_call_parenthesis: self.data.completion_addCallParenthesis,
             add_call_argument_snippets: self.data.completion_addCallArgumentSnippets,
             insert_use: self.insert_use_config(),
@@ -896,6 +943,12 @@ impl Config {
                 false
             )),
             snippets: self.snippets.clone(),
+            trait_impl_body: match self.data.completion_traitImplBody {
+                TraitImplBodyDef::Skeleton => TraitImplBody::Skeleton,
+                TraitImplBodyDef::CopyDefault => TraitImplBody::CopyDefault,
+                TraitImplBodyDef::Delegate => TraitImplBody::Delegate,
+            },
+            recently_accepted: Vec::new(),
         }
     }
     pub fn assist(&self) -> AssistConfig {
@@ -913,6 +966,15 @@ impl Config {
             join_assignments: self.data.joinLines_joinAssignments,
         }
     }
+    pub fn typing(&self) -> TypingConfig {
+        TypingConfig {
+            close_angle_brackets: self.data.typing_autoClosingAngleBrackets_enable,
+            close_closure_params: self.data.typing_autoClosingClosureParams_enable,
+            match_arm_arrow: self.data.typing_matchArmArrow_enable,
+            let_semicolon_on_newline: self.data.typing_letSemicolonOnNewline_enable,
+            snippet_cap: SnippetCap::new(self.experimental("snippetTextEdit")),
+        }
+    }
     pub fn call_info_full(&self) -> bool {
         self.data.callInfo_full
     }
@@ -961,6 +1023,7 @@ impl Config {
                     HoverDocFormat::PlainText
                 }
             }),
+            runnables: self.ide_runnables(),
         }
     }
 
@@ -1014,6 +1077,7 @@ impl Config {
             show_reference: get("rust-analyzer.showReferences"),
             goto_location: get("rust-analyzer.gotoLocation"),
             trigger_parameter_hints: get("editor.action.triggerParameterHints"),
+            completion_accepted: get("rust-analyzer.completionAccepted"),
         }
     }
 
@@ -1060,6 +1124,8 @@ struct SnippetDef {
     body: Vec<String>,
     #[serde(deserialize_with = "single_or_array")]
     requires: Vec<String>,
+    #[serde(rename = "receiverType")]
+    receiver_type: Option<String>,
     scope: SnippetScopeDef,
 }
 
@@ -1110,6 +1176,15 @@ pub enum ExprFillDefaultDef {
     Default,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum TraitImplBodyDef {
+    Skeleton,
+    #[serde(alias = "default")]
+    CopyDefault,
+    Delegate,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum ImportGranularityDef {
@@ -1326,6 +1401,15 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Fill missing expressions with reasonable defaults, `new` or `default` constructors."
             ],
         },
+        "TraitImplBodyDef" => set! {
+            "type": "string",
+            "enum": ["skeleton", "default", "delegate"],
+            "enumDescriptions": [
+                "Leave the body empty.",
+                "Copy the default body of the trait method, if there is one.",
+                "Forward the call to a field of `Self` that implements the same trait, if there is one."
+            ],
+        },
         "ImportGranularityDef" => set! {
             "type": "string",
             "enum": ["preserve", "crate", "module", "item"],
@@ -1481,6 +1565,34 @@ mod tests {
         ensure_file_contents(&docs_path, &expected);
     }
 
+    #[test]
+    fn loads_snippet_files() {
+        let dir = std::env::temp_dir().join(format!("ra-snippet-files-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("snippets.json"),
+            r#"{
+                "ok_or": {
+                    "postfix": "ok_or",
+                    "body": "${receiver}.ok_or(${error:()})",
+                    "receiverType": "core::option::Option"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut config =
+            Config::new(AbsPathBuf::assert(dir.clone()), ClientCapabilities::default());
+        config
+            .update(serde_json::json!({ "completion": { "snippetFiles": ["snippets.json"] } }))
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let snippets = config.completion().snippets;
+        let ok_or = snippets.iter().find(|it| &*it.postfix_triggers[0] == "ok_or").unwrap();
+        assert_eq!(ok_or.postfix_snippet("x"), "x.ok_or(${1:()})");
+    }
+
     fn remove_ws(text: &str) -> String {
         text.replace(char::is_whitespace, "")
     }
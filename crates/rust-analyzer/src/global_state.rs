@@ -3,7 +3,7 @@
 //!
 //! Each tick provides an immutable snapshot of the state as `WorldSnapshot`.
 
-use std::{sync::Arc, time::Instant};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
@@ -14,9 +14,10 @@ use parking_lot::{Mutex, RwLock};
 use proc_macro_api::ProcMacroServer;
 use project_model::{CargoWorkspace, ProjectWorkspace, Target, WorkspaceBuildScripts};
 use rustc_hash::FxHashMap;
-use vfs::AnchoredPathBuf;
+use vfs::{AbsPathBuf, AnchoredPathBuf};
 
 use crate::{
+    cargo_toml,
     config::Config,
     diagnostics::{CheckFixes, DiagnosticCollection},
     from_proto,
@@ -47,6 +48,9 @@ pub(crate) type ReqQueue = lsp_server::ReqQueue<(String, Instant), ReqHandler>;
 /// incremental salsa database.
 ///
 /// Note that this struct has more than one impl in various modules!
+/// How many recently accepted completions are remembered to boost their ranking.
+const RECENT_COMPLETIONS_LIMIT: usize = 32;
+
 pub(crate) struct GlobalState {
     sender: Sender<lsp_server::Message>,
     req_queue: ReqQueue,
@@ -57,6 +61,8 @@ pub(crate) struct GlobalState {
     pub(crate) diagnostics: DiagnosticCollection,
     pub(crate) mem_docs: MemDocs,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    /// Labels of the recently accepted completions, most recent first.
+    pub(crate) recent_completions: Arc<Mutex<VecDeque<String>>>,
     pub(crate) shutdown_requested: bool,
     pub(crate) proc_macro_changed: bool,
     pub(crate) last_reported_status: Option<lsp_ext::ServerStatusParams>,
@@ -113,6 +119,7 @@ pub(crate) struct GlobalStateSnapshot {
     pub(crate) check_fixes: CheckFixes,
     mem_docs: MemDocs,
     pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) recent_completions: Arc<Mutex<VecDeque<String>>>,
     vfs: Arc<RwLock<(vfs::Vfs, FxHashMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
 }
@@ -147,6 +154,7 @@ impl GlobalState {
             diagnostics: Default::default(),
             mem_docs: MemDocs::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            recent_completions: Arc::new(Default::default()),
             shutdown_requested: false,
             proc_macro_changed: false,
             last_reported_status: None,
@@ -244,6 +252,7 @@ impl GlobalState {
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
             mem_docs: self.mem_docs.clone(),
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
+            recent_completions: Arc::clone(&self.recent_completions),
         }
     }
 
@@ -296,6 +305,14 @@ impl GlobalState {
         }
     }
 
+    /// Remembers a completion the user accepted, to rank it higher in the next completions.
+    pub(crate) fn completion_accepted(&mut self, label: String) {
+        let mut recent = self.recent_completions.lock();
+        recent.retain(|it| *it != label);
+        recent.push_front(label);
+        recent.truncate(RECENT_COMPLETIONS_LIMIT);
+    }
+
     fn send(&mut self, message: lsp_server::Message) {
         self.sender.send(message).unwrap()
     }
@@ -323,6 +340,13 @@ impl GlobalStateSnapshot {
         Ok(res)
     }
 
+    /// The path of `file_id` if it is a `Cargo.toml` rather than a Rust file.
+    pub(crate) fn manifest_path(&self, file_id: FileId) -> Option<AbsPathBuf> {
+        let path = self.vfs.read().0.file_path(file_id);
+        let path = path.as_path()?;
+        cargo_toml::is_manifest(path.as_ref()).then(|| path.to_path_buf())
+    }
+
     pub(crate) fn url_file_version(&self, url: &Url) -> Option<i32> {
         let path = from_proto::vfs_path(url).ok()?;
         Some(self.mem_docs.get(&path)?.version)
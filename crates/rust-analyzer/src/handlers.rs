@@ -18,21 +18,22 @@ use lsp_server::ErrorCode;
 use lsp_types::{
     CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
-    CodeLens, CompletionItem, Diagnostic, DiagnosticTag, DocumentFormattingParams, FoldingRange,
-    FoldingRangeParams, HoverContents, Location, LocationLink, NumberOrString, Position,
-    PrepareRenameResponse, Range, RenameParams, SemanticTokensDeltaParams,
-    SemanticTokensFullDeltaResult, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, SymbolTag,
-    TextDocumentIdentifier, Url, WorkspaceEdit,
+    CodeLens, CompletionItem, CompletionTextEdit, Diagnostic, DiagnosticTag,
+    DocumentFormattingParams, FoldingRange, FoldingRangeParams, HoverContents, Location,
+    LocationLink, NumberOrString, Position, PrepareRenameResponse, Range, RenameParams,
+    SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::{ManifestPath, ProjectWorkspace, TargetKind};
 use serde_json::json;
 use stdx::{format_to, never};
 use syntax::{algo, ast, AstNode, TextRange, TextSize, T};
-use vfs::AbsPathBuf;
+use vfs::{AbsPath, AbsPathBuf};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
+    cargo_toml,
     config::RustfmtConfig,
     diff::diff,
     from_proto,
@@ -264,17 +265,27 @@ pub(crate) fn handle_on_enter(
 pub(crate) fn handle_on_type_formatting(
     snap: GlobalStateSnapshot,
     params: lsp_types::DocumentOnTypeFormattingParams,
-) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+) -> Result<Option<Vec<lsp_ext::SnippetTextEdit>>> {
     let _p = profile::span("handle_on_type_formatting");
     let mut position = from_proto::file_position(&snap, params.text_document_position)?;
     let line_index = snap.file_line_index(position.file_id)?;
 
+    let char_typed = params.ch.chars().next().unwrap_or('\0');
+    let text = snap.analysis.file_text(position.file_id)?;
+
     // in `ide`, the `on_type` invariant is that
     // `text.char_at(position) == typed_char`.
-    position.offset -= TextSize::of('.');
-    let char_typed = params.ch.chars().next().unwrap_or('\0');
+    if char_typed == '\n' {
+        // The editor may have already indented the new line, so the position is not necessarily
+        // right after the newline.
+        match text[..usize::from(position.offset)].rfind('\n') {
+            Some(offset) => position.offset = TextSize::from(offset as u32),
+            None => return Ok(None),
+        }
+    } else {
+        position.offset -= TextSize::of('.');
+    }
 
-    let text = snap.analysis.file_text(position.file_id)?;
     if stdx::never!(!text[usize::from(position.offset)..].starts_with(char_typed)) {
         return Ok(None);
     }
@@ -287,16 +298,16 @@ pub(crate) fn handle_on_type_formatting(
         return Ok(None);
     }
 
-    let edit = snap.analysis.on_char_typed(position, char_typed)?;
+    let edit = snap.analysis.on_char_typed(&snap.config.typing(), position, char_typed)?;
     let edit = match edit {
         Some(it) => it,
         None => return Ok(None),
     };
 
     // This should be a single-file edit
-    let (_, edit) = edit.source_file_edits.into_iter().next().unwrap();
+    let (_, text_edit) = edit.source_file_edits.into_iter().next().unwrap();
 
-    let change = to_proto::text_edit_vec(&line_index, edit);
+    let change = to_proto::snippet_text_edit_vec(&line_index, edit.is_snippet, text_edit);
     Ok(Some(change))
 }
 
@@ -600,6 +611,19 @@ pub(crate) fn handle_goto_type_definition(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_moniker(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::MonikerParams,
+) -> Result<Option<Vec<lsp_types::Moniker>>> {
+    let _p = profile::span("handle_moniker");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let monikers = match snap.analysis.moniker(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    Ok(Some(monikers.info.into_iter().map(to_proto::moniker).collect()))
+}
+
 pub(crate) fn handle_parent_module(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentPositionParams,
@@ -688,7 +712,7 @@ pub(crate) fn handle_runnables(
     };
 
     let mut res = Vec::new();
-    for runnable in snap.analysis.runnables(file_id)? {
+    for runnable in snap.analysis.runnables(&snap.config.ide_runnables(), file_id)? {
         if let Some(offset) = offset {
             if !runnable.nav.full_range.contains_inclusive(offset) {
                 continue;
@@ -765,7 +789,7 @@ pub(crate) fn handle_related_tests(
     let _p = profile::span("handle_related_tests");
     let position = from_proto::file_position(&snap, params)?;
 
-    let tests = snap.analysis.related_tests(position, None)?;
+    let tests = snap.analysis.related_tests(&snap.config.ide_runnables(), position, None)?;
     let mut res = Vec::new();
     for it in tests {
         if let Ok(runnable) = to_proto::runnable(&snap, it) {
@@ -783,6 +807,9 @@ pub(crate) fn handle_completion(
     let _p = profile::span("handle_completion");
     let text_document_position = params.text_document_position.clone();
     let position = from_proto::file_position(&snap, params.text_document_position)?;
+    if let Some(manifest) = snap.manifest_path(position.file_id) {
+        return handle_manifest_completion(&snap, &manifest, position);
+    }
     let completion_triggered_after_single_colon = {
         let mut res = false;
         if let Some(ctx) = params.context {
@@ -802,7 +829,10 @@ pub(crate) fn handle_completion(
         return Ok(None);
     }
 
-    let completion_config = &snap.config.completion();
+    let completion_config = &ide::CompletionConfig {
+        recently_accepted: snap.recent_completions.lock().iter().cloned().collect(),
+        ..snap.config.completion()
+    };
     let items = match snap.analysis.completions(completion_config, position)? {
         None => return Ok(None),
         Some(items) => items,
@@ -816,6 +846,35 @@ pub(crate) fn handle_completion(
     Ok(Some(completion_list.into()))
 }
 
+fn handle_manifest_completion(
+    snap: &GlobalStateSnapshot,
+    manifest: &AbsPath,
+    position: FilePosition,
+) -> Result<Option<lsp_types::CompletionResponse>> {
+    let text = snap.analysis.file_text(position.file_id)?;
+    let line_index = snap.file_line_index(position.file_id)?;
+    let data = cargo_toml::manifest_data(&snap.workspaces, manifest).unwrap_or_default();
+    let cargo_home = toolchain::cargo_home();
+    let registry = |prefix: &str| match &cargo_home {
+        Some(cargo_home) => cargo_toml::registry_crate_names(cargo_home, prefix),
+        None => Vec::new(),
+    };
+    let items = cargo_toml::completions(&text, position.offset, &data, &registry)
+        .into_iter()
+        .map(|item| CompletionItem {
+            label: item.label.clone(),
+            detail: item.detail,
+            text_edit: Some(CompletionTextEdit::Edit(lsp_types::TextEdit::new(
+                to_proto::range(&line_index, item.range),
+                item.label,
+            ))),
+            ..CompletionItem::default()
+        })
+        .collect();
+    let completion_list = lsp_types::CompletionList { is_incomplete: true, items };
+    Ok(Some(completion_list.into()))
+}
+
 pub(crate) fn handle_completion_resolve(
     snap: GlobalStateSnapshot,
     mut original_completion: CompletionItem,
@@ -916,6 +975,9 @@ pub(crate) fn handle_hover(
     };
 
     let file_range = from_proto::file_range(&snap, params.text_document, range)?;
+    if let Some(manifest) = snap.manifest_path(file_range.file_id) {
+        return handle_manifest_hover(&snap, &manifest, file_range);
+    }
     let info = match snap.analysis.hover(&snap.config.hover(), file_range)? {
         None => return Ok(None),
         Some(info) => info,
@@ -943,6 +1005,31 @@ pub(crate) fn handle_hover(
     Ok(Some(hover))
 }
 
+fn handle_manifest_hover(
+    snap: &GlobalStateSnapshot,
+    manifest: &AbsPath,
+    file_range: FileRange,
+) -> Result<Option<lsp_ext::Hover>> {
+    let text = snap.analysis.file_text(file_range.file_id)?;
+    let line_index = snap.file_line_index(file_range.file_id)?;
+    let data = cargo_toml::manifest_data(&snap.workspaces, manifest).unwrap_or_default();
+    let info = match cargo_toml::hover(&text, file_range.range.start(), &data) {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let hover = lsp_ext::Hover {
+        hover: lsp_types::Hover {
+            contents: HoverContents::Markup(lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: info.markdown,
+            }),
+            range: Some(to_proto::range(&line_index, info.range)),
+        },
+        actions: Vec::new(),
+    };
+    Ok(Some(hover))
+}
+
 pub(crate) fn handle_prepare_rename(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentPositionParams,
@@ -1206,6 +1293,7 @@ pub(crate) fn handle_code_lens(
             annotate_references: lens_config.refs,
             annotate_method_references: lens_config.method_refs,
             annotate_enum_variant_references: lens_config.enum_variant_refs,
+            runnables: snap.config.ide_runnables(),
         },
         file_id,
     )?;
@@ -1281,6 +1369,38 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_change_signature");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let params = params
+        .parameters
+        .into_iter()
+        .map(|param| match param.old_index {
+            Some(index) => Ok(ide::ParamChange::Existing {
+                index: index as usize,
+                name: param.name,
+                ty: param.ty,
+            }),
+            None => match (param.name, param.ty, param.default_value) {
+                (Some(name), Some(ty), Some(default)) => {
+                    Ok(ide::ParamChange::Added { name, ty, default })
+                }
+                _ => Err(invalid_params_error(
+                    "new parameters need a name, a type and a default value".to_owned(),
+                )),
+            },
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let source_change = snap
+        .analysis
+        .change_signature(position, ide::SignatureChange { params })?
+        .map_err(invalid_params_error)?;
+    to_proto::workspace_edit(&snap, source_change)
+}
+
 pub(crate) fn publish_diagnostics(
     snap: &GlobalStateSnapshot,
     file_id: FileId,
@@ -1288,6 +1408,27 @@ pub(crate) fn publish_diagnostics(
     let _p = profile::span("publish_diagnostics");
     let line_index = snap.file_line_index(file_id)?;
 
+    if let Some(manifest) = snap.manifest_path(file_id) {
+        let text = snap.analysis.file_text(file_id)?;
+        // Without metadata we can't tell which features exist.
+        let data = match cargo_toml::manifest_data(&snap.workspaces, &manifest) {
+            Some(it) => it,
+            None => return Ok(Vec::new()),
+        };
+        let diagnostics = cargo_toml::diagnostics(&text, &data)
+            .into_iter()
+            .map(|d| Diagnostic {
+                range: to_proto::range(&line_index, d.range),
+                severity: Some(lsp_types::DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("unknown-feature".to_string())),
+                source: Some("rust-analyzer".to_string()),
+                message: d.message,
+                ..Diagnostic::default()
+            })
+            .collect();
+        return Ok(diagnostics);
+    }
+
     let diagnostics: Vec<Diagnostic> = snap
         .analysis
         .diagnostics(&snap.config.diagnostics(), AssistResolveStrategy::None, file_id)?
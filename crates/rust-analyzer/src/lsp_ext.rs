@@ -326,6 +326,18 @@ pub enum Health {
     Error,
 }
 
+pub enum CompletionAccepted {}
+
+impl Notification for CompletionAccepted {
+    type Params = CompletionAcceptedParams;
+    const METHOD: &'static str = "rust-analyzer/completionAccepted";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CompletionAcceptedParams {
+    pub label: String,
+}
+
 pub enum CodeActionRequest {}
 
 impl Request for CodeActionRequest {
@@ -406,6 +418,14 @@ pub struct SnippetTextEdit {
     pub annotation_id: Option<lsp_types::ChangeAnnotationIdentifier>,
 }
 
+pub enum OnTypeFormatting {}
+
+impl Request for OnTypeFormatting {
+    type Params = lsp_types::DocumentOnTypeFormattingParams;
+    type Result = Option<Vec<SnippetTextEdit>>;
+    const METHOD: &'static str = "textDocument/onTypeFormatting";
+}
+
 pub enum HoverRequest {}
 
 impl Request for HoverRequest {
@@ -489,6 +509,36 @@ pub fn supports_utf8(caps: &lsp_types::ClientCapabilities) -> bool {
     caps.offset_encoding.as_deref().unwrap_or_default().iter().any(|it| it == "utf-8")
 }
 
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "experimental/changeSignature";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+    /// The new parameters, not counting `self`.
+    pub parameters: Vec<SignatureParameter>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureParameter {
+    /// Index of the parameter in the current signature, not counting `self`. Absent for new
+    /// parameters.
+    pub old_index: Option<u32>,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    /// The argument callers pass for a new parameter.
+    pub default_value: Option<String>,
+}
+
 pub enum MoveItem {}
 
 impl Request for MoveItem {
@@ -572,6 +572,12 @@ impl GlobalState {
             return Ok(());
         }
 
+        // Only completion and hover know about `Cargo.toml`, everything else expects Rust.
+        if is_manifest_request(&req) {
+            self.respond(lsp_server::Response::new_ok(req.id, serde_json::Value::Null));
+            return Ok(());
+        }
+
         RequestDispatcher { req: Some(req), global_state: self }
             .on_sync_mut::<lsp_ext::ReloadWorkspace>(|s, ()| {
                 s.fetch_workspaces_queue.request_op();
@@ -604,11 +610,12 @@ impl GlobalState {
             .on::<lsp_ext::OpenCargoToml>(handlers::handle_open_cargo_toml)
             .on::<lsp_ext::MoveItem>(handlers::handle_move_item)
             .on::<lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
-            .on::<lsp_types::request::OnTypeFormatting>(handlers::handle_on_type_formatting)
+            .on::<lsp_ext::OnTypeFormatting>(handlers::handle_on_type_formatting)
             .on::<lsp_types::request::DocumentSymbolRequest>(handlers::handle_document_symbol)
             .on::<lsp_types::request::GotoDefinition>(handlers::handle_goto_definition)
             .on::<lsp_types::request::GotoDeclaration>(handlers::handle_goto_declaration)
             .on::<lsp_types::request::GotoImplementation>(handlers::handle_goto_implementation)
+            .on::<lsp_types::request::MonikerRequest>(handlers::handle_moniker)
             .on::<lsp_types::request::GotoTypeDefinition>(handlers::handle_goto_type_definition)
             .on::<lsp_types::request::Completion>(handlers::handle_completion)
             .on::<lsp_types::request::ResolveCompletionItem>(handlers::handle_completion_resolve)
@@ -640,6 +647,7 @@ impl GlobalState {
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
             .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<lsp_ext::ChangeSignature>(handlers::handle_change_signature)
             .finish();
         Ok(())
     }
@@ -777,6 +785,10 @@ impl GlobalState {
                 }
                 Ok(())
             })?
+            .on::<lsp_ext::CompletionAccepted>(|this, params| {
+                this.completion_accepted(params.label);
+                Ok(())
+            })?
             .finish();
         Ok(())
     }
@@ -809,3 +821,13 @@ impl GlobalState {
         })
     }
 }
+
+fn is_manifest_request(req: &Request) -> bool {
+    use lsp_types::request::{Completion, Request as _};
+
+    if req.method == Completion::METHOD || req.method == lsp_ext::HoverRequest::METHOD {
+        return false;
+    }
+    let uri = req.params.pointer("/textDocument/uri").and_then(|it| it.as_str());
+    uri.map_or(false, |uri| uri.ends_with("/Cargo.toml"))
+}
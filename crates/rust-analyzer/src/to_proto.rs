@@ -9,8 +9,9 @@ use ide::{
     Annotation, AnnotationKind, Assist, AssistKind, CallInfo, Cancellable, CompletionItem,
     CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
     Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel, InlayHint,
-    InlayKind, Markup, NavigationTarget, ReferenceCategory, RenameError, Runnable, Severity,
-    SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
+    InlayKind, Markup, MonikerKind, MonikerResult, NavigationTarget, ReferenceCategory,
+    RenameError, Runnable, Severity, SourceChange, StructureNodeKind, SymbolKind, TextEdit,
+    TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
@@ -271,7 +272,13 @@ fn completion_item(
         lsp_item.tags = Some(vec![lsp_types::CompletionItemTag::DEPRECATED])
     }
 
-    if item.trigger_call_info() && config.client_commands().trigger_parameter_hints {
+    let client_commands = config.client_commands();
+    let trigger_parameter_hints =
+        item.trigger_call_info() && client_commands.trigger_parameter_hints;
+    if client_commands.completion_accepted {
+        lsp_item.command =
+            Some(command::completion_accepted(item.label(), trigger_parameter_hints));
+    } else if trigger_parameter_hints {
         lsp_item.command = Some(command::trigger_parameter_hints());
     }
 
@@ -958,6 +965,20 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn moniker(moniker: MonikerResult) -> lsp_types::Moniker {
+    let (kind, unique) = match moniker.kind {
+        MonikerKind::Import => (lsp_types::MonikerKind::Import, lsp_types::UniquenessLevel::Scheme),
+        MonikerKind::Export => (lsp_types::MonikerKind::Export, lsp_types::UniquenessLevel::Scheme),
+        MonikerKind::Local => (lsp_types::MonikerKind::Local, lsp_types::UniquenessLevel::Project),
+    };
+    lsp_types::Moniker {
+        scheme: "rust-analyzer".to_string(),
+        identifier: moniker.identifier.to_string(),
+        unique,
+        kind: Some(kind),
+    }
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -1220,6 +1241,23 @@ pub(crate) mod command {
             arguments: None,
         }
     }
+
+    /// Reports the acceptance of a completion back to the server, triggering the parameter
+    /// hints afterwards if asked to.
+    pub(crate) fn completion_accepted(
+        label: &str,
+        trigger_parameter_hints: bool,
+    ) -> lsp_types::Command {
+        let params = lsp_ext::CompletionAcceptedParams { label: label.to_string() };
+        lsp_types::Command {
+            title: "completionAccepted".into(),
+            command: "rust-analyzer.completionAccepted".into(),
+            arguments: Some(vec![
+                to_value(params).unwrap(),
+                to_value(trigger_parameter_hints).unwrap(),
+            ]),
+        }
+    }
 }
 
 pub(crate) fn implementation_title(count: usize) -> String {
@@ -85,6 +85,10 @@ pub mod ext {
         block_expr(None, None)
     }
 
+    pub fn derive_attr<'a>(traits: impl IntoIterator<Item = &'a str>) -> ast::Attr {
+        ast_from_text(&format!("#[derive({})] struct S;", traits.into_iter().join(", ")))
+    }
+
     pub fn ty_bool() -> ast::Type {
         ty_path(ident_path("bool"))
     }
@@ -180,6 +184,11 @@ pub fn impl_trait(
     ast_from_text(&format!("impl{2} {} for {}{2} {{}}", trait_, ty, ty_params))
 }
 
+pub fn trait_(visibility: Option<ast::Visibility>, name: ast::Name) -> ast::Trait {
+    let visibility = visibility.map_or_else(String::new, |it| format!("{} ", it));
+    ast_from_text(&format!("{}trait {} {{}}", visibility, name))
+}
+
 pub(crate) fn generic_arg_list() -> ast::GenericArgList {
     ast_from_text("const S: T<> = ();")
 }
@@ -485,6 +494,10 @@ pub fn wildcard_pat() -> ast::WildcardPat {
     }
 }
 
+pub fn rest_pat() -> ast::RestPat {
+    ast_from_text("fn f(S(..): ())")
+}
+
 pub fn literal_pat(lit: &str) -> ast::LiteralPat {
     return from_text(lit);
 
@@ -733,6 +746,23 @@ pub fn variant(name: ast::Name, field_list: Option<ast::FieldList>) -> ast::Vari
     ast_from_text(&format!("enum f {{ {}{} }}", name, field_list))
 }
 
+pub fn variant_list(variants: impl IntoIterator<Item = ast::Variant>) -> ast::VariantList {
+    let variants = variants.into_iter().map(|it| format!("    {},\n", it)).join("");
+    ast_from_text(&format!("enum f {{\n{}}}", variants))
+}
+
+pub fn enum_(
+    visibility: Option<ast::Visibility>,
+    enum_name: ast::Name,
+    variant_list: ast::VariantList,
+) -> ast::Enum {
+    let visibility = match visibility {
+        None => String::new(),
+        Some(it) => format!("{} ", it),
+    };
+    ast_from_text(&format!("{}enum {} {}", visibility, enum_name, variant_list))
+}
+
 pub fn fn_(
     visibility: Option<ast::Visibility>,
     fn_name: ast::Name,
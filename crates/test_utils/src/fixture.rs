@@ -51,6 +51,7 @@
 //! - crate names via `crate:cratename`
 //! - dependencies via `deps:dep1,dep2`
 //! - configuration settings via `cfg:dbg=false,opt_level=2`
+//! - declared but not necessarily enabled cargo features via `features:std,alloc`
 //! - environment variables via `env:PATH=/bin,RUST_LOG=debug`
 //!
 //! Example using all available metadata:
@@ -73,7 +74,9 @@ pub struct Fixture {
     pub extern_prelude: Option<Vec<String>>,
     pub cfg_atoms: Vec<String>,
     pub cfg_key_values: Vec<(String, String)>,
+    pub features: Vec<String>,
     pub edition: Option<String>,
+    pub rust_version: Option<String>,
     pub env: FxHashMap<String, String>,
     pub introduce_new_source_root: Option<String>,
 }
@@ -175,8 +178,10 @@ impl Fixture {
         let mut deps = Vec::new();
         let mut extern_prelude = None;
         let mut edition = None;
+        let mut rust_version = None;
         let mut cfg_atoms = Vec::new();
         let mut cfg_key_values = Vec::new();
+        let mut features = Vec::new();
         let mut env = FxHashMap::default();
         let mut introduce_new_source_root = None;
         for component in components[1..].iter() {
@@ -195,6 +200,7 @@ impl Fixture {
                     }
                 }
                 "edition" => edition = Some(value.to_string()),
+                "rust-version" => rust_version = Some(value.to_string()),
                 "cfg" => {
                     for entry in value.split(',') {
                         match entry.split_once('=') {
@@ -203,6 +209,7 @@ impl Fixture {
                         }
                     }
                 }
+                "features" => features = value.split(',').map(|it| it.to_string()).collect(),
                 "env" => {
                     for key in value.split(',') {
                         if let Some((k, v)) = key.split_once('=') {
@@ -232,7 +239,9 @@ impl Fixture {
             extern_prelude,
             cfg_atoms,
             cfg_key_values,
+            features,
             edition,
+            rust_version,
             env,
             introduce_new_source_root,
         }
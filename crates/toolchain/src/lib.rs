@@ -52,6 +52,14 @@ fn get_path_for_executable(executable_name: &'static str) -> PathBuf {
     executable_name.into()
 }
 
+/// Returns the cargo home directory, `$CARGO_HOME` or `~/.cargo`.
+pub fn cargo_home() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("CARGO_HOME") {
+        return Some(path.into());
+    }
+    home::home_dir().map(|home| home.join(".cargo"))
+}
+
 fn lookup_in_path(exec: &str) -> bool {
     let paths = env::var_os("PATH").unwrap_or_default();
     env::split_paths(&paths).map(|path| path.join(exec)).find_map(probe).is_some()
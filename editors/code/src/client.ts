@@ -5,6 +5,7 @@ import * as Is from 'vscode-languageclient/lib/common/utils/is';
 import { assert } from './util';
 import { WorkspaceEdit } from 'vscode';
 import { Workspace } from './ctx';
+import { applySnippetTextEdits } from './snippets';
 
 export interface Env {
     [name: string]: string;
@@ -50,7 +51,10 @@ export function createClient(serverPath: string, workspace: Workspace, extraEnv:
     }
 
     const clientOptions: lc.LanguageClientOptions = {
-        documentSelector: [{ scheme: 'file', language: 'rust' }],
+        documentSelector: [
+            { scheme: 'file', language: 'rust' },
+            { scheme: 'file', pattern: '**/Cargo.toml' },
+        ],
         initializationOptions,
         diagnosticCollectionName: "rustc",
         traceOutputChannel,
@@ -84,6 +88,27 @@ export function createClient(serverPath: string, workspace: Workspace, extraEnv:
                     }
                 );
             },
+            // Using custom handling of on type formatting to support snippet edits.
+            async provideOnTypeFormattingEdits(document: vscode.TextDocument, position: vscode.Position, ch: string, options: vscode.FormattingOptions, token: vscode.CancellationToken, _next: lc.ProvideOnTypeFormattingEditsSignature) {
+                const params: lc.DocumentOnTypeFormattingParams = {
+                    textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(document),
+                    position: client.code2ProtocolConverter.asPosition(position),
+                    ch,
+                    options: { tabSize: options.tabSize, insertSpaces: options.insertSpaces },
+                };
+                return client.sendRequest(lc.DocumentOnTypeFormattingRequest.type, params, token).then(async (values) => {
+                    if (!values) return undefined;
+                    const edits = client.protocol2CodeConverter.asTextEdits(values);
+                    const isSnippet = values.some((edit) => (edit as any).insertTextFormat === lc.InsertTextFormat.Snippet);
+                    const editor = vscode.window.activeTextEditor;
+                    if (!isSnippet || editor?.document !== document) return edits;
+                    // VS Code can't apply snippets as formatting edits, so apply them ourselves.
+                    await applySnippetTextEdits(editor, edits);
+                    return [];
+                },
+                    (_error) => undefined
+                );
+            },
             // Using custom handling of CodeActions to support action groups and snippet edits.
             // Note that this means we have to re-implement lazy edit resolving ourselves as well.
             async provideCodeActions(document: vscode.TextDocument, range: vscode.Range, context: vscode.CodeActionContext, token: vscode.CancellationToken, _next: lc.ProvideCodeActionsSignature) {
@@ -187,6 +212,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
                 "rust-analyzer.debugSingle",
                 "rust-analyzer.showReferences",
                 "rust-analyzer.gotoLocation",
+                "rust-analyzer.completionAccepted",
                 "editor.action.triggerParameterHints",
             ]
         };
@@ -650,6 +650,15 @@ export function gotoLocation(ctx: Ctx): Cmd {
     };
 }
 
+export function completionAccepted(ctx: Ctx): Cmd {
+    return async (params: ra.CompletionAcceptedParams, triggerParameterHints: boolean) => {
+        await ctx.client?.sendNotification(ra.completionAccepted, params);
+        if (triggerParameterHints) {
+            await vscode.commands.executeCommand("editor.action.triggerParameterHints");
+        }
+    };
+}
+
 export function openDocs(ctx: Ctx): Cmd {
     return async () => {
 
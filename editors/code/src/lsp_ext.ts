@@ -19,6 +19,11 @@ export interface ServerStatusParams {
 }
 export const serverStatus = new lc.NotificationType<ServerStatusParams>("experimental/serverStatus");
 
+export interface CompletionAcceptedParams {
+    label: string;
+}
+export const completionAccepted = new lc.NotificationType<CompletionAcceptedParams>("rust-analyzer/completionAccepted");
+
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
 
 export const hover = new lc.RequestType<HoverParams, lc.Hover | null, void>("textDocument/hover");